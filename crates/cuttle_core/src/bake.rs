@@ -0,0 +1,257 @@
+//! Rendering shapes into images with the GPU pipeline, see [`RenderToImage`].
+
+use crate::bounding::{ComputeGlobalBounding, GlobalBoundingBox, check_visibility};
+use crate::internal_prelude::*;
+use bevy_asset::Handle;
+use bevy_camera::visibility::{NoCpuCulling, RenderLayers};
+use bevy_camera::{
+    Camera, Camera2d, CameraUpdateSystems, ClearColorConfig, OrthographicProjection, Projection,
+    ScalingMode,
+};
+use bevy_color::Color;
+use bevy_ecs::lifecycle::HookContext;
+use bevy_ecs::world::DeferredWorld;
+use bevy_image::{BevyDefault, Image};
+use bevy_log::warn_once;
+use bevy_math::{Rect, UVec2, Vec2};
+use bevy_platform::collections::HashMap;
+use bevy_render::RenderApp;
+use bevy_render::extract_component::{ExtractComponent, ExtractComponentPlugin};
+use bevy_render::render_resource::{TextureFormat, TextureUsages};
+use bevy_render::view::Msaa;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+pub(crate) fn plugin(app: &mut App) {
+    let rendered = RenderedImages::default();
+    app.insert_resource(rendered.clone())
+        .add_plugins(ExtractComponentPlugin::<ImageTarget>::default())
+        .add_systems(
+            PostUpdate,
+            (
+                start_rendering_to_image.before(CameraUpdateSystems),
+                place_image_cameras
+                    .in_set(PlaceImageCameras)
+                    .after(ComputeGlobalBounding)
+                    .before(check_visibility),
+            ),
+        );
+    app.sub_app_mut(RenderApp).insert_resource(rendered);
+}
+
+#[derive(Debug, SystemSet, Hash, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct PlaceImageCameras;
+
+/// Render layers of the cameras of [`RenderToImage`], one per shape rendered at the same time.
+pub const RENDER_TO_IMAGE_LAYERS: Range<usize> = 32..64;
+
+/// Renders the shape of this entity into an image every frame while present,
+/// with a camera that only sees this shape.
+///
+/// The shape is added to one of the [`RENDER_TO_IMAGE_LAYERS`], on top of its own
+/// [`RenderLayers`], which are restored when this is removed.
+/// Colors in the image have straight alpha, pixels outside the shape are transparent.
+/// The image only exists on the GPU, use bevy's `Readback` to get its data on the CPU.
+#[derive(Component, Debug, Clone)]
+#[component(on_remove = stop_rendering_to_image)]
+pub struct RenderToImage {
+    /// Created with [`RenderToImage::target_image`]
+    pub image: Handle<Image>,
+    /// Part of the world drawn, the shape's bounds if `None`.
+    /// Grows to the aspect ratio of the image.
+    pub area: Option<Rect>,
+}
+
+impl RenderToImage {
+    pub fn new(image: Handle<Image>) -> Self {
+        Self { image, area: None }
+    }
+
+    /// An image of `size` pixels the pipeline can render into.
+    pub fn target_image(size: UVec2) -> Image {
+        let mut image = Image::new_target_texture(size.x, size.y, TextureFormat::bevy_default());
+        image.texture_descriptor.usage |= TextureUsages::COPY_SRC;
+        image
+    }
+}
+
+/// The part of the world drawn into an image of `size` pixels: `area`, or the shape's
+/// `bounds` if `None`, grown around its center to the aspect ratio of the image.
+pub fn image_area(bounds: &GlobalBoundingBox, area: Option<Rect>, size: UVec2) -> Rect {
+    let area = area.unwrap_or_else(|| bounds.world_rect());
+    let aspect = size.x.max(1) as f32 / size.y.max(1) as f32;
+    let half_size = area.half_size().max(Vec2::splat(f32::EPSILON));
+    let half_size = half_size.max(Vec2::new(half_size.y * aspect, half_size.x / aspect));
+    Rect::from_center_half_size(area.center(), half_size)
+}
+
+/// The camera rendering a shape into its image.
+#[derive(Component)]
+pub(crate) struct ImageCamera {
+    pub(crate) camera: Entity,
+    pub(crate) layer: usize,
+    pub(crate) previous_layers: Option<RenderLayers>,
+}
+
+/// Marks the cameras of [`RenderToImage`], which draw with straight alpha.
+/// Renders are reported to [`RenderedImages`] with the generation of the camera.
+#[derive(Component, Clone, Copy, Default, ExtractComponent)]
+pub struct ImageTarget {
+    pub(crate) generation: u32,
+}
+
+/// The latest generation each image camera rendered with all of its pipelines ready,
+/// written by the render world.
+#[derive(Resource, Clone, Default)]
+pub struct RenderedImages(Arc<Mutex<HashMap<Entity, u32>>>);
+
+impl RenderedImages {
+    pub(crate) fn get(&self, camera: Entity) -> Option<u32> {
+        self.0.lock().ok()?.get(&camera).copied()
+    }
+
+    pub(crate) fn insert(&self, camera: Entity, generation: u32) {
+        if let Ok(mut rendered) = self.0.lock() {
+            rendered.insert(camera, generation);
+        }
+    }
+}
+
+fn start_rendering_to_image(
+    mut cmds: Commands,
+    started: Query<(Entity, &RenderToImage, Option<&RenderLayers>), Without<ImageCamera>>,
+    mut changed: Query<(&RenderToImage, &ImageCamera), Changed<RenderToImage>>,
+    mut cameras: Query<&mut Camera>,
+    in_use: Query<&ImageCamera>,
+) {
+    for (render, image_camera) in &mut changed {
+        if let Ok(mut camera) = cameras.get_mut(image_camera.camera) {
+            camera.target = render.image.clone().into();
+        }
+    }
+
+    let mut free =
+        RENDER_TO_IMAGE_LAYERS.filter(|&layer| in_use.iter().all(|camera| camera.layer != layer));
+    for (entity, render, layers) in &started {
+        let Some(layer) = free.next() else {
+            warn_once!(
+                "More than {} shapes are rendered to images at once, the rest waits",
+                RENDER_TO_IMAGE_LAYERS.len()
+            );
+            break;
+        };
+        let camera = cmds
+            .spawn((
+                Camera2d,
+                Camera {
+                    target: render.image.clone().into(),
+                    clear_color: ClearColorConfig::Custom(Color::NONE),
+                    ..Default::default()
+                },
+                Msaa::Off,
+                ImageTarget::default(),
+                RenderLayers::layer(layer),
+                NoCpuCulling,
+            ))
+            .id();
+        let previous_layers = layers.cloned();
+        let shape_layers = previous_layers.clone().unwrap_or_default().with(layer);
+        cmds.entity(entity).insert((
+            shape_layers,
+            ImageCamera {
+                camera,
+                layer,
+                previous_layers,
+            },
+        ));
+    }
+}
+
+/// Frames the area of each shape. The bounds are only known after bevy computed the
+/// projection of the cameras, so the projection matrix is updated here as well.
+fn place_image_cameras(
+    shapes: Query<(&RenderToImage, &ImageCamera, &GlobalBoundingBox)>,
+    mut cameras: Query<(
+        &mut Camera,
+        &mut Projection,
+        &mut Transform,
+        &mut GlobalTransform,
+    )>,
+) {
+    for (render, image_camera, bounds) in &shapes {
+        let Ok((mut camera, mut projection, mut transform, mut global)) =
+            cameras.get_mut(image_camera.camera)
+        else {
+            continue;
+        };
+        let Some(size) = camera.physical_target_size() else {
+            continue;
+        };
+        let area = image_area(bounds, render.area, size);
+
+        *transform = Transform::from_translation(area.center().extend(0.));
+        *global = GlobalTransform::from(*transform);
+        let framed = matches!(
+            projection.as_ref(),
+            Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::Fixed { width, height },
+                ..
+            }) if *width == area.width() && *height == area.height()
+        );
+        if !framed {
+            *projection = Projection::Orthographic(OrthographicProjection {
+                scaling_mode: ScalingMode::Fixed {
+                    width: area.width(),
+                    height: area.height(),
+                },
+                ..OrthographicProjection::default_2d()
+            });
+            projection.update(size.x as f32, size.y as f32);
+            camera.computed.clip_from_view = projection.get_clip_from_view();
+        }
+    }
+}
+
+fn stop_rendering_to_image(mut world: DeferredWorld, ctx: HookContext) {
+    let Some(ImageCamera {
+        camera,
+        previous_layers,
+        ..
+    }) = world.get::<ImageCamera>(ctx.entity)
+    else {
+        return;
+    };
+    let (camera, previous_layers) = (*camera, previous_layers.clone());
+
+    let mut cmds = world.commands();
+    cmds.entity(camera).try_despawn();
+    let mut entity = cmds.entity(ctx.entity);
+    entity.try_remove::<(ImageCamera, RenderLayers)>();
+    if let Some(layers) = previous_layers {
+        entity.try_insert(layers);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::image_area;
+    use crate::bounding::GlobalBoundingBox;
+    use bevy_math::{Isometry2d, Rect, UVec2, Vec2};
+
+    #[test]
+    fn area_grows_to_the_aspect_of_the_image() {
+        let bounds = GlobalBoundingBox {
+            isometry: Isometry2d::from_translation(Vec2::new(10., 0.)),
+            half_size: Vec2::new(4., 2.),
+        };
+
+        let area = image_area(&bounds, None, UVec2::new(100, 100));
+        assert_eq!(area, Rect::new(6., -4., 14., 4.));
+
+        let area = image_area(&bounds, None, UVec2::new(400, 100));
+        assert_eq!(area, Rect::new(2., -2., 18., 2.));
+
+        let given = Rect::new(0., 0., 2., 4.);
+        assert_eq!(image_area(&bounds, Some(given), UVec2::new(50, 100)), given);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::bounding::{GlobalBoundingBox, GlobalBoundingCircle};
+use crate::cpu::{CuttleEvaluator, CuttleSample};
+use crate::indices::CuttleIndices;
+use crate::internal_prelude::*;
+use bevy_math::bounding::{Aabb2d, BoundingVolume};
+use bevy_math::{Isometry2d, Vec2};
+
+/// Estimates the bounds of a shape from the CPU implementations of its components,
+/// see [`CuttleCpu`](crate::cpu::CuttleCpu), instead of relying on hand-written bounds alone.
+///
+/// The bounds computed from `affect_bounds` remain the default: they are used as the region the
+/// search starts in, and are kept whenever a component of the shape has no CPU implementation.
+///
+/// The estimate samples the distance on a grid each frame, so prefer hand-written bounds
+/// for shapes that exist in large numbers.
+#[derive(Debug, Clone, Copy, Component, Reflect)]
+#[reflect(Component)]
+pub struct AutoBounds {
+    /// Samples per axis.
+    pub resolution: u32,
+    /// Added to the estimated bounds on each side, useful for distance functions
+    /// that overestimate the distance to the shape.
+    pub margin: f32,
+}
+
+impl Default for AutoBounds {
+    fn default() -> Self {
+        Self {
+            resolution: 32,
+            margin: 0.,
+        }
+    }
+}
+
+/// How often the search region may double when the shape touches its border.
+const MAX_GROWTH: u32 = 6;
+/// Half size of the search region when no hand-written bounds are available.
+const MIN_HALF_SIZE: f32 = 16.;
+
+pub(crate) fn auto_bounds<S: CuttleSample>(
+    world: &mut World,
+    roots: &mut QueryState<(Entity, &AutoBounds, &GlobalBoundingBox), With<CuttleIndices>>,
+) {
+    let Some(evaluator) = CuttleEvaluator::<S>::new(world) else {
+        return;
+    };
+
+    let estimates: Vec<_> = roots
+        .iter(world)
+        .filter(|(entity, ..)| evaluator.supports(*entity))
+        .filter_map(|(entity, settings, bounds)| {
+            let distance = |point| evaluator.distance(entity, point).unwrap_or(f32::MAX);
+            let local = estimate_bounds(distance, bounds.isometry, bounds.half_size, settings)?;
+            let bounding_box = GlobalBoundingBox {
+                isometry: Isometry2d::new(
+                    bounds.isometry.transform_point(local.center()),
+                    bounds.isometry.rotation,
+                ),
+                half_size: local.half_size(),
+            };
+            Some((entity, bounding_box))
+        })
+        .collect();
+
+    for (entity, bounding_box) in estimates {
+        let mut entity = world.entity_mut(entity);
+        **entity.get_mut::<GlobalBoundingCircle>().unwrap() = bounding_box.bounding_circle();
+        *entity.get_mut::<GlobalBoundingBox>().unwrap() = bounding_box;
+    }
+}
+
+/// Finds the bounds of the area where `distance` is negative, in the space of `frame`.
+///
+/// Samples cell centers on a grid and keeps every cell whose center is closer to the surface
+/// than the cell's half diagonal, which is conservative for distance functions that never
+/// overestimate the distance. The search region doubles while the shape touches its border.
+pub fn estimate_bounds(
+    distance: impl Fn(Vec2) -> f32,
+    frame: Isometry2d,
+    initial_half_size: Vec2,
+    settings: &AutoBounds,
+) -> Option<Aabb2d> {
+    let resolution = settings.resolution.max(2);
+    let mut half_size = initial_half_size.max(Vec2::splat(MIN_HALF_SIZE));
+
+    for _ in 0..=MAX_GROWTH {
+        let cell = half_size * 2. / resolution as f32;
+        let reach = cell.length() * 0.5;
+        let mut result: Option<Aabb2d> = None;
+        let mut touches_border = false;
+
+        for y in 0..resolution {
+            for x in 0..resolution {
+                let local = -half_size + cell * (Vec2::new(x as f32, y as f32) + 0.5);
+                if distance(frame.transform_point(local)) > reach {
+                    continue;
+                }
+
+                let cell_bounds = Aabb2d::new(local, cell * 0.5);
+                result = Some(result.map_or(cell_bounds, |r| r.merge(&cell_bounds)));
+                touches_border |= x == 0 || y == 0 || x == resolution - 1 || y == resolution - 1;
+            }
+        }
+
+        if !touches_border {
+            return result.map(|r| r.grow(Vec2::splat(settings.margin.max(0.))));
+        }
+        half_size *= 2.;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AutoBounds, estimate_bounds};
+    use bevy_math::bounding::BoundingVolume;
+    use bevy_math::{Isometry2d, Vec2};
+
+    #[test]
+    fn estimate_contains_shape() {
+        let settings = AutoBounds::default();
+        let center = Vec2::new(30., -10.);
+        let circle = |p: Vec2| p.distance(center) - 50.;
+
+        let bounds = estimate_bounds(circle, Isometry2d::IDENTITY, Vec2::ZERO, &settings).unwrap();
+
+        assert!(bounds.min.cmple(center - 50.).all());
+        assert!(bounds.max.cmpge(center + 50.).all());
+        assert!(bounds.half_size().max_element() < 70.);
+    }
+
+    #[test]
+    fn estimate_in_rotated_frame() {
+        let settings = AutoBounds::default();
+        let frame = Isometry2d::new(Vec2::new(100., 0.), std::f32::consts::FRAC_PI_2.into());
+        let quad = |p: Vec2| {
+            let d = (p - Vec2::new(100., 0.)).abs() - Vec2::new(10., 80.);
+            d.max(Vec2::ZERO).length() + d.x.max(d.y).min(0.)
+        };
+
+        let bounds = estimate_bounds(quad, frame, Vec2::splat(5.), &settings).unwrap();
+
+        // The frame is rotated by 90 degrees, so the long side lies along its x axis
+        assert!(bounds.half_size().x >= 80.);
+        assert!(bounds.half_size().y >= 10.);
+        assert!(bounds.half_size().y < 30.);
+    }
+
+    #[test]
+    fn empty_shape_has_no_bounds() {
+        let settings = AutoBounds::default();
+        let nothing = |_: Vec2| 1000.;
+
+        assert!(estimate_bounds(nothing, Isometry2d::IDENTITY, Vec2::ZERO, &settings).is_none());
+    }
+}
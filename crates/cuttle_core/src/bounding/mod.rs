@@ -0,0 +1,340 @@
+use crate::extensions::ExtendedBy;
+use crate::internal_prelude::*;
+use bevy_camera::prelude::*;
+use bevy_camera::primitives::{Aabb, Frustum};
+use bevy_camera::visibility::{
+    NoCpuCulling, NoFrustumCulling, RenderLayers, VisibilitySystems, VisibleEntities,
+};
+use bevy_math::bounding::{Aabb2d, BoundingCircle, BoundingVolume};
+use bevy_math::{Affine2, Affine3A, Isometry2d, Mat2, Quat, Rect, Rot2, Vec2, Vec3A, Vec3Swizzles};
+use serde::Deserialize;
+use std::any::TypeId;
+
+pub mod auto;
+
+pub fn plugin(app: &mut App) {
+    app.register_type::<LocalBounds>()
+        .register_type::<GlobalBoundingBox>()
+        .register_type::<GlobalBoundingCircle>()
+        .register_type::<auto::AutoBounds>()
+        .configure_sets(
+            PostUpdate,
+            (
+                (
+                    Bounding::Union,
+                    Bounding::Add,
+                    Bounding::Multiply,
+                    Bounding::Offset,
+                )
+                    .chain()
+                    .in_set(ComputeBounding),
+                (
+                    ComputeBounding,
+                    ComputeGlobalBounding.before(check_visibility),
+                )
+                    .chain()
+                    .after(VisibilitySystems::CheckVisibility),
+                ComputeBounding.ambiguous_with(ComputeBounding),
+            ),
+        )
+        .add_systems(
+            PostUpdate,
+            (
+                compute_global_bounds.in_set(ComputeGlobalBounding),
+                check_visibility,
+            ),
+        );
+}
+
+#[derive(Debug, SystemSet, Ord, PartialOrd, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct ComputeGlobalBounding;
+
+#[derive(Debug, SystemSet, Ord, PartialOrd, Eq, PartialEq, Hash, Copy, Clone)]
+pub struct ComputeBounding;
+
+/// Bounds of an entity's shape in its local space.
+/// Accumulated each frame by the systems registered with `affect_bounds` and `combine_bounds`,
+/// see [`Bounding`] for the order they are applied in.
+#[derive(Clone, Copy, Debug, Component, Reflect, Deref, DerefMut)]
+#[reflect(Component)]
+pub struct LocalBounds(pub Aabb2d);
+
+impl Default for LocalBounds {
+    fn default() -> Self {
+        Self(Aabb2d {
+            min: Vec2::ZERO,
+            max: Vec2::ZERO,
+        })
+    }
+}
+
+/// A per axis value a component contributes to [`LocalBounds`].
+/// A single `f32` affects both axes equally.
+pub trait BoundingValue: Send + Sync + 'static {
+    fn to_extents(self) -> Vec2;
+}
+
+impl BoundingValue for f32 {
+    fn to_extents(self) -> Vec2 {
+        Vec2::splat(self)
+    }
+}
+
+impl BoundingValue for Vec2 {
+    fn to_extents(self) -> Vec2 {
+        self
+    }
+}
+
+/// Oriented box enclosing a root entity and all of its extensions in world space.
+/// Used to build the quad the shape is rasterized on.
+#[derive(Clone, Copy, Debug, Component, Reflect)]
+#[reflect(Component)]
+pub struct GlobalBoundingBox {
+    pub isometry: Isometry2d,
+    pub half_size: Vec2,
+}
+
+impl Default for GlobalBoundingBox {
+    fn default() -> Self {
+        Self {
+            isometry: Isometry2d::IDENTITY,
+            half_size: Vec2::ZERO,
+        }
+    }
+}
+
+impl GlobalBoundingBox {
+    pub fn bounding_circle(&self) -> BoundingCircle {
+        BoundingCircle::new(self.isometry.translation, self.half_size.length())
+    }
+
+    /// The axis aligned rectangle containing the box in world space.
+    pub fn world_rect(&self) -> Rect {
+        let rotation = Mat2::from(self.isometry.rotation);
+        let half_size = rotation.abs() * self.half_size;
+        Rect::from_center_half_size(self.isometry.translation, half_size)
+    }
+
+    fn world_from_local(&self) -> Affine3A {
+        Affine3A::from_rotation_translation(
+            Quat::from_rotation_z(self.isometry.rotation.as_radians()),
+            self.isometry.translation.extend(0.),
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, Component, Reflect, Deref, DerefMut)]
+#[reflect(Component)]
+pub struct GlobalBoundingCircle(BoundingCircle);
+
+impl Default for GlobalBoundingCircle {
+    fn default() -> Self {
+        Self(BoundingCircle::new(Vec2::ZERO, 0.0))
+    }
+}
+
+/// The stages [`LocalBounds`] are built in, each stage runs after the previous one.
+#[derive(SystemSet, Hash, PartialEq, Eq, Debug, Clone, Copy, Deserialize)]
+pub enum Bounding {
+    /// Merges the bounds with a box of the given half extents around the origin,
+    /// i.e. takes the per axis maximum. Used by base shapes.
+    Union,
+    /// Grows the bounds by the given amount on each side.
+    Add,
+    /// Scales the bounds around the origin.
+    Multiply,
+    /// Moves the bounds by the given translation.
+    Offset,
+}
+
+impl Bounding {
+    pub fn apply(self, bounds: &mut Aabb2d, value: Vec2) {
+        match self {
+            Bounding::Union => *bounds = bounds.merge(&Aabb2d::new(Vec2::ZERO, value.abs())),
+            Bounding::Add => {
+                bounds.min -= value;
+                bounds.max += value;
+            }
+            Bounding::Multiply => {
+                let (a, b) = (bounds.min * value, bounds.max * value);
+                *bounds = Aabb2d {
+                    min: a.min(b),
+                    max: a.max(b),
+                };
+            }
+            Bounding::Offset => {
+                bounds.min += value;
+                bounds.max += value;
+            }
+        }
+    }
+}
+
+#[inline]
+pub const fn make_compute_aabb_system<C: Component, V: BoundingValue>(
+    func: fn(&C) -> V,
+    set: Bounding,
+) -> impl Fn(Query<(&mut LocalBounds, &C)>) {
+    move |mut query| {
+        for (mut bounds, c) in &mut query {
+            set.apply(&mut bounds, func(c).to_extents());
+        }
+    }
+}
+
+#[inline]
+pub const fn make_combine_bounds_system<C: Component>(
+    func: fn(&C, &mut Aabb2d),
+) -> impl Fn(Query<(&mut LocalBounds, &C)>) {
+    move |mut query| {
+        for (mut bounds, c) in &mut query {
+            func(c, &mut bounds);
+        }
+    }
+}
+
+fn isometry_from_transform(transform: &GlobalTransform) -> Isometry2d {
+    let x_axis = transform.affine().matrix3.x_axis.xy().normalize_or(Vec2::X);
+    Isometry2d::new(
+        transform.translation().xy(),
+        Rot2::from_sin_cos(x_axis.y, x_axis.x),
+    )
+}
+
+/// Maps local bounds through the full transform of their entity, including scale and shear,
+/// into the frame of the root entity.
+fn bounds_in_frame(frame: Isometry2d, transform: &GlobalTransform, bounds: Aabb2d) -> Aabb2d {
+    let affine = transform.affine();
+    let world_from_local = Affine2::from_mat2_translation(
+        Mat2::from_cols(affine.matrix3.x_axis.xy(), affine.matrix3.y_axis.xy()),
+        affine.translation.xy(),
+    );
+    let frame_from_local = Affine2::from(frame.inverse()) * world_from_local;
+    let corners = [
+        bounds.min,
+        Vec2::new(bounds.min.x, bounds.max.y),
+        bounds.max,
+        Vec2::new(bounds.max.x, bounds.min.y),
+    ]
+    .map(|corner| frame_from_local.transform_point2(corner));
+    Aabb2d::from_point_cloud(Isometry2d::IDENTITY, &corners)
+}
+
+pub(crate) fn compute_global_bounds(
+    mut roots: Query<(
+        &GlobalTransform,
+        &mut LocalBounds,
+        &ExtendedBy,
+        &mut GlobalBoundingBox,
+        &mut GlobalBoundingCircle,
+    )>,
+    mut extension_bounds: Query<(&GlobalTransform, &mut LocalBounds), Without<GlobalBoundingBox>>,
+) {
+    for (transform, mut bounds, extensions, mut bounding_box, mut bounding_circle) in &mut roots {
+        let isometry = isometry_from_transform(transform);
+        let mut local = bounds_in_frame(isometry, transform, **bounds);
+        *bounds = default();
+
+        for extension_entity in extensions.iter() {
+            if let Ok((transform, mut bounds)) = extension_bounds.get_mut(extension_entity) {
+                local = local.merge(&bounds_in_frame(isometry, transform, **bounds));
+                *bounds = default();
+            }
+        }
+
+        *bounding_box = GlobalBoundingBox {
+            isometry: Isometry2d::new(isometry.transform_point(local.center()), isometry.rotation),
+            half_size: local.half_size(),
+        };
+        **bounding_circle = bounding_box.bounding_circle();
+    }
+}
+
+// TODO: Change Comments
+/// System updating the visibility of entities each frame.
+///
+/// The system is part of the [`VisibilitySystems::CheckVisibility`] set. Each
+/// frame, it updates the [`ViewVisibility`] of all entities, and for each view
+/// also computes the [`VisibleEntities`] for that view.
+///
+/// This system needs to be run for each type of renderable entity. If you add a
+/// new type of renderable entity, you'll need to add an instantiation of this
+/// system to the [`VisibilitySystems::CheckVisibility`] set so that Bevy will
+/// detect visibility properly for those entities.
+pub fn check_visibility(
+    mut thread_queues: Local<Parallel<Vec<Entity>>>,
+    mut view_query: Query<(
+        &mut VisibleEntities,
+        &Frustum,
+        Option<&RenderLayers>,
+        &Camera,
+        Has<NoCpuCulling>,
+    )>,
+    mut visible_aabb_query: Query<(
+        Entity,
+        &InheritedVisibility,
+        &mut ViewVisibility,
+        Option<&RenderLayers>,
+        &GlobalBoundingBox,
+        Has<NoFrustumCulling>,
+    )>,
+) {
+    for (mut visible_entities, frustum, maybe_view_mask, camera, no_cpu_culling) in &mut view_query
+    {
+        if !camera.is_active {
+            println!("Camera is not active");
+            continue;
+        }
+
+        let view_mask = maybe_view_mask.unwrap_or_default();
+
+        visible_aabb_query.par_iter_mut().for_each_init(
+            || thread_queues.borrow_local_mut(),
+            |queue, query_item| {
+                let (
+                    entity,
+                    inherited_visibility,
+                    mut view_visibility,
+                    maybe_entity_mask,
+                    bounding,
+                    no_frustum_culling,
+                ) = query_item;
+
+                // Skip computing visibility for entities that are configured to be hidden.
+                // ViewVisibility has already been reset in `reset_view_visibility`.
+                if !inherited_visibility.get() {
+                    return;
+                }
+
+                let entity_mask = maybe_entity_mask.unwrap_or_default();
+                if !view_mask.intersects(entity_mask) {
+                    return;
+                }
+
+                // frustum culling
+                if !no_frustum_culling && !no_cpu_culling {
+                    let model_aabb = Aabb {
+                        center: Vec3A::ZERO,
+                        half_extents: Vec3A::from(bounding.half_size.extend(0.)),
+                    };
+                    if !frustum.intersects_obb(
+                        &model_aabb,
+                        &bounding.world_from_local(),
+                        true,
+                        false,
+                    ) {
+                        return;
+                    }
+                }
+
+                view_visibility.set();
+                queue.push(entity);
+            },
+        );
+
+        let id = TypeId::of::<LocalBounds>();
+        visible_entities.clear(id);
+        thread_queues.drain_into(visible_entities.get_mut(id));
+    }
+}
@@ -0,0 +1,294 @@
+//! Drawing static shapes from an image of them, see [`CuttleCache`].
+
+use crate::bake::{
+    ImageCamera, ImageTarget, PlaceImageCameras, RenderToImage, RenderedImages, image_area,
+};
+use crate::bounding::{ComputeGlobalBounding, GlobalBoundingBox};
+use crate::extensions::Extends;
+use crate::indices::CuttleIndices;
+use crate::internal_prelude::*;
+use bevy_asset::{Assets, Handle};
+use bevy_camera::visibility::{RenderLayers, Visibility};
+use bevy_camera::{Camera, Projection};
+use bevy_ecs::lifecycle::HookContext;
+use bevy_ecs::world::DeferredWorld;
+use bevy_image::Image;
+use bevy_math::{Rect, UVec2};
+use bevy_sprite::Sprite;
+use bevy_transform::TransformSystems;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<CuttleCache>()
+        .configure_sets(
+            PostUpdate,
+            InvalidateCaches
+                .after(TransformSystems::Propagate)
+                .before(update_caches),
+        )
+        .add_systems(
+            PostUpdate,
+            (
+                (
+                    invalidate_caches::<GlobalTransform>,
+                    invalidate_changed_indices,
+                )
+                    .in_set(InvalidateCaches),
+                update_caches
+                    .after(ComputeGlobalBounding)
+                    .before(PlaceImageCameras),
+            ),
+        );
+}
+
+/// Largest side of the image of a cached shape in pixels.
+pub const MAX_CACHE_SIZE: u32 = 4096;
+
+/// Draws the shape from an image of it while nothing about it changes,
+/// instead of evaluating all of its components for every pixel each frame.
+///
+/// The shape is rendered at the scale of the most zoomed in camera, with a camera of
+/// [`RenderToImage`], and drawn as a sprite afterwards. It is rendered again when
+/// one of its components or transforms or those of its extensions changes, or when
+/// the camera scale changes by more than `scale_tolerance`.
+/// Until the new image is ready, the shape is drawn as usual.
+///
+/// Changes to config globals, like an elapsed time animating a shape, don't invalidate
+/// the cache, such shapes stand still while cached.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+#[component(on_remove = stop_caching)]
+pub struct CuttleCache {
+    /// Relative change of the camera scale after which the shape is rendered again
+    pub scale_tolerance: f32,
+}
+
+impl Default for CuttleCache {
+    fn default() -> Self {
+        Self {
+            scale_tolerance: 0.25,
+        }
+    }
+}
+
+/// Marks the caches of shapes whose components changed.
+#[derive(Debug, SystemSet, Hash, PartialEq, Eq, Clone, Copy)]
+pub(crate) struct InvalidateCaches;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CacheStage {
+    /// The image camera is set up for the current state of the shape
+    Requested,
+    /// Waiting for the image camera to render the current generation
+    Rendering,
+    /// The sprite is drawn instead of the shape
+    Cached,
+}
+
+#[derive(Component)]
+pub(crate) struct CacheState {
+    sprite: Entity,
+    image: Handle<Image>,
+    area: Rect,
+    size: UVec2,
+    /// Pixels per world unit the image was rendered at
+    scale: f32,
+    generation: u32,
+    stage: CacheStage,
+    changed: bool,
+}
+
+pub(crate) fn invalidate_caches<C: Component>(
+    changed: Query<(Entity, Option<&Extends>), Changed<C>>,
+    mut caches: Query<&mut CacheState>,
+) {
+    for (entity, extends) in &changed {
+        let root = extends.map_or(entity, |extends| extends.0);
+        if let Ok(mut cache) = caches.get_mut(root) {
+            cache.changed = true;
+        }
+    }
+}
+
+fn invalidate_changed_indices(mut caches: Query<&mut CacheState, Changed<CuttleIndices>>) {
+    for mut cache in &mut caches {
+        cache.changed = true;
+    }
+}
+
+/// The area and image size a shape is rendered at.
+fn cache_layout(bounds: &GlobalBoundingBox, scale: f32) -> (Rect, UVec2) {
+    let rect = bounds.world_rect().inflate(1. / scale);
+    let scale = scale.min(MAX_CACHE_SIZE as f32 / rect.size().max_element());
+    let size = (rect.size() * scale).ceil().as_uvec2();
+    let size = size.clamp(UVec2::ONE, UVec2::splat(MAX_CACHE_SIZE));
+    (image_area(bounds, Some(rect), size), size)
+}
+
+/// Pixels per world unit of the most zoomed in camera.
+fn camera_scale(cameras: &Query<(&Camera, &Projection), Without<ImageTarget>>) -> f32 {
+    cameras
+        .iter()
+        .filter(|(camera, _)| camera.is_active)
+        .filter_map(|(camera, projection)| match projection {
+            Projection::Orthographic(ortho) => {
+                Some(camera.physical_viewport_size()?.x as f32 / ortho.area.width())
+            }
+            _ => None,
+        })
+        .filter(|scale| scale.is_finite() && *scale > 0.)
+        .reduce(f32::max)
+        .unwrap_or(1.)
+}
+
+fn update_caches(
+    mut cmds: Commands,
+    mut shapes: Query<(
+        Entity,
+        &CuttleCache,
+        Option<&mut CacheState>,
+        Option<&ImageCamera>,
+        Option<&mut RenderToImage>,
+        &GlobalBoundingBox,
+        &GlobalTransform,
+    )>,
+    cameras: Query<(&Camera, &Projection), Without<ImageTarget>>,
+    mut image_cameras: Query<(&mut Camera, &mut ImageTarget)>,
+    mut sprites: Query<(&mut Sprite, &mut Transform, &mut Visibility)>,
+    mut images: ResMut<Assets<Image>>,
+    rendered: Res<RenderedImages>,
+) {
+    let scale = camera_scale(&cameras);
+
+    for (entity, cache, state, image_camera, render, bounds, transform) in &mut shapes {
+        if bounds.half_size.min_element() <= 0. {
+            continue;
+        }
+
+        let Some(mut state) = state else {
+            let (area, size) = cache_layout(bounds, scale);
+            let image = images.add(RenderToImage::target_image(size));
+            // A child, so hiding or despawning the shape does the same to its sprite
+            let sprite = cmds
+                .spawn((
+                    Sprite::from_image(image.clone()),
+                    Transform::default(),
+                    Visibility::Hidden,
+                    ChildOf(entity),
+                ))
+                .id();
+            cmds.entity(entity).insert((
+                RenderToImage {
+                    image: image.clone(),
+                    area: Some(area),
+                },
+                CacheState {
+                    sprite,
+                    image,
+                    area,
+                    size,
+                    scale,
+                    generation: 1,
+                    stage: CacheStage::Requested,
+                    changed: false,
+                },
+            ));
+            continue;
+        };
+        let (Some(image_camera), Some(mut render)) = (image_camera, render) else {
+            continue;
+        };
+        let Ok((mut camera, mut target)) = image_cameras.get_mut(image_camera.camera) else {
+            continue;
+        };
+        let shape_layers = image_camera.previous_layers.clone().unwrap_or_default();
+
+        let rescaled = (scale / state.scale - 1.).abs() > cache.scale_tolerance;
+        let (area, size) = cache_layout(bounds, if rescaled { scale } else { state.scale });
+        if state.changed || rescaled || state.area != area {
+            if size != state.size {
+                state.image = images.add(RenderToImage::target_image(size));
+                render.image = state.image.clone();
+            }
+            render.area = Some(area);
+            camera.is_active = true;
+            cmds.entity(entity)
+                .insert(shape_layers.with(image_camera.layer));
+            if let Ok((.., mut visibility)) = sprites.get_mut(state.sprite) {
+                *visibility = Visibility::Hidden;
+            }
+            state.area = area;
+            state.size = size;
+            state.scale = scale;
+            state.generation += 1;
+            state.stage = CacheStage::Requested;
+            state.changed = false;
+            continue;
+        }
+
+        match state.stage {
+            // The camera renders the new state from the next frame on, earlier renders
+            // are reported with the previous generation
+            CacheStage::Requested => {
+                camera.is_active = true;
+                target.generation = state.generation;
+                state.stage = CacheStage::Rendering;
+            }
+            CacheStage::Rendering
+                if rendered.get(image_camera.camera) == Some(state.generation) =>
+            {
+                camera.is_active = false;
+                cmds.entity(entity)
+                    .insert(RenderLayers::layer(image_camera.layer));
+                if let Ok((mut sprite, mut sprite_transform, mut visibility)) =
+                    sprites.get_mut(state.sprite)
+                {
+                    sprite.image = state.image.clone();
+                    sprite.custom_size = Some(state.area.size());
+                    *sprite_transform = GlobalTransform::from_translation(
+                        state.area.center().extend(transform.translation().z),
+                    )
+                    .reparented_to(transform);
+                    *visibility = Visibility::Inherited;
+                    cmds.entity(state.sprite).insert(shape_layers);
+                }
+                state.stage = CacheStage::Cached;
+            }
+            CacheStage::Rendering | CacheStage::Cached => {}
+        }
+    }
+}
+
+fn stop_caching(mut world: DeferredWorld, ctx: HookContext) {
+    let sprite = world
+        .get::<CacheState>(ctx.entity)
+        .map(|state| state.sprite);
+    let mut cmds = world.commands();
+    if let Some(sprite) = sprite {
+        cmds.entity(sprite).try_despawn();
+    }
+    cmds.entity(ctx.entity)
+        .try_remove::<(CacheState, RenderToImage)>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_CACHE_SIZE, cache_layout};
+    use crate::bounding::GlobalBoundingBox;
+    use bevy_math::{Isometry2d, UVec2, Vec2};
+
+    #[test]
+    fn layout_covers_the_bounds_at_the_camera_scale() {
+        let bounds = GlobalBoundingBox {
+            isometry: Isometry2d::IDENTITY,
+            half_size: Vec2::new(10., 5.),
+        };
+
+        let (area, size) = cache_layout(&bounds, 2.);
+        assert_eq!(size, UVec2::new(42, 22));
+        assert!(area.min.abs_diff_eq(Vec2::new(-10.5, -5.5), 1e-4));
+        assert!(area.max.abs_diff_eq(Vec2::new(10.5, 5.5), 1e-4));
+
+        let (_, size) = cache_layout(&bounds, 1000.);
+        assert_eq!(size.x, MAX_CACHE_SIZE);
+    }
+}
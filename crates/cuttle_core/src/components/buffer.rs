@@ -43,6 +43,10 @@ where
 {
     pub fn insert(&mut self, index: usize, comp: &Comp) {
         let value = (self.to_render_data)(comp);
+        self.set(index, value);
+    }
+
+    pub fn set(&mut self, index: usize, value: Render) {
         *self.storage.get_mut().get_mut(index).unwrap() = value;
     }
 
@@ -87,8 +91,9 @@ where
         let mut buffer_fns = ent.get_mut::<BufferFns>().unwrap();
 
         let count = buffer_fns.bindings.len();
-        buffer_fns.write.push(Self::write);
-        buffer_fns.bindings.push(Self::get_binding_res);
+        buffer_fns.write.push(Box::new(Self::write));
+        buffer_fns.bindings.push(Box::new(Self::get_binding_res));
+        buffer_fns.layout.push(STORAGE_BINDING);
         ent.insert(Self::new(to_render_data));
         count
     }
@@ -110,17 +115,26 @@ where
     }
 }
 
-type EntMut<'w, 's> = EntityMutExcept<'w, 's, BufferFns>;
-pub type WriteBufferFn = fn(&mut EntMut, &RenderDevice, &RenderQueue);
-type EntRef<'w, 's> = EntityRefExcept<'w, 's, (Bind, BufferFns, BindLayout)>;
-pub type GetBufferBindingResFn = for<'w, 's> fn(&'w EntRef<'w, 's>) -> BindingResource<'w>;
+pub(crate) type EntMut<'w, 's> = EntityMutExcept<'w, 's, BufferFns>;
+pub type WriteBufferFn = Box<dyn Fn(&mut EntMut, &RenderDevice, &RenderQueue) + Send + Sync>;
+pub(crate) type EntRef<'w, 's> = EntityRefExcept<'w, 's, (Bind, BufferFns, BindLayout)>;
+pub type GetBufferBindingResFn =
+    Box<dyn for<'w, 's> Fn(&'w EntRef<'w, 's>) -> BindingResource<'w> + Send + Sync>;
 
 #[derive(Component, Default)]
 pub struct BufferFns {
     pub write: Vec<WriteBufferFn>,
     pub bindings: Vec<GetBufferBindingResFn>,
+    /// The type of each binding, in the same order as `bindings`
+    pub layout: Vec<BindingType>,
 }
 
+pub const STORAGE_BINDING: BindingType = BindingType::Buffer {
+    ty: BufferBindingType::Storage { read_only: true },
+    has_dynamic_offset: false,
+    min_binding_size: None,
+};
+
 #[derive(Component)]
 #[require(Bind, BufferFns)]
 pub struct CompBufferEntity;
@@ -159,11 +173,7 @@ pub fn build_comp_layout(
     device: Res<RenderDevice>,
 ) -> BindGroupLayout {
     let (entity, fns) = *buffer_entity;
-    let layout = build_buffer_layout(
-        fns.bindings.len() as u32,
-        &device,
-        "cuttle components bind group layout",
-    );
+    let layout = build_buffer_layout(&fns.layout, &device, "cuttle components bind group layout");
     cmds.entity(entity).insert(BindLayout(layout.clone()));
     layout
 }
@@ -177,7 +187,7 @@ pub fn build_global_layouts(
 
     for (entity, id, fns) in &query {
         let name = format!("cuttle global bind group layouts for {id:?}");
-        let layout = build_buffer_layout(fns.bindings.len() as u32, &device, name.as_str());
+        let layout = build_buffer_layout(&fns.layout, &device, name.as_str());
         cmds.entity(entity).insert(BindLayout(layout.clone()));
         result.insert(*id, layout);
     }
@@ -185,7 +195,7 @@ pub fn build_global_layouts(
     result
 }
 
-fn build_buffer_bind_groups(
+pub(crate) fn build_buffer_bind_groups(
     mut query: Query<(EntRef, &BufferFns, &mut Bind, &BindLayout)>,
     device: Res<RenderDevice>,
 ) {
@@ -204,17 +214,18 @@ fn build_buffer_bind_groups(
     }
 }
 
-pub fn build_buffer_layout(count: u32, device: &RenderDevice, name: &str) -> BindGroupLayout {
-    let entries: Vec<BindGroupLayoutEntry> = (0..count)
-        .map(|binding| BindGroupLayoutEntry {
+pub fn build_buffer_layout(
+    layout: &[BindingType],
+    device: &RenderDevice,
+    name: &str,
+) -> BindGroupLayout {
+    let entries: Vec<BindGroupLayoutEntry> = (0..)
+        .zip(layout)
+        .map(|(binding, &ty)| BindGroupLayoutEntry {
             binding,
             visibility: ShaderStages::FRAGMENT,
             count: None,
-            ty: BindingType::Buffer {
-                ty: BufferBindingType::Storage { read_only: true },
-                has_dynamic_offset: false,
-                min_binding_size: None,
-            },
+            ty,
         })
         .collect();
     device.create_bind_group_layout(name, &entries)
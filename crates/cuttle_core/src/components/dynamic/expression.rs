@@ -0,0 +1,343 @@
+//! Bounds expressions of dynamic components, e.g. `radius + 2` or `vec2(size.x, size.y * 0.5)`.
+//!
+//! Supports numbers, field names with `.x`/`.y`/`.z`/`.w` swizzles, `+ - * /`, parentheses
+//! and the functions `vec2`, `min`, `max`, `abs` and `length`. Scalars are used for both axes.
+
+use super::layout::{DynamicLayout, DynamicValue, WgslFieldType};
+use bevy_math::Vec2;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoundsExpression {
+    Number(f32),
+    /// Index of the field in the layout and an optional swizzle
+    Field(usize, Option<usize>),
+    Negate(Box<BoundsExpression>),
+    Binary(Operator, Box<BoundsExpression>, Box<BoundsExpression>),
+    Call(Function, Vec<BoundsExpression>),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Function {
+    Vec2,
+    Min,
+    Max,
+    Abs,
+    Length,
+}
+
+impl Function {
+    fn parse(name: &str) -> Option<(Self, usize)> {
+        Some(match name {
+            "vec2" => (Self::Vec2, 2),
+            "min" => (Self::Min, 2),
+            "max" => (Self::Max, 2),
+            "abs" => (Self::Abs, 1),
+            "length" => (Self::Length, 1),
+            _ => return None,
+        })
+    }
+}
+
+/// Result of evaluating a [`BoundsExpression`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BoundsValue {
+    Scalar(f32),
+    Vector(Vec2),
+}
+
+impl BoundsValue {
+    pub fn extents(self) -> Vec2 {
+        match self {
+            BoundsValue::Scalar(s) => Vec2::splat(s),
+            BoundsValue::Vector(v) => v,
+        }
+    }
+
+    fn map(self, other: Self, func: impl Fn(f32, f32) -> f32) -> Self {
+        match (self, other) {
+            (BoundsValue::Scalar(a), BoundsValue::Scalar(b)) => BoundsValue::Scalar(func(a, b)),
+            (a, b) => {
+                let (a, b) = (a.extents(), b.extents());
+                BoundsValue::Vector(Vec2::new(func(a.x, b.x), func(a.y, b.y)))
+            }
+        }
+    }
+
+    fn scalar(self) -> f32 {
+        match self {
+            BoundsValue::Scalar(s) => s,
+            BoundsValue::Vector(v) => v.x,
+        }
+    }
+}
+
+impl BoundsExpression {
+    /// Parses `source`, resolving field names against `layout`.
+    pub fn parse(source: &str, layout: &DynamicLayout) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens: &tokens,
+            position: 0,
+            layout,
+        };
+        let expression = parser.expression()?;
+        match parser.tokens.get(parser.position) {
+            None => Ok(expression),
+            Some(token) => Err(format!("unexpected `{token:?}` in `{source}`")),
+        }
+    }
+
+    /// Evaluates the expression, reading fields through `field`.
+    /// Fields without a value evaluate to zero.
+    pub fn eval(&self, field: &impl Fn(usize) -> Option<DynamicValue>) -> BoundsValue {
+        match self {
+            BoundsExpression::Number(n) => BoundsValue::Scalar(*n),
+            BoundsExpression::Field(index, swizzle) => {
+                let value = field(*index);
+                let components = value.map(|v| v.words()).unwrap_or_default();
+                let component = |i: usize| match value {
+                    Some(DynamicValue::U32(v)) => v as f32,
+                    Some(DynamicValue::I32(v)) => v as f32,
+                    _ => components.get(i).map_or(0., |&bits| f32::from_bits(bits)),
+                };
+                match (swizzle, value.map(|v| v.wgsl_type())) {
+                    (Some(i), _) => BoundsValue::Scalar(component(*i)),
+                    (
+                        None,
+                        Some(WgslFieldType::Vec2 | WgslFieldType::Vec3 | WgslFieldType::Vec4),
+                    ) => BoundsValue::Vector(Vec2::new(component(0), component(1))),
+                    (None, _) => BoundsValue::Scalar(component(0)),
+                }
+            }
+            BoundsExpression::Negate(inner) => {
+                inner.eval(field).map(BoundsValue::Scalar(0.), |a, _| -a)
+            }
+            BoundsExpression::Binary(operator, a, b) => {
+                let (a, b) = (a.eval(field), b.eval(field));
+                a.map(b, |a, b| match operator {
+                    Operator::Add => a + b,
+                    Operator::Subtract => a - b,
+                    Operator::Multiply => a * b,
+                    Operator::Divide => a / b,
+                })
+            }
+            BoundsExpression::Call(function, arguments) => {
+                let arguments: Vec<_> = arguments.iter().map(|a| a.eval(field)).collect();
+                match function {
+                    Function::Vec2 => {
+                        BoundsValue::Vector(Vec2::new(arguments[0].scalar(), arguments[1].scalar()))
+                    }
+                    Function::Min => arguments[0].map(arguments[1], f32::min),
+                    Function::Max => arguments[0].map(arguments[1], f32::max),
+                    Function::Abs => arguments[0].map(arguments[0], |a, _| a.abs()),
+                    Function::Length => BoundsValue::Scalar(match arguments[0] {
+                        BoundsValue::Scalar(s) => s.abs(),
+                        BoundsValue::Vector(v) => v.length(),
+                    }),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f32),
+    Identifier(String),
+    Symbol(char),
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit()
+            || c == '.'
+                && tokens
+                    .last()
+                    .is_none_or(|t| !matches!(t, Token::Identifier(_)))
+        {
+            let mut end = start;
+            while let Some(&(i, c)) = chars
+                .peek()
+                .filter(|(_, c)| c.is_ascii_digit() || *c == '.')
+            {
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            let number = &source[start..end];
+            let number = number
+                .parse()
+                .map_err(|_| format!("invalid number `{number}`"))?;
+            tokens.push(Token::Number(number));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = start;
+            while let Some(&(i, c)) = chars
+                .peek()
+                .filter(|(_, c)| c.is_alphanumeric() || *c == '_')
+            {
+                end = i + c.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Identifier(source[start..end].to_string()));
+        } else if "+-*/(),.".contains(c) {
+            tokens.push(Token::Symbol(c));
+            chars.next();
+        } else {
+            return Err(format!("unexpected `{c}` in `{source}`"));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    layout: &'a DynamicLayout,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: char) -> bool {
+        let matches = self.tokens.get(self.position) == Some(&Token::Symbol(symbol));
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), String> {
+        match self.eat(symbol) {
+            true => Ok(()),
+            false => Err(format!("expected `{symbol}`")),
+        }
+    }
+
+    fn expression(&mut self) -> Result<BoundsExpression, String> {
+        let mut result = self.term()?;
+        loop {
+            let operator = match () {
+                _ if self.eat('+') => Operator::Add,
+                _ if self.eat('-') => Operator::Subtract,
+                _ => return Ok(result),
+            };
+            result = BoundsExpression::Binary(operator, Box::new(result), Box::new(self.term()?));
+        }
+    }
+
+    fn term(&mut self) -> Result<BoundsExpression, String> {
+        let mut result = self.unary()?;
+        loop {
+            let operator = match () {
+                _ if self.eat('*') => Operator::Multiply,
+                _ if self.eat('/') => Operator::Divide,
+                _ => return Ok(result),
+            };
+            result = BoundsExpression::Binary(operator, Box::new(result), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> Result<BoundsExpression, String> {
+        match self.eat('-') {
+            true => Ok(BoundsExpression::Negate(Box::new(self.unary()?))),
+            false => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<BoundsExpression, String> {
+        match self.next().cloned() {
+            Some(Token::Number(n)) => Ok(BoundsExpression::Number(n)),
+            Some(Token::Symbol('(')) => {
+                let inner = self.expression()?;
+                self.expect(')')?;
+                Ok(inner)
+            }
+            Some(Token::Identifier(name)) if self.eat('(') => {
+                let (function, arity) =
+                    Function::parse(&name).ok_or_else(|| format!("unknown function `{name}`"))?;
+                let mut arguments = vec![self.expression()?];
+                while self.eat(',') {
+                    arguments.push(self.expression()?);
+                }
+                self.expect(')')?;
+                if arguments.len() != arity {
+                    return Err(format!("`{name}` takes {arity} arguments"));
+                }
+                Ok(BoundsExpression::Call(function, arguments))
+            }
+            Some(Token::Identifier(name)) => {
+                let (index, field) = self
+                    .layout
+                    .field(&name)
+                    .ok_or_else(|| format!("unknown field `{name}`"))?;
+                if matches!(field.wgsl_type, WgslFieldType::Mat2 | WgslFieldType::Mat4) {
+                    return Err(format!("matrix field `{name}` can not be used in bounds"));
+                }
+                let swizzle = match self.eat('.') {
+                    false => None,
+                    true => match self.next() {
+                        Some(Token::Identifier(s)) if s.len() == 1 => {
+                            Some("xyzw".find(s.as_str()).ok_or("invalid swizzle")?)
+                        }
+                        _ => return Err("invalid swizzle".to_string()),
+                    },
+                };
+                Ok(BoundsExpression::Field(index, swizzle))
+            }
+            token => Err(format!("unexpected {token:?}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundsExpression, BoundsValue};
+    use crate::components::dynamic::layout::{DynamicLayout, DynamicValue};
+    use bevy_math::Vec2;
+
+    #[test]
+    fn parse_and_eval() {
+        let layout = DynamicLayout::new(&[
+            ("radius".to_string(), "f32".to_string()),
+            ("size".to_string(), "vec2f".to_string()),
+        ])
+        .unwrap();
+        let values = |index: usize| match index {
+            0 => Some(DynamicValue::F32(4.)),
+            _ => Some(DynamicValue::Vec2(Vec2::new(2., 6.))),
+        };
+        let eval = |source: &str| {
+            BoundsExpression::parse(source, &layout)
+                .unwrap()
+                .eval(&values)
+        };
+
+        assert_eq!(eval("radius * 2 + 1"), BoundsValue::Scalar(9.));
+        assert_eq!(eval("-(radius - 1.5)"), BoundsValue::Scalar(-2.5));
+        assert_eq!(
+            eval("size + radius"),
+            BoundsValue::Vector(Vec2::new(6., 10.))
+        );
+        assert_eq!(
+            eval("vec2(size.y, max(radius, 5))"),
+            BoundsValue::Vector(Vec2::new(6., 5.))
+        );
+        assert!(BoundsExpression::parse("unknown", &layout).is_err());
+        assert!(BoundsExpression::parse("radius +", &layout).is_err());
+    }
+}
@@ -0,0 +1,246 @@
+use bevy_math::{Mat2, Mat4, Vec2, Vec3, Vec4};
+use bevy_reflect::Reflect;
+
+/// The value of a field of a [`DynamicCuttleComponent`](super::DynamicCuttleComponent).
+#[derive(Debug, Copy, Clone, PartialEq, Reflect)]
+pub enum DynamicValue {
+    F32(f32),
+    U32(u32),
+    I32(i32),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Vec4(Vec4),
+    Mat2(Mat2),
+    Mat4(Mat4),
+}
+
+impl DynamicValue {
+    pub fn wgsl_type(&self) -> WgslFieldType {
+        match self {
+            DynamicValue::F32(_) => WgslFieldType::F32,
+            DynamicValue::U32(_) => WgslFieldType::U32,
+            DynamicValue::I32(_) => WgslFieldType::I32,
+            DynamicValue::Vec2(_) => WgslFieldType::Vec2,
+            DynamicValue::Vec3(_) => WgslFieldType::Vec3,
+            DynamicValue::Vec4(_) => WgslFieldType::Vec4,
+            DynamicValue::Mat2(_) => WgslFieldType::Mat2,
+            DynamicValue::Mat4(_) => WgslFieldType::Mat4,
+        }
+    }
+
+    /// The value as 32 bit words, in the order the shader reads them.
+    pub fn words(&self) -> Vec<u32> {
+        let floats = |floats: &[f32]| floats.iter().map(|f| f.to_bits()).collect();
+        match *self {
+            DynamicValue::F32(v) => vec![v.to_bits()],
+            DynamicValue::U32(v) => vec![v],
+            DynamicValue::I32(v) => vec![v as u32],
+            DynamicValue::Vec2(v) => floats(&v.to_array()),
+            DynamicValue::Vec3(v) => floats(&v.to_array()),
+            DynamicValue::Vec4(v) => floats(&v.to_array()),
+            DynamicValue::Mat2(v) => floats(&v.to_cols_array()),
+            DynamicValue::Mat4(v) => floats(&v.to_cols_array()),
+        }
+    }
+}
+
+macro_rules! impl_from_for_dynamic_value {
+    ($($variant:ident($ty:ty)),*) => {
+        $(
+        impl From<$ty> for DynamicValue {
+            fn from(value: $ty) -> Self {
+                DynamicValue::$variant(value)
+            }
+        }
+        )*
+    };
+}
+
+impl_from_for_dynamic_value!(
+    F32(f32),
+    U32(u32),
+    I32(i32),
+    Vec2(Vec2),
+    Vec3(Vec3),
+    Vec4(Vec4),
+    Mat2(Mat2),
+    Mat4(Mat4)
+);
+
+/// The WGSL types fields of dynamic components can have.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WgslFieldType {
+    F32,
+    U32,
+    I32,
+    Vec2,
+    Vec3,
+    Vec4,
+    Mat2,
+    Mat4,
+}
+
+impl WgslFieldType {
+    pub fn parse(name: &str) -> Option<Self> {
+        Some(match name.trim() {
+            "f32" => Self::F32,
+            "u32" => Self::U32,
+            "i32" => Self::I32,
+            "vec2<f32>" | "vec2f" => Self::Vec2,
+            "vec3<f32>" | "vec3f" => Self::Vec3,
+            "vec4<f32>" | "vec4f" => Self::Vec4,
+            "mat2x2<f32>" | "mat2x2f" => Self::Mat2,
+            "mat4x4<f32>" | "mat4x4f" => Self::Mat4,
+            _ => return None,
+        })
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::F32 => "f32",
+            Self::U32 => "u32",
+            Self::I32 => "i32",
+            Self::Vec2 => "vec2<f32>",
+            Self::Vec3 => "vec3<f32>",
+            Self::Vec4 => "vec4<f32>",
+            Self::Mat2 => "mat2x2<f32>",
+            Self::Mat4 => "mat4x4<f32>",
+        }
+    }
+
+    /// Size and alignment in 32 bit words, following the WGSL storage layout rules.
+    fn size_align(self) -> (usize, usize) {
+        match self {
+            Self::F32 | Self::U32 | Self::I32 => (1, 1),
+            Self::Vec2 => (2, 2),
+            Self::Vec3 => (3, 4),
+            Self::Vec4 => (4, 4),
+            Self::Mat2 => (4, 2),
+            Self::Mat4 => (16, 4),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicField {
+    pub name: String,
+    pub wgsl_type: WgslFieldType,
+    /// Offset from the start of the struct in 32 bit words
+    pub offset: usize,
+}
+
+/// Memory layout of a dynamic component's struct in its storage buffer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicLayout {
+    pub fields: Vec<DynamicField>,
+    /// Distance between elements of the storage array in 32 bit words
+    pub stride: usize,
+}
+
+impl DynamicLayout {
+    pub fn new(fields: &[(String, String)]) -> Result<Self, String> {
+        let mut offset: usize = 0;
+        let mut struct_align = 1;
+        let fields = fields
+            .iter()
+            .map(|(name, wgsl_type)| {
+                let wgsl_type = WgslFieldType::parse(wgsl_type)
+                    .ok_or_else(|| format!("field `{name}` has unsupported type `{wgsl_type}`"))?;
+                let (size, align) = wgsl_type.size_align();
+                struct_align = struct_align.max(align);
+                let field = DynamicField {
+                    name: name.clone(),
+                    wgsl_type,
+                    offset: offset.next_multiple_of(align),
+                };
+                offset = field.offset + size;
+                Ok(field)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            fields,
+            // Structs without fields still take up one word, WGSL has no empty structs
+            stride: offset.max(1).next_multiple_of(struct_align),
+        })
+    }
+
+    pub fn field(&self, name: &str) -> Option<(usize, &DynamicField)> {
+        self.fields
+            .iter()
+            .enumerate()
+            .find(|(_, field)| field.name == name)
+    }
+
+    /// The WGSL struct declaration of this layout.
+    pub fn wgsl_struct(&self, name: &str) -> String {
+        let mut wgsl = format!("struct {name} {{\n");
+        for field in &self.fields {
+            wgsl.push_str(&format!(
+                "    {}: {},\n",
+                field.name,
+                field.wgsl_type.name()
+            ));
+        }
+        if self.fields.is_empty() {
+            wgsl.push_str("    _padding: u32,\n");
+        }
+        wgsl.push_str("}\n");
+        wgsl
+    }
+
+    /// Writes the values of one element into `words`, fields without a value of
+    /// the matching type are zeroed.
+    pub fn write(&self, words: &mut [u32], value: impl Fn(&str) -> Option<DynamicValue>) {
+        words.fill(0);
+        for field in &self.fields {
+            let Some(value) = value(&field.name).filter(|v| v.wgsl_type() == field.wgsl_type)
+            else {
+                continue;
+            };
+            let value = value.words();
+            words[field.offset..field.offset + value.len()].copy_from_slice(&value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DynamicLayout, DynamicValue};
+    use bevy_math::{Vec3, Vec4};
+
+    fn layout(fields: &[(&str, &str)]) -> DynamicLayout {
+        let fields: Vec<_> = fields
+            .iter()
+            .map(|(name, ty)| (name.to_string(), ty.to_string()))
+            .collect();
+        DynamicLayout::new(&fields).unwrap()
+    }
+
+    #[test]
+    fn storage_layout() {
+        let layout = layout(&[
+            ("a", "f32"),
+            ("b", "vec3f"),
+            ("c", "f32"),
+            ("d", "vec2<f32>"),
+        ]);
+        let offsets: Vec<_> = layout.fields.iter().map(|f| f.offset).collect();
+        assert_eq!(offsets, vec![0, 4, 7, 8]);
+        assert_eq!(layout.stride, 12);
+        assert!(DynamicLayout::new(&[("a".into(), "bool".into())]).is_err());
+    }
+
+    #[test]
+    fn write_values() {
+        let layout = layout(&[("radius", "f32"), ("color", "vec4f")]);
+        let mut words = vec![7; layout.stride];
+        layout.write(&mut words, |name| match name {
+            "color" => Some(DynamicValue::Vec4(Vec4::ONE)),
+            // Mismatched types are left at zero
+            _ => Some(DynamicValue::Vec3(Vec3::ONE)),
+        });
+        assert_eq!(words[0], 0);
+        assert_eq!(words[4..8], [1f32.to_bits(); 4]);
+    }
+}
@@ -0,0 +1,586 @@
+//! Components declared by data instead of Rust types.
+//!
+//! A [`DynamicComponentDefinition`] names a component, lists its fields with their WGSL types
+//! and gives the body of its WGSL function. Once registered with
+//! [`CuttleConfigBuilder::dynamic_component`](crate::configs::builder::CuttleConfigBuilder::dynamic_component)
+//! it gets a storage buffer and shader function like any [`Cuttle`](super::initialization::Cuttle)
+//! component, and entities use it by carrying its values in a [`DynamicCuttleComponent`].
+//!
+//! ```ron
+//! [
+//!     (
+//!         name: "Wobble",
+//!         fields: [("amplitude", "f32"), ("frequency", "f32")],
+//!         sort: 4000,
+//!         bounds: Some((set: Add, expression: "abs(amplitude)")),
+//!         wgsl: "
+//!             let wave = sin(position.x * input.frequency) * sin(position.y * input.frequency);
+//!             distance += wave * input.amplitude;
+//!         ",
+//!     ),
+//! ]
+//! ```
+//!
+//! The shader layout is fixed once the app is built, so definitions are read at startup
+//! and do not hot reload.
+
+use crate::bounding::{Bounding, LocalBounds};
+use crate::components::arena::IndexArena;
+use crate::components::buffer::{BufferFns, EntMut, EntRef, STORAGE_BINDING};
+use crate::components::{ConfigComponents, Positions, Sort};
+use crate::configs::global::GlobalConfigInfos;
+use crate::extensions::Extends;
+use crate::indices::{AddCuttleComponent, CuttleIndices, set_flag_indices};
+use crate::internal_prelude::*;
+use crate::shader::{AddSnippet, FunctionName, RenderData, Snippets};
+use bevy_asset::{AssetPath, AssetServer};
+use bevy_ecs::component::Components;
+use bevy_render::render_resource::StorageBuffer;
+use bevy_render::renderer::{RenderDevice, RenderQueue};
+use bevy_render::{Extract, RenderApp};
+use convert_case::{Case, Casing};
+use expression::BoundsExpression;
+use layout::{DynamicLayout, DynamicValue};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+pub mod expression;
+pub mod layout;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<DynamicComponentRegistry>()
+        .register_type::<DynamicCuttleComponent>()
+        .add_observer(removed_dynamic_component)
+        .add_systems(PostUpdate, assign_dynamic_indices.before(set_flag_indices));
+}
+
+/// Declares a component through data, see the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DynamicComponentDefinition {
+    /// Name used by [`DynamicCuttleComponent`], the WGSL struct is named in
+    /// PascalCase and the function in snake_case
+    pub name: String,
+    /// Field names with their WGSL types, see [`WgslFieldType`](layout::WgslFieldType)
+    pub fields: Vec<(String, String)>,
+    /// Body of the WGSL function, the fields are available through `input`
+    pub wgsl: String,
+    /// Position among the config's components, see [`Sort`]
+    pub sort: u32,
+    #[serde(default)]
+    pub bounds: Option<DynamicBounds>,
+}
+
+/// How a dynamic component affects [`LocalBounds`], see [`expression`] for the syntax.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DynamicBounds {
+    pub set: Bounding,
+    pub expression: String,
+}
+
+/// Values of dynamic components on an entity, by component and field name.
+///
+/// Fields without a value, or with a value of another type than declared, are zero.
+/// Components can be added and removed at any time by changing the values.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component, Default)]
+#[require(DynamicComponentIndices)]
+pub struct DynamicCuttleComponent {
+    values: BTreeMap<String, BTreeMap<String, DynamicValue>>,
+}
+
+impl DynamicCuttleComponent {
+    pub fn new(
+        component: impl Into<String>,
+        fields: impl IntoIterator<Item = (impl Into<String>, DynamicValue)>,
+    ) -> Self {
+        Self::default().with(component, fields)
+    }
+
+    pub fn with(
+        mut self,
+        component: impl Into<String>,
+        fields: impl IntoIterator<Item = (impl Into<String>, DynamicValue)>,
+    ) -> Self {
+        let values = self.values.entry(component.into()).or_default();
+        values.extend(fields.into_iter().map(|(name, value)| (name.into(), value)));
+        self
+    }
+
+    pub fn set(
+        &mut self,
+        component: impl Into<String>,
+        field: impl Into<String>,
+        value: impl Into<DynamicValue>,
+    ) {
+        let values = self.values.entry(component.into()).or_default();
+        values.insert(field.into(), value.into());
+    }
+
+    pub fn get(&self, component: &str, field: &str) -> Option<DynamicValue> {
+        self.values.get(component)?.get(field).copied()
+    }
+
+    pub fn contains(&self, component: &str) -> bool {
+        self.values.contains_key(component)
+    }
+
+    /// Removes a component with all of its values.
+    pub fn remove(&mut self, component: &str) {
+        self.values.remove(component);
+    }
+}
+
+/// Buffer index of each dynamic component of an entity, by registry id.
+#[derive(Component, Debug, Default, Deref)]
+pub struct DynamicComponentIndices(BTreeMap<usize, u32>);
+
+/// Releases the buffer indices of a removed or despawned [`DynamicCuttleComponent`]
+/// and drops them from its shape.
+fn removed_dynamic_component(
+    remove: On<Remove, DynamicCuttleComponent>,
+    mut registry: ResMut<DynamicComponentRegistry>,
+    mut dynamic_indices: Query<&mut DynamicComponentIndices>,
+    positions: Query<&Positions>,
+    extensions: Query<&Extends>,
+    mut indices: Query<&mut CuttleIndices>,
+) {
+    let Ok(mut dynamic_indices) = dynamic_indices.get_mut(remove.entity) else {
+        return;
+    };
+    let root = extensions.get(remove.entity).map_or(remove.entity, |e| e.0);
+    let mut shape = indices.get_mut(root).ok();
+    for (id, index) in std::mem::take(&mut dynamic_indices.0) {
+        release_dynamic_index(
+            &mut registry,
+            &positions,
+            shape.as_deref_mut(),
+            remove.entity,
+            id,
+            index,
+        );
+    }
+}
+
+fn release_dynamic_index(
+    registry: &mut DynamicComponentRegistry,
+    positions: &Query<&Positions>,
+    shape: Option<&mut CuttleIndices>,
+    entity: Entity,
+    id: usize,
+    index: u32,
+) {
+    let info = &mut registry.components[id];
+    info.arena.release(index);
+    let Some(shape) = shape else {
+        return;
+    };
+    let position = positions
+        .get(info.component)
+        .ok()
+        .and_then(|positions| positions.get(shape.group_id).copied().flatten());
+    if let Some(position) = position {
+        shape.remove_source(position, entity);
+    }
+}
+
+/// All registered dynamic components, shared between configs.
+#[derive(Resource, Default)]
+pub struct DynamicComponentRegistry {
+    components: Vec<DynamicComponentInfo>,
+}
+
+pub struct DynamicComponentInfo {
+    pub definition: DynamicComponentDefinition,
+    pub layout: DynamicLayout,
+    /// Entity describing the component to configs, like a [`CuttleComponent`](super::CuttleComponent)
+    pub component: Entity,
+    bounds: Option<(Bounding, BoundsExpression)>,
+    arena: IndexArena<DynamicCuttleComponent>,
+}
+
+impl DynamicComponentRegistry {
+    pub fn get(&self, name: &str) -> Option<(usize, &DynamicComponentInfo)> {
+        self.components
+            .iter()
+            .enumerate()
+            .find(|(_, info)| info.definition.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &DynamicComponentInfo> {
+        self.components.iter()
+    }
+}
+
+/// Storage buffers of all dynamic components, by registry id.
+#[derive(Component, Default)]
+pub(crate) struct DynamicBuffers(Vec<StorageBuffer<Vec<u32>>>);
+
+pub(crate) fn register_dynamic_component(
+    app: &mut App,
+    config: Entity,
+    definition: DynamicComponentDefinition,
+) {
+    let registry = app
+        .world_mut()
+        .get_resource_or_init::<DynamicComponentRegistry>();
+    let component = match registry.get(&definition.name) {
+        Some((_, info)) => {
+            assert_eq!(
+                info.definition, definition,
+                "Dynamic component `{}` was registered with different definitions",
+                definition.name
+            );
+            info.component
+        }
+        None => init_dynamic_component(app, definition),
+    };
+    app.world_mut()
+        .get_mut::<ConfigComponents>(config)
+        .unwrap()
+        .push(component);
+}
+
+fn init_dynamic_component(app: &mut App, definition: DynamicComponentDefinition) -> Entity {
+    let name = &definition.name;
+    let layout = DynamicLayout::new(&definition.fields)
+        .unwrap_or_else(|err| panic!("Dynamic component `{name}`: {err}"));
+    let bounds = definition.bounds.as_ref().map(|bounds| {
+        let expression = BoundsExpression::parse(&bounds.expression, &layout)
+            .unwrap_or_else(|err| panic!("Bounds of dynamic component `{name}`: {err}"));
+        (bounds.set, expression)
+    });
+
+    let struct_name = name.to_case(Case::Pascal);
+    let function_name = name.to_case(Case::Snake);
+    let snippet = format!(
+        "{}\nfn {function_name}(input: {struct_name}) {{\n{}\n}}\n",
+        layout.wgsl_struct(&struct_name),
+        definition.wgsl
+    );
+
+    let mut globals = app.world_mut().resource_mut::<GlobalConfigInfos>();
+    let binding = globals.binding();
+    let buffer_entity = globals.buffer_entity.id();
+
+    let id = app
+        .world()
+        .resource::<DynamicComponentRegistry>()
+        .components
+        .len();
+    init_dynamic_buffer(app, buffer_entity, id);
+    if let Some((set, _)) = bounds {
+        app.add_systems(PostUpdate, make_dynamic_bounds_system(id, set).in_set(set));
+    }
+
+    let mut snippets = Snippets::default();
+    snippets.push(AddSnippet::Inline(snippet));
+    let component = app
+        .world_mut()
+        .spawn((
+            Name::new(format!("DynamicCuttleComponent<{name}>")),
+            Sort(definition.sort),
+            Positions::default(),
+            snippets,
+            FunctionName(function_name),
+            RenderData {
+                binding,
+                type_name: struct_name,
+            },
+        ))
+        .id();
+
+    app.world_mut()
+        .resource_mut::<DynamicComponentRegistry>()
+        .components
+        .push(DynamicComponentInfo {
+            definition,
+            layout,
+            component,
+            bounds,
+            arena: IndexArena::default(),
+        });
+    component
+}
+
+fn init_dynamic_buffer(app: &mut App, buffer_entity: Entity, id: usize) {
+    let render_app = app.sub_app_mut(RenderApp);
+    if id == 0 {
+        render_app.add_systems(ExtractSchedule, extract_dynamic_components);
+    }
+
+    let mut entity = render_app.world_mut().entity_mut(buffer_entity);
+    if !entity.contains::<DynamicBuffers>() {
+        entity.insert(DynamicBuffers::default());
+    }
+    let mut buffers = entity.get_mut::<DynamicBuffers>().unwrap();
+    buffers.0.push(StorageBuffer::default());
+
+    let mut buffer_fns = entity.get_mut::<BufferFns>().unwrap();
+    buffer_fns.write.push(Box::new(
+        move |entity: &mut EntMut, device: &RenderDevice, queue: &RenderQueue| {
+            if let Some(mut buffers) = entity.get_mut::<DynamicBuffers>() {
+                buffers.0[id].write_buffer(device, queue);
+            }
+        },
+    ));
+    buffer_fns.layout.push(STORAGE_BINDING);
+    buffer_fns.bindings.push(Box::new(move |entity: &EntRef| {
+        entity.get::<DynamicBuffers>().unwrap().0[id]
+            .buffer()
+            .unwrap()
+            .as_entire_binding()
+    }));
+}
+
+fn assign_dynamic_indices(
+    mut registry: ResMut<DynamicComponentRegistry>,
+    mut query: Query<
+        (
+            Entity,
+            &DynamicCuttleComponent,
+            &mut DynamicComponentIndices,
+        ),
+        Changed<DynamicCuttleComponent>,
+    >,
+    positions: Query<&Positions>,
+    extensions: Query<&Extends>,
+    mut shapes: Query<&mut CuttleIndices>,
+    components: &Components,
+    mut messages: MessageWriter<AddCuttleComponent>,
+) {
+    let Some(component_id) = components.component_id::<DynamicCuttleComponent>() else {
+        return;
+    };
+    for (entity, component, mut indices) in &mut query {
+        // Components whose values were removed
+        let removed: Vec<_> = indices
+            .iter()
+            .filter(|&(&id, _)| !component.contains(&registry.components[id].definition.name))
+            .map(|(&id, &index)| (id, index))
+            .collect();
+        if !removed.is_empty() {
+            let root = extensions.get(entity).map_or(entity, |e| e.0);
+            let mut shape = shapes.get_mut(root).ok();
+            for (id, index) in removed {
+                indices.0.remove(&id);
+                release_dynamic_index(
+                    &mut registry,
+                    &positions,
+                    shape.as_deref_mut(),
+                    entity,
+                    id,
+                    index,
+                );
+            }
+        }
+
+        for name in component.values.keys() {
+            let Some((id, _)) = registry.get(name) else {
+                continue;
+            };
+            if indices.contains_key(&id) {
+                continue;
+            }
+            let info = &mut registry.components[id];
+            let index = info.arena.get();
+            indices.0.insert(id, index);
+            messages.write(AddCuttleComponent::new(
+                info.component,
+                component_id,
+                entity,
+                index,
+            ));
+        }
+    }
+}
+
+fn extract_dynamic_components(
+    mut buffers: Single<&mut DynamicBuffers>,
+    registry: Extract<Res<DynamicComponentRegistry>>,
+    components: Extract<
+        Query<
+            (&DynamicCuttleComponent, &DynamicComponentIndices),
+            Or<(
+                Changed<DynamicCuttleComponent>,
+                Changed<DynamicComponentIndices>,
+            )>,
+        >,
+    >,
+) {
+    for (info, buffer) in registry.components.iter().zip(&mut buffers.0) {
+        let size = info.arena.max as usize * info.layout.stride;
+        if buffer.get().len() != size {
+            buffer.get_mut().resize(size, 0);
+        }
+    }
+
+    for (component, indices) in &components {
+        for (&id, &index) in indices.iter() {
+            let info = &registry.components[id];
+            let Some(values) = component.values.get(&info.definition.name) else {
+                continue;
+            };
+            let start = index as usize * info.layout.stride;
+            let words = &mut buffers.0[id].get_mut()[start..start + info.layout.stride];
+            info.layout.write(words, |field| values.get(field).copied());
+        }
+    }
+}
+
+fn make_dynamic_bounds_system(
+    id: usize,
+    set: Bounding,
+) -> impl Fn(Res<DynamicComponentRegistry>, Query<(&mut LocalBounds, &DynamicCuttleComponent)>) {
+    move |registry, mut query| {
+        let info = &registry.components[id];
+        let Some((_, expression)) = &info.bounds else {
+            return;
+        };
+        for (mut bounds, component) in &mut query {
+            let Some(values) = component.values.get(&info.definition.name) else {
+                continue;
+            };
+            let field = |index: usize| values.get(&info.layout.fields[index].name).copied();
+            set.apply(&mut bounds, expression.eval(&field).extents());
+        }
+    }
+}
+
+/// Parses a RON list of [`DynamicComponentDefinition`]s.
+pub fn parse_definitions(
+    ron: &str,
+) -> Result<Vec<DynamicComponentDefinition>, ron::error::SpannedError> {
+    ron::from_str(ron)
+}
+
+/// Reads an asset synchronously, for files needed before the app is built.
+pub(crate) fn read_asset_to_string(app: &App, path: &AssetPath) -> Result<String, String> {
+    let server = app
+        .world()
+        .get_resource::<AssetServer>()
+        .ok_or("the AssetPlugin has to be added before reading assets")?;
+    let source = server
+        .get_source(path.source().clone())
+        .map_err(|err| err.to_string())?;
+    bevy_tasks::block_on(async {
+        let mut reader = source
+            .reader()
+            .read(path.path())
+            .await
+            .map_err(|err| err.to_string())?;
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|err| err.to_string())?;
+        String::from_utf8(bytes).map_err(|err| err.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DynamicComponentDefinition, DynamicComponentInfo, DynamicComponentRegistry,
+        DynamicCuttleComponent, assign_dynamic_indices, removed_dynamic_component,
+    };
+    use crate::components::Positions;
+    use crate::components::arena::IndexArena;
+    use crate::components::dynamic::layout::{DynamicLayout, DynamicValue};
+    use crate::extensions::{Extends, set_extension_index};
+    use crate::indices::{AddCuttleComponent, CuttleIndices, set_flag_indices};
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::system::SystemId;
+
+    fn info(world: &mut World, name: &str, position: u8) -> DynamicComponentInfo {
+        let fields = vec![("amount".to_string(), "f32".to_string())];
+        DynamicComponentInfo {
+            layout: DynamicLayout::new(&fields).unwrap(),
+            definition: DynamicComponentDefinition {
+                name: name.to_string(),
+                fields,
+                wgsl: String::new(),
+                sort: 0,
+                bounds: None,
+            },
+            component: world.spawn(Positions(vec![Some(position)])).id(),
+            bounds: None,
+            arena: IndexArena::default(),
+        }
+    }
+
+    fn values(names: &[&str]) -> DynamicCuttleComponent {
+        names
+            .iter()
+            .fold(DynamicCuttleComponent::default(), |c, name| {
+                c.with(*name, [("amount", DynamicValue::F32(1.))])
+            })
+    }
+
+    #[test]
+    fn removed_components_release_their_indices() {
+        let mut world = World::new();
+        let components = vec![info(&mut world, "wobble", 0), info(&mut world, "glow", 1)];
+        world.insert_resource(DynamicComponentRegistry { components });
+        world.init_resource::<Messages<AddCuttleComponent>>();
+        world.add_observer(removed_dynamic_component);
+        let systems: [SystemId<(), Result<()>>; 2] = [
+            world.register_system(set_extension_index),
+            world.register_system(set_flag_indices),
+        ];
+        let assign = world.register_system(assign_dynamic_indices);
+        let update = |world: &mut World| {
+            world.run_system(assign).unwrap();
+            for system in systems {
+                world.run_system(system).unwrap().unwrap();
+            }
+        };
+        let shape = |world: &World, root| {
+            let indices = world.get::<CuttleIndices>(root).unwrap();
+            let shape: Vec<_> = indices
+                .components()
+                .zip(indices.values().copied())
+                .collect();
+            shape
+        };
+        let arena_max = |world: &World, id: usize| {
+            let registry = world.resource::<DynamicComponentRegistry>();
+            registry.components[id].arena.max
+        };
+
+        let root = world
+            .spawn((CuttleIndices::default(), values(&["wobble"])))
+            .id();
+        let extension = world
+            .spawn((Extends(root), values(&["wobble", "glow"])))
+            .id();
+        update(&mut world);
+        assert_eq!(
+            shape(&world, root),
+            vec![((0, root), 0), ((0, extension), 1), ((1, extension), 0)]
+        );
+
+        // Removing a key drops its component
+        world
+            .get_mut::<DynamicCuttleComponent>(extension)
+            .unwrap()
+            .remove("glow");
+        update(&mut world);
+        assert_eq!(
+            shape(&world, root),
+            vec![((0, root), 0), ((0, extension), 1)]
+        );
+
+        // A despawned extension's indices are reused by the next one
+        world.despawn(extension);
+        assert_eq!(shape(&world, root), vec![((0, root), 0)]);
+        let added = world
+            .spawn((Extends(root), values(&["wobble", "glow"])))
+            .id();
+        update(&mut world);
+        assert_eq!(
+            shape(&world, root),
+            vec![((0, root), 0), ((0, added), 1), ((1, added), 0)]
+        );
+        assert_eq!((arena_max(&world, 0), arena_max(&world, 1)), (2, 1));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::cache::{InvalidateCaches, invalidate_caches};
 use crate::components::arena::IndexArena;
 use crate::components::buffer::{CompBuffer, GlobalBuffer};
 use crate::configs::builder::CuttleBuilder;
@@ -7,6 +8,7 @@ use crate::internal_prelude::*;
 use crate::pipeline::extract::{extract_cuttle_comp, extract_cuttle_global};
 use crate::shader::wgsl_struct::{WgslType, WgslTypes};
 use crate::shader::{AddSnippet, RenderData, Snippets};
+use bevy_ecs::system::ScheduleSystem;
 use bevy_reflect::Typed;
 use bevy_render::RenderApp;
 use bevy_render::render_resource::ShaderSize;
@@ -26,8 +28,19 @@ pub fn init_component_render_data<C: Component, R: CuttleRenderData>(
     entity: Entity,
     to_render_data: fn(&C) -> R,
 ) {
+    init_component_buffer(app, entity, to_render_data, extract_cuttle_comp::<C, R>);
+}
+
+/// Sets up the [`CompBuffer`] of a component, filled by the `extract` system.
+/// Returns `false` if the component already has render data.
+pub(crate) fn init_component_buffer<C: Component, R: CuttleRenderData, M>(
+    app: &mut App,
+    entity: Entity,
+    to_render_data: fn(&C) -> R,
+    extract: impl IntoScheduleConfigs<ScheduleSystem, M>,
+) -> bool {
     if app.world().entity(entity).contains::<RenderData>() {
-        return;
+        return false;
     }
 
     let mut globals = app.world_mut().resource_mut::<GlobalConfigInfos>();
@@ -38,9 +51,10 @@ pub fn init_component_render_data<C: Component, R: CuttleRenderData>(
 
     app.register_required_components::<C, CuttleComponentIndex<C>>();
     app.init_resource::<IndexArena<C>>();
+    app.add_systems(PostUpdate, invalidate_caches::<C>.in_set(InvalidateCaches));
 
     app.sub_app_mut(RenderApp)
-        .add_systems(ExtractSchedule, extract_cuttle_comp::<C, R>);
+        .add_systems(ExtractSchedule, extract);
 
     let wgsl_types = app.world().resource::<WgslTypes>();
     let WgslType { type_name, snippet } = wgsl_types.get_type::<R>();
@@ -54,6 +68,7 @@ pub fn init_component_render_data<C: Component, R: CuttleRenderData>(
     }
 
     entity.insert(RenderData { binding, type_name });
+    true
 }
 
 pub fn init_global_render_data<C: Component, R: CuttleRenderData>(
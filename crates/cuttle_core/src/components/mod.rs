@@ -1,5 +1,5 @@
 use crate::configs::ConfigId;
-use crate::indices::added_cuttle_component;
+use crate::indices::{added_cuttle_component, removed_cuttle_component};
 use crate::shader::Snippets;
 use crate::{FinishCuttleSetup, FinishCuttleSetupSet, internal_prelude::*};
 use bevy_app::{App, Plugin};
@@ -9,7 +9,10 @@ use std::marker::PhantomData;
 
 pub mod arena;
 pub mod buffer;
+pub mod dynamic;
 pub mod initialization;
+pub mod points;
+pub mod textures;
 
 pub struct CompPlugin;
 impl Plugin for CompPlugin {
@@ -25,7 +28,7 @@ impl Plugin for CompPlugin {
                     init_component_positions.in_set(FinishCuttleSetupSet::InitPositions),
                 ),
             )
-            .add_plugins(BufferPlugin);
+            .add_plugins((BufferPlugin, dynamic::plugin, points::plugin));
     }
 }
 
@@ -84,6 +87,7 @@ pub fn register_cuttle<C: Component>(
 
 fn init_cuttle<C: Component>(mut cmds: Commands) -> Entity {
     cmds.add_observer(added_cuttle_component::<C>);
+    cmds.add_observer(removed_cuttle_component::<C>);
     cmds.spawn((
         Name::new(format!("CuttleComponent<{}>", type_name::<C>())),
         CuttleComponent::<C>::new(),
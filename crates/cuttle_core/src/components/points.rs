@@ -0,0 +1,196 @@
+//! Variable-length point data, for components like polygons whose size differs per instance.
+//!
+//! The points of all instances are packed into one storage buffer, available to the shader
+//! as `cuttle_points`. The render data of a point component holds a [`PointsRange`]
+//! locating its points in there:
+//!
+//! ```wgsl
+//! fn my_polyline(input: MyPolylineData) {
+//!     for (var i = 0u; i < input.points.len; i++) {
+//!         let point = cuttle_points[input.points.offset + i];
+//!     }
+//! }
+//! ```
+//!
+//! Point components are registered with
+//! [`CuttleBuilder::render_points_from`](crate::configs::builder::CuttleBuilder::render_points_from).
+
+use crate::components::arena::IndexArena;
+use crate::components::buffer::{BufferFns, CompBuffer, EntMut, EntRef, STORAGE_BINDING};
+use crate::components::initialization::{CuttleRenderData, init_component_buffer};
+use crate::configs::global::GlobalConfigInfos;
+use crate::indices::CuttleComponentIndex;
+use crate::internal_prelude::*;
+use crate::shader::wgsl_struct::RegisterWgslTypeExt;
+use crate::shader::{AddSnippet, Snippets};
+use bevy_math::Vec2;
+use bevy_render::render_resource::{ShaderType, StorageBuffer};
+use bevy_render::renderer::{RenderDevice, RenderQueue};
+use bevy_render::{Extract, RenderApp};
+use std::any::TypeId;
+use std::collections::HashMap;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<PointsRange>()
+        .register_wgsl_type::<PointsRange>("PointsRange");
+}
+
+/// Components with a variable number of points.
+pub trait CuttlePoints: Component {
+    fn points(&self) -> &[Vec2];
+}
+
+/// Where the points of a component instance lie in `cuttle_points`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect, ShaderType)]
+pub struct PointsRange {
+    pub offset: u32,
+    pub len: u32,
+}
+
+/// Shader declarations of the points buffer, shared by all point components.
+#[derive(Resource)]
+struct PointsSnippet(String);
+
+/// Points of all point components, on the render world's component buffer entity.
+#[derive(Component, Default)]
+pub(crate) struct PointsBuffer {
+    storage: StorageBuffer<Vec<Vec2>>,
+    /// Space reserved by component type and index, which can exceed the current points
+    slots: HashMap<(TypeId, u32), PointsRange>,
+    /// Space given up by instances that outgrew it
+    free: Vec<PointsRange>,
+}
+
+impl PointsBuffer {
+    /// Writes the points of an instance, moving them if they don't fit its previous space.
+    pub(crate) fn write(&mut self, key: (TypeId, u32), points: &[Vec2]) -> PointsRange {
+        let len = points.len() as u32;
+        let slot = match self.slots.get(&key).copied() {
+            Some(slot) if slot.len >= len => slot,
+            previous => {
+                self.free.extend(previous);
+                let slot = self.allocate(len);
+                self.slots.insert(key, slot);
+                slot
+            }
+        };
+        let start = slot.offset as usize;
+        self.storage.get_mut()[start..start + points.len()].copy_from_slice(points);
+        PointsRange {
+            offset: slot.offset,
+            len,
+        }
+    }
+
+    /// Reuses the first free space large enough, otherwise grows the buffer.
+    fn allocate(&mut self, len: u32) -> PointsRange {
+        if let Some(i) = self.free.iter().position(|slot| slot.len >= len) {
+            return self.free.swap_remove(i);
+        }
+        let buffer = self.storage.get_mut();
+        let offset = buffer.len() as u32;
+        buffer.resize(buffer.len() + len as usize, Vec2::ZERO);
+        PointsRange { offset, len }
+    }
+}
+
+pub(crate) fn init_component_points_data<C: Component, R: CuttleRenderData>(
+    app: &mut App,
+    entity: Entity,
+    points: fn(&C) -> &[Vec2],
+    to_render_data: fn(&C, PointsRange) -> R,
+) {
+    let extract = make_extract_points_system(points, to_render_data);
+    if !init_component_buffer(app, entity, |_: &C| R::default(), extract) {
+        return;
+    }
+
+    if !app.world().contains_resource::<PointsSnippet>() {
+        init_points_buffer(app);
+    }
+    let snippet = app.world().resource::<PointsSnippet>().0.clone();
+    app.world_mut()
+        .get_mut::<Snippets>(entity)
+        .unwrap()
+        .push(AddSnippet::Inline(snippet));
+}
+
+fn init_points_buffer(app: &mut App) {
+    let mut globals = app.world_mut().resource_mut::<GlobalConfigInfos>();
+    let binding = globals.binding();
+    let buffer_entity = globals.buffer_entity.id();
+
+    let mut entity = app
+        .sub_app_mut(RenderApp)
+        .world_mut()
+        .entity_mut(buffer_entity);
+    entity.insert(PointsBuffer::default());
+    let mut buffer_fns = entity.get_mut::<BufferFns>().unwrap();
+    buffer_fns.write.push(Box::new(
+        |entity: &mut EntMut, device: &RenderDevice, queue: &RenderQueue| {
+            if let Some(mut buffer) = entity.get_mut::<PointsBuffer>() {
+                buffer.storage.write_buffer(device, queue);
+            }
+        },
+    ));
+    buffer_fns.layout.push(STORAGE_BINDING);
+    buffer_fns.bindings.push(Box::new(|entity: &EntRef| {
+        entity
+            .get::<PointsBuffer>()
+            .unwrap()
+            .storage
+            .buffer()
+            .unwrap()
+            .as_entire_binding()
+    }));
+
+    app.insert_resource(PointsSnippet(format!(
+        "struct PointsRange {{\n    offset: u32,\n    len: u32,\n}}\n\n\
+         @group(2) @binding({binding}) var<storage, read> cuttle_points: array<vec2<f32>>;\n"
+    )));
+}
+
+fn make_extract_points_system<C: Component, R: CuttleRenderData>(
+    points: fn(&C) -> &[Vec2],
+    to_render_data: fn(&C, PointsRange) -> R,
+) -> impl FnMut(
+    Single<(&mut CompBuffer<C, R>, &mut PointsBuffer)>,
+    Extract<Res<IndexArena<C>>>,
+    Extract<Query<(&CuttleComponentIndex<C>, &C), Changed<C>>>,
+) {
+    move |buffers, arena, comps| {
+        let (mut buffer, mut points_buffer) = buffers.into_inner();
+        buffer.resize(arena.max as usize);
+        for (index, comp) in &comps {
+            let range = points_buffer.write((TypeId::of::<C>(), **index), points(comp));
+            buffer.set(**index as usize, to_render_data(comp, range));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PointsBuffer, PointsRange};
+    use bevy_math::Vec2;
+    use std::any::TypeId;
+
+    #[test]
+    fn reuses_space_of_outgrown_instances() {
+        let mut buffer = PointsBuffer::default();
+        let key = |index| (TypeId::of::<PointsBuffer>(), index);
+
+        let first = buffer.write(key(0), &[Vec2::ONE; 3]);
+        let second = buffer.write(key(1), &[Vec2::X; 2]);
+        assert_eq!(first, PointsRange { offset: 0, len: 3 });
+        assert_eq!(second, PointsRange { offset: 3, len: 2 });
+
+        // Shrinking stays in place, growing moves to the end
+        assert_eq!(buffer.write(key(0), &[Vec2::Y]).offset, 0);
+        assert_eq!(buffer.write(key(1), &[Vec2::Y; 4]).offset, 5);
+        // The space given up by the second instance is reused
+        assert_eq!(buffer.write(key(2), &[Vec2::NEG_Y; 2]).offset, 3);
+
+        assert_eq!(buffer.storage.get().len(), 9);
+        assert_eq!(&buffer.storage.get()[3..5], &[Vec2::NEG_Y; 2]);
+    }
+}
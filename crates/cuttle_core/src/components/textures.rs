@@ -0,0 +1,352 @@
+//! Images sampled by components, for fills, patterns or shapes read from a texture.
+//!
+//! The component bind group has [`MAX_TEXTURES`] texture slots, each bound with the image's
+//! own sampler. The render data of a texture component holds the [`TextureSlot`] its image
+//! was given, which the shader passes to `cuttle_texture`:
+//!
+//! ```wgsl
+//! fn my_image_fill(input: MyImageFillData) {
+//!     color = cuttle_texture(input.texture, position / input.size + 0.5);
+//! }
+//! ```
+//!
+//! Slots are shared by all instances using the same image. They are bound once for all
+//! batches rather than per batch, so at most [`MAX_TEXTURES`] different images are drawn
+//! at a time. Instances of further images sample white until a slot is freed.
+//! Sampling ignores mipmaps, and the image format has to be filterable.
+//!
+//! Texture components are registered with
+//! [`CuttleBuilder::render_texture_from`](crate::configs::builder::CuttleBuilder::render_texture_from).
+
+use crate::components::arena::IndexArena;
+use crate::components::buffer::{
+    BufferFns, CompBuffer, CompBufferEntity, EntRef, build_buffer_bind_groups,
+};
+use crate::components::initialization::{CuttleRenderData, init_component_buffer};
+use crate::configs::global::GlobalConfigInfos;
+use crate::indices::CuttleComponentIndex;
+use crate::internal_prelude::*;
+use crate::pipeline::CuttleRenderSet;
+use crate::shader::{AddSnippet, Snippets};
+use bevy_asset::{AssetId, Handle};
+use bevy_image::Image;
+use bevy_log::warn_once;
+use bevy_render::render_asset::RenderAssets;
+use bevy_render::render_resource::{
+    BindingResource, BindingType, Sampler, SamplerBindingType, TextureSampleType, TextureView,
+    TextureViewDimension,
+};
+use bevy_render::texture::{FallbackImage, GpuImage};
+use bevy_render::{Extract, Render, RenderApp, RenderSystems};
+use std::any::TypeId;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+/// Images that can be bound at the same time, each taking a texture and a sampler binding.
+pub const MAX_TEXTURES: usize = 16;
+
+/// Components sampling an image.
+pub trait CuttleTexture: Component {
+    fn texture(&self) -> &Handle<Image>;
+}
+
+/// The slot a component instance's image is bound to, passed to `cuttle_texture`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deref)]
+pub struct TextureSlot(pub u32);
+
+impl TextureSlot {
+    /// No slot was free, sampling gives white
+    pub const NONE: Self = Self(u32::MAX);
+}
+
+/// Shader declarations of the texture slots, shared by all texture components.
+#[derive(Resource)]
+struct TexturesSnippet(String);
+
+/// Images bound to the texture slots, on the render world's component buffer entity.
+#[derive(Component, Default)]
+pub(crate) struct TextureSlots {
+    /// The image in each slot and the number of instances using it
+    slots: [Option<(AssetId<Image>, u32)>; MAX_TEXTURES],
+    /// The image of each instance holding a slot, by component type and entity
+    users: HashMap<(TypeId, Entity), AssetId<Image>>,
+    /// Instances no slot was free for, given one once a slot frees up
+    waiting: HashSet<(TypeId, Entity)>,
+    /// What is bound to each slot this frame, the fallback image for empty
+    /// slots and images that are still loading
+    bound: Vec<(TextureView, Sampler)>,
+}
+
+impl TextureSlots {
+    /// Gives an instance the slot of its image, taking a free one if the image has none yet.
+    pub(crate) fn write(&mut self, key: (TypeId, Entity), image: AssetId<Image>) -> TextureSlot {
+        self.remove(key);
+
+        let slot = self
+            .position(image)
+            .or_else(|| self.slots.iter().position(Option::is_none));
+        let Some(slot) = slot else {
+            warn_once!(
+                "All {MAX_TEXTURES} cuttle texture slots are in use, further images are not bound"
+            );
+            self.waiting.insert(key);
+            return TextureSlot::NONE;
+        };
+        self.waiting.remove(&key);
+
+        self.slots[slot].get_or_insert((image, 0)).1 += 1;
+        self.users.insert(key, image);
+        TextureSlot(slot as u32)
+    }
+
+    /// Frees the slot of an instance whose component was removed.
+    pub(crate) fn remove(&mut self, key: (TypeId, Entity)) {
+        self.waiting.remove(&key);
+        if let Some(image) = self.users.remove(&key) {
+            self.release(image);
+        }
+    }
+
+    /// Instances of a component type waiting for a slot, if one is free.
+    pub(crate) fn waiting(&self, type_id: TypeId) -> Vec<Entity> {
+        if !self.slots.iter().any(Option::is_none) {
+            return Vec::new();
+        }
+        self.waiting
+            .iter()
+            .filter(|(id, _)| *id == type_id)
+            .map(|&(_, entity)| entity)
+            .collect()
+    }
+
+    fn release(&mut self, image: AssetId<Image>) {
+        let Some(slot) = self.position(image) else {
+            return;
+        };
+        let (_, users) = self.slots[slot].as_mut().unwrap();
+        *users -= 1;
+        if *users == 0 {
+            self.slots[slot] = None;
+        }
+    }
+
+    fn position(&self, image: AssetId<Image>) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|slot| matches!(slot, Some((id, _)) if *id == image))
+    }
+}
+
+pub(crate) fn init_component_texture_data<C: Component, R: CuttleRenderData>(
+    app: &mut App,
+    entity: Entity,
+    texture: fn(&C) -> &Handle<Image>,
+    to_render_data: fn(&C, TextureSlot) -> R,
+) {
+    let extract = make_extract_texture_system(texture, to_render_data);
+    if !init_component_buffer(app, entity, |_: &C| R::default(), extract) {
+        return;
+    }
+
+    if !app.world().contains_resource::<TexturesSnippet>() {
+        init_texture_slots(app);
+    }
+    let snippet = app.world().resource::<TexturesSnippet>().0.clone();
+    app.world_mut()
+        .get_mut::<Snippets>(entity)
+        .unwrap()
+        .push(AddSnippet::Inline(snippet));
+}
+
+fn init_texture_slots(app: &mut App) {
+    let mut globals = app.world_mut().resource_mut::<GlobalConfigInfos>();
+    let bindings: Vec<u32> = (0..MAX_TEXTURES * 2).map(|_| globals.binding()).collect();
+    let buffer_entity = globals.buffer_entity.id();
+
+    let render_app = app.sub_app_mut(RenderApp);
+    render_app.add_systems(
+        Render,
+        bind_texture_slots
+            .in_set(CuttleRenderSet::ComponentBuffers)
+            .after(RenderSystems::PrepareAssets)
+            .before(build_buffer_bind_groups),
+    );
+
+    let mut entity = render_app.world_mut().entity_mut(buffer_entity);
+    entity.insert(TextureSlots::default());
+    let mut buffer_fns = entity.get_mut::<BufferFns>().unwrap();
+    for slot in 0..MAX_TEXTURES {
+        buffer_fns.layout.push(BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D2,
+            multisampled: false,
+        });
+        buffer_fns.bindings.push(Box::new(move |entity: &EntRef| {
+            let (view, _) = &entity.get::<TextureSlots>().unwrap().bound[slot];
+            BindingResource::TextureView(view)
+        }));
+        buffer_fns
+            .layout
+            .push(BindingType::Sampler(SamplerBindingType::Filtering));
+        buffer_fns.bindings.push(Box::new(move |entity: &EntRef| {
+            let (_, sampler) = &entity.get::<TextureSlots>().unwrap().bound[slot];
+            BindingResource::Sampler(sampler)
+        }));
+    }
+
+    app.insert_resource(TexturesSnippet(textures_snippet(&bindings)));
+}
+
+fn textures_snippet(bindings: &[u32]) -> String {
+    let mut snippet = String::new();
+    for (slot, pair) in bindings.chunks(2).enumerate() {
+        let _ = writeln!(
+            snippet,
+            "@group(2) @binding({}) var cuttle_texture_{slot}: texture_2d<f32>;\n\
+             @group(2) @binding({}) var cuttle_sampler_{slot}: sampler;",
+            pair[0], pair[1]
+        );
+    }
+
+    snippet.push_str(
+        "\n/// Samples the image in a texture slot, white if the slot is empty\n\
+         fn cuttle_texture(slot: u32, uv: vec2<f32>) -> vec4<f32> {\n    switch slot {\n",
+    );
+    for slot in 0..MAX_TEXTURES {
+        let _ = writeln!(
+            snippet,
+            "        case {slot}u: {{ return textureSampleLevel(cuttle_texture_{slot}, cuttle_sampler_{slot}, uv, 0.0); }}"
+        );
+    }
+    snippet.push_str("        default: { return vec4(1.0); }\n    }\n}\n");
+    snippet
+}
+
+fn bind_texture_slots(
+    mut slots: Single<&mut TextureSlots, With<CompBufferEntity>>,
+    images: Res<RenderAssets<GpuImage>>,
+    fallback: Res<FallbackImage>,
+) {
+    let bound = (0..MAX_TEXTURES)
+        .map(|slot| {
+            let image = slots.slots[slot]
+                .and_then(|(id, _)| images.get(id))
+                .unwrap_or(&fallback.d2);
+            (image.texture_view.clone(), image.sampler.clone())
+        })
+        .collect();
+    slots.bound = bound;
+}
+
+fn make_extract_texture_system<C: Component, R: CuttleRenderData>(
+    texture: fn(&C) -> &Handle<Image>,
+    to_render_data: fn(&C, TextureSlot) -> R,
+) -> impl FnMut(
+    Single<(&mut CompBuffer<C, R>, &mut TextureSlots)>,
+    Extract<Res<IndexArena<C>>>,
+    Extract<Query<(Entity, &CuttleComponentIndex<C>, &C), Changed<C>>>,
+    Extract<Query<(&CuttleComponentIndex<C>, &C)>>,
+    Extract<RemovedComponents<C>>,
+) {
+    move |buffers, arena, comps, all, mut removed| {
+        let (mut buffer, mut slots) = buffers.into_inner();
+        for entity in removed.read() {
+            slots.remove((TypeId::of::<C>(), entity));
+        }
+        buffer.resize(arena.max as usize);
+        for (entity, index, comp) in &comps {
+            let slot = slots.write((TypeId::of::<C>(), entity), texture(comp).id());
+            buffer.set(**index as usize, to_render_data(comp, slot));
+        }
+        for entity in slots.waiting(TypeId::of::<C>()) {
+            if let Ok((index, comp)) = all.get(entity) {
+                let slot = slots.write((TypeId::of::<C>(), entity), texture(comp).id());
+                buffer.set(**index as usize, to_render_data(comp, slot));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_TEXTURES, TextureSlot, TextureSlots};
+    use bevy_asset::AssetId;
+    use bevy_asset::uuid::Uuid;
+    use bevy_ecs::entity::Entity;
+    use bevy_image::Image;
+    use std::any::TypeId;
+
+    fn key(n: u32) -> (TypeId, Entity) {
+        (
+            TypeId::of::<TextureSlots>(),
+            Entity::from_raw_u32(n).unwrap(),
+        )
+    }
+
+    fn image(n: u128) -> AssetId<Image> {
+        AssetId::Uuid {
+            uuid: Uuid::from_u128(n),
+        }
+    }
+
+    #[test]
+    fn instances_share_the_slot_of_their_image() {
+        let mut slots = TextureSlots::default();
+
+        assert_eq!(slots.write(key(0), image(1)), TextureSlot(0));
+        assert_eq!(slots.write(key(1), image(2)), TextureSlot(1));
+        assert_eq!(slots.write(key(2), image(1)), TextureSlot(0));
+
+        // The slot of an image stays until its last instance moves on
+        assert_eq!(slots.write(key(0), image(2)), TextureSlot(1));
+        assert_eq!(slots.write(key(3), image(3)), TextureSlot(2));
+        assert_eq!(slots.write(key(2), image(2)), TextureSlot(1));
+        assert_eq!(slots.write(key(4), image(4)), TextureSlot(0));
+
+        for n in 5..MAX_TEXTURES as u32 + 2 {
+            slots.write(key(n), image(n as u128));
+        }
+        assert_eq!(slots.write(key(99), image(99)), TextureSlot::NONE);
+    }
+
+    #[test]
+    fn removed_instances_free_their_slot() {
+        let mut slots = TextureSlots::default();
+        for n in 0..MAX_TEXTURES as u32 {
+            slots.write(key(n), image(n as u128));
+        }
+        slots.write(key(99), image(0));
+
+        // Image 0 is still used by another instance
+        slots.remove(key(0));
+        assert_eq!(slots.write(key(100), image(100)), TextureSlot::NONE);
+
+        slots.remove(key(99));
+        slots.remove(key(5));
+        assert_eq!(slots.write(key(100), image(100)), TextureSlot(0));
+        assert_eq!(slots.write(key(101), image(101)), TextureSlot(5));
+    }
+
+    #[test]
+    fn instances_without_a_slot_wait_for_one() {
+        let mut slots = TextureSlots::default();
+        for n in 0..MAX_TEXTURES as u32 {
+            slots.write(key(n), image(n as u128));
+        }
+        assert_eq!(slots.write(key(100), image(100)), TextureSlot::NONE);
+        assert_eq!(slots.write(key(101), image(101)), TextureSlot::NONE);
+        assert!(slots.waiting(key(0).0).is_empty());
+
+        slots.remove(key(3));
+        let waiting = slots.waiting(key(0).0);
+        assert_eq!(waiting.len(), 2);
+        assert!(waiting.contains(&key(100).1) && waiting.contains(&key(101).1));
+        assert!(slots.waiting(TypeId::of::<u32>()).is_empty());
+
+        assert_eq!(slots.write(key(100), image(100)), TextureSlot(3));
+        assert!(slots.waiting(key(0).0).is_empty());
+        slots.remove(key(101));
+        slots.remove(key(4));
+        assert!(slots.waiting(key(0).0).is_empty());
+    }
+}
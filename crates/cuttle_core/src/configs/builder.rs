@@ -1,14 +1,26 @@
-use crate::bounding::{Bounding, make_compute_aabb_system};
+use crate::bounding::{
+    Bounding, BoundingValue, make_combine_bounds_system, make_compute_aabb_system,
+};
+use crate::components::dynamic::{
+    DynamicComponentDefinition, parse_definitions, read_asset_to_string, register_dynamic_component,
+};
 use crate::components::initialization::{
     Cuttle, init_component_render_data, init_global_render_data,
 };
+use crate::components::points::{CuttlePoints, PointsRange, init_component_points_data};
+use crate::components::textures::{CuttleTexture, TextureSlot, init_component_texture_data};
 use crate::components::{Sort, register_cuttle};
 use crate::configs::{CuttleConfig, initialize_config};
+use crate::cpu::{CpuEval, CuttleCpu, CuttleSample, init_cpu_sample};
 use crate::internal_prelude::*;
-use crate::prelude::{ComputeBounding, CuttleRenderData};
+use crate::prelude::CuttleRenderData;
 use crate::shader::{AddSnippet, FunctionName, Snippets};
+use bevy_asset::{AssetPath, Handle};
 use bevy_ecs::component::Mutable;
 use bevy_ecs::system::RunSystemOnce;
+use bevy_image::Image;
+use bevy_math::Vec2;
+use bevy_math::bounding::Aabb2d;
 use bevy_reflect::Typed;
 use convert_case::{Case, Casing};
 use std::marker::PhantomData;
@@ -149,11 +161,52 @@ impl<Config: CuttleConfig> CuttleConfigBuilder<'_, Config> {
         CuttleBuilder::new(self.app, self.config, None)
     }
 
-    pub fn affect_bounds<C: Component>(&mut self, set: Bounding, func: fn(&C) -> f32) -> &mut Self {
-        self.app.add_systems(
-            PostUpdate,
-            make_compute_aabb_system(func, set).in_set(ComputeBounding),
-        );
+    /// Registers a component declared through data, see [`dynamic`](crate::components::dynamic).
+    /// Entities use it through a [`DynamicCuttleComponent`](crate::components::dynamic::DynamicCuttleComponent).
+    pub fn dynamic_component(&mut self, definition: DynamicComponentDefinition) -> &mut Self {
+        register_dynamic_component(self.app, self.config, definition);
+        self
+    }
+
+    /// Registers each [`DynamicComponentDefinition`] of a RON list.
+    pub fn dynamic_components(&mut self, ron: &str) -> &mut Self {
+        let definitions = parse_definitions(ron)
+            .unwrap_or_else(|err| panic!("Invalid dynamic component definitions: {err}"));
+        for definition in definitions {
+            self.dynamic_component(definition);
+        }
+        self
+    }
+
+    /// Reads a RON list of [`DynamicComponentDefinition`]s from the assets.
+    /// The file is read once while building the app, changes to it are not hot reloaded
+    /// as the shader layout is fixed at startup.
+    pub fn dynamic_components_file<'p>(&mut self, path: impl Into<AssetPath<'p>>) -> &mut Self {
+        let path = path.into();
+        let ron = read_asset_to_string(self.app, &path)
+            .unwrap_or_else(|err| panic!("Failed to read dynamic components from {path}: {err}"));
+        self.dynamic_components(&ron)
+    }
+
+    pub fn affect_bounds<C: Component, V: BoundingValue>(
+        &mut self,
+        set: Bounding,
+        func: fn(&C) -> V,
+    ) -> &mut Self {
+        self.app
+            .add_systems(PostUpdate, make_compute_aabb_system(func, set).in_set(set));
+        self
+    }
+
+    /// Registers a custom combiner for the [`LocalBounds`](crate::bounding::LocalBounds)
+    /// of any entity with `C`, run as part of the given [`Bounding`] stage.
+    pub fn combine_bounds<C: Component>(
+        &mut self,
+        set: Bounding,
+        func: fn(&C, &mut Aabb2d),
+    ) -> &mut Self {
+        self.app
+            .add_systems(PostUpdate, make_combine_bounds_system(func).in_set(set));
         self
     }
 }
@@ -223,6 +276,52 @@ impl<'a, C: Component> CuttleBuilder<'a, C> {
         self
     }
 
+    /// Uploads the points of the component to the shared points buffer,
+    /// see [`points`](crate::components::points).
+    pub fn render_points_from<R>(&mut self) -> &mut Self
+    where
+        C: CuttlePoints,
+        for<'f> R: CuttleRenderData + From<(&'f C, PointsRange)>,
+    {
+        self.render_points_manual::<R>(C::points, |c: &C, range| (c, range).into())
+    }
+
+    pub fn render_points_manual<R: CuttleRenderData>(
+        &mut self,
+        points: fn(&C) -> &[Vec2],
+        to_render_data: fn(&C, PointsRange) -> R,
+    ) -> &mut Self {
+        assert!(
+            self.global.is_none(),
+            "Point data is only supported for components"
+        );
+        init_component_points_data::<C, R>(self.app, self.component, points, to_render_data);
+        self
+    }
+
+    /// Binds the image of the component to a texture slot,
+    /// see [`textures`](crate::components::textures).
+    pub fn render_texture_from<R>(&mut self) -> &mut Self
+    where
+        C: CuttleTexture,
+        for<'f> R: CuttleRenderData + From<(&'f C, TextureSlot)>,
+    {
+        self.render_texture_manual::<R>(C::texture, |c: &C, slot| (c, slot).into())
+    }
+
+    pub fn render_texture_manual<R: CuttleRenderData>(
+        &mut self,
+        texture: fn(&C) -> &Handle<Image>,
+        to_render_data: fn(&C, TextureSlot) -> R,
+    ) -> &mut Self {
+        assert!(
+            self.global.is_none(),
+            "Texture data is only supported for components"
+        );
+        init_component_texture_data::<C, R>(self.app, self.component, texture, to_render_data);
+        self
+    }
+
     pub fn sort(&mut self, sort: impl Into<u32>) -> &mut Self {
         self.insert(Sort(sort.into()))
     }
@@ -241,11 +340,34 @@ impl<'a, C: Component> CuttleBuilder<'a, C> {
         self
     }
 
-    pub fn affect_bounds(&mut self, set: Bounding, func: fn(&C) -> f32) -> &mut Self {
+    pub fn affect_bounds<V: BoundingValue>(
+        &mut self,
+        set: Bounding,
+        func: fn(&C) -> V,
+    ) -> &mut Self {
+        self.app
+            .add_systems(PostUpdate, make_compute_aabb_system(func, set).in_set(set));
+        self
+    }
+
+    pub fn combine_bounds(&mut self, set: Bounding, func: fn(&C, &mut Aabb2d)) -> &mut Self {
         self.app
-            .add_systems(PostUpdate, make_compute_aabb_system(func, set));
+            .add_systems(PostUpdate, make_combine_bounds_system(func).in_set(set));
         self
     }
+
+    /// Registers the [`CuttleCpu`] implementation of the component for the sample type `S`.
+    pub fn cpu<S: CuttleSample>(&mut self) -> &mut Self
+    where
+        C: CuttleCpu<S>,
+    {
+        self.cpu_with::<S>(C::eval)
+    }
+
+    pub fn cpu_with<S: CuttleSample>(&mut self, func: fn(&C, &mut S)) -> &mut Self {
+        init_cpu_sample::<S>(self.app);
+        self.insert(CpuEval::<S>::new::<C>(func))
+    }
 }
 
 pub trait CuttleGroupBuilderAppExt {
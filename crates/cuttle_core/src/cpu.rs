@@ -0,0 +1,363 @@
+use crate::bounding::GlobalBoundingBox;
+use crate::bounding::auto::auto_bounds;
+use crate::bounding::{ComputeGlobalBounding, check_visibility};
+use crate::components::{ConfigComponents, init_component_positions};
+use crate::configs::ConfigId;
+use crate::indices::CuttleIndices;
+use crate::internal_prelude::*;
+use crate::{FinishCuttleSetup, FinishCuttleSetupSet};
+use bevy_ecs::system::SystemParam;
+use bevy_ecs::world::EntityRef;
+use bevy_math::Vec2;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// The state a config's shader works on for a single pixel, mirrored on the CPU.
+///
+/// Components implement [`CuttleCpu`] for a sample type to take part in CPU evaluation,
+/// which is what e.g. [`AutoBounds`](crate::bounding::auto::AutoBounds) is built on.
+pub trait CuttleSample: Sized + Send + Sync + 'static {
+    /// Creates the state for evaluating a shape at `world_position`.
+    /// `size` mirrors the vertex size passed to the shader,
+    /// `config` is the config entity holding the config's globals.
+    fn new(world_position: Vec2, size: f32, config: EntityRef) -> Self;
+
+    /// Signed distance to the shape after all components were evaluated.
+    fn distance(&self) -> f32;
+
+    /// Whether a component used the gradient of the distance, which the shader gets from
+    /// screen space derivatives. The evaluator then estimates it and evaluates again.
+    fn needs_gradient(&self) -> bool {
+        false
+    }
+
+    /// Gives the sample the gradient of the shape's distance at its position,
+    /// before its components are evaluated.
+    fn set_gradient(&mut self, _gradient: Vec2) {}
+}
+
+/// Distance between the points the gradient of a shape is estimated from, in world units.
+const GRADIENT_STEP: f32 = 1e-2;
+
+/// CPU implementation of a component's shader function for the sample type `S`.
+pub trait CuttleCpu<S>: Component {
+    fn eval(&self, sample: &mut S);
+}
+
+/// Type erased [`CuttleCpu`] implementation, stored on the entity describing a component.
+#[derive(Component)]
+pub struct CpuEval<S>(Arc<dyn Fn(EntityRef, &mut S) + Send + Sync>);
+
+impl<S> Clone for CpuEval<S> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<S: CuttleSample> CpuEval<S> {
+    pub fn new<C: Component>(func: fn(&C, &mut S)) -> Self {
+        Self(Arc::new(move |entity: EntityRef, sample: &mut S| {
+            if let Some(component) = entity.get::<C>() {
+                func(component, sample);
+            }
+        }))
+    }
+}
+
+pub(crate) fn init_cpu_sample<S: CuttleSample>(app: &mut App) {
+    if app.world().contains_resource::<CpuEvaluators<S>>() {
+        return;
+    }
+
+    app.init_resource::<CpuEvaluators<S>>()
+        .add_systems(
+            FinishCuttleSetup,
+            build_cpu_evaluators::<S>
+                .in_set(FinishCuttleSetupSet::InitPositions)
+                .after(init_component_positions),
+        )
+        .add_systems(
+            PostUpdate,
+            auto_bounds::<S>
+                .in_set(ComputeGlobalBounding)
+                .after(crate::bounding::compute_global_bounds)
+                .before(check_visibility),
+        );
+}
+
+/// The [`CpuEval`]s of every config, indexed by [`ConfigId`] and component position.
+#[derive(Resource)]
+pub struct CpuEvaluators<S> {
+    configs: Vec<ConfigEvaluators<S>>,
+    marker: PhantomData<S>,
+}
+
+impl<S> Default for CpuEvaluators<S> {
+    fn default() -> Self {
+        Self {
+            configs: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+impl<S> CpuEvaluators<S> {
+    /// Evaluators of a single config, the one with [`ConfigId`] 0.
+    pub(crate) fn single(config: Entity, components: Vec<Option<CpuEval<S>>>) -> Self {
+        Self {
+            configs: vec![ConfigEvaluators { config, components }],
+            marker: PhantomData,
+        }
+    }
+}
+
+struct ConfigEvaluators<S> {
+    config: Entity,
+    components: Vec<Option<CpuEval<S>>>,
+}
+
+fn build_cpu_evaluators<S: CuttleSample>(
+    mut evaluators: ResMut<CpuEvaluators<S>>,
+    configs: Query<(Entity, &ConfigId, &ConfigComponents)>,
+    components: Query<Option<&CpuEval<S>>>,
+) {
+    let mut configs: Vec<_> = configs.iter().collect();
+    configs.sort_by_key(|(_, id, _)| id.0);
+
+    evaluators.configs = configs
+        .into_iter()
+        .map(|(config, _, comps)| ConfigEvaluators {
+            config,
+            components: comps
+                .iter()
+                .map(|&entity| components.get(entity).ok().flatten().cloned())
+                .collect(),
+        })
+        .collect();
+}
+
+/// Read only access to the entities a [`CuttleEvaluator`] works on.
+trait EntitySource {
+    fn entity(&self, entity: Entity) -> Option<EntityRef<'_>>;
+}
+
+impl EntitySource for World {
+    fn entity(&self, entity: Entity) -> Option<EntityRef<'_>> {
+        self.get_entity(entity).ok()
+    }
+}
+
+impl EntitySource for Query<'_, '_, EntityRef<'_>> {
+    fn entity(&self, entity: Entity) -> Option<EntityRef<'_>> {
+        self.get(entity).ok()
+    }
+}
+
+/// System param for evaluating shapes on the CPU, see [`CuttleEvaluator`].
+///
+/// Reads every component, so it conflicts with mutable component access in the same system.
+#[derive(SystemParam)]
+pub struct CuttleCpuQuery<'w, 's, S: CuttleSample> {
+    entities: Query<'w, 's, EntityRef<'static>>,
+    evaluators: Option<Res<'w, CpuEvaluators<S>>>,
+}
+
+impl<S: CuttleSample> CuttleCpuQuery<'_, '_, S> {
+    /// Returns `None` if no component was registered with a CPU implementation for `S`.
+    pub fn evaluator(&self) -> Option<CuttleEvaluator<'_, S>> {
+        Some(CuttleEvaluator {
+            entities: &self.entities,
+            evaluators: self.evaluators.as_deref()?,
+        })
+    }
+
+    /// See [`CuttleEvaluator::sample`].
+    pub fn sample(&self, entity: Entity, world_position: Vec2) -> Option<S> {
+        self.evaluator()?.sample(entity, world_position)
+    }
+
+    /// See [`CuttleEvaluator::distance`].
+    pub fn distance(&self, entity: Entity, world_position: Vec2) -> Option<f32> {
+        self.evaluator()?.distance(entity, world_position)
+    }
+}
+
+/// Evaluates shapes on the CPU by running the [`CuttleCpu`] implementations of their
+/// components in the same order the generated shader runs them in.
+pub struct CuttleEvaluator<'w, S> {
+    entities: &'w dyn EntitySource,
+    evaluators: &'w CpuEvaluators<S>,
+}
+
+impl<'w, S: CuttleSample> CuttleEvaluator<'w, S> {
+    /// Returns `None` if no component was registered with a CPU implementation for `S`.
+    pub fn new(world: &'w World) -> Option<Self> {
+        Some(Self {
+            entities: world,
+            evaluators: world.get_resource::<CpuEvaluators<S>>()?,
+        })
+    }
+
+    /// Whether every component of the shape rooted at `entity` has a CPU implementation.
+    pub fn supports(&self, entity: Entity) -> bool {
+        let Some(indices) = self
+            .entities
+            .entity(entity)
+            .and_then(|e| e.get::<CuttleIndices>())
+        else {
+            return false;
+        };
+        let Some(config) = self.evaluators.configs.get(indices.group_id) else {
+            return false;
+        };
+        indices.keys().all(|index| {
+            config
+                .components
+                .get(index.component_id as usize)
+                .is_some_and(Option::is_some)
+        })
+    }
+
+    /// Evaluates the shape rooted at `entity` at `world_position`,
+    /// returning the sample after all components ran.
+    /// Components without a CPU implementation are skipped.
+    pub fn sample(&self, entity: Entity, world_position: Vec2) -> Option<S> {
+        let sample = self.sample_with_gradient(entity, world_position, None)?;
+        if !sample.needs_gradient() {
+            return Some(sample);
+        }
+
+        // Central differences of the distance, as derivatives aren't available on the CPU
+        let distance = |offset: Vec2| {
+            self.sample_with_gradient(entity, world_position + offset, None)
+                .map(|sample| sample.distance())
+        };
+        let dx = Vec2::X * GRADIENT_STEP;
+        let dy = Vec2::Y * GRADIENT_STEP;
+        let gradient = Vec2::new(
+            distance(dx)? - distance(-dx)?,
+            distance(dy)? - distance(-dy)?,
+        ) / (2. * GRADIENT_STEP);
+        self.sample_with_gradient(entity, world_position, Some(gradient))
+    }
+
+    fn sample_with_gradient(
+        &self,
+        entity: Entity,
+        world_position: Vec2,
+        gradient: Option<Vec2>,
+    ) -> Option<S> {
+        let root = self.entities.entity(entity)?;
+        let indices = root.get::<CuttleIndices>()?;
+        let config = self.evaluators.configs.get(indices.group_id)?;
+        let size = root
+            .get::<GlobalBoundingBox>()
+            .map(|bounds| bounds.half_size.max_element())
+            .unwrap_or_default();
+
+        let mut sample = S::new(world_position, size, self.entities.entity(config.config)?);
+        if let Some(gradient) = gradient {
+            sample.set_gradient(gradient);
+        }
+        for (index, source) in indices.sources.iter() {
+            let Some(Some(eval)) = config.components.get(index.component_id as usize) else {
+                continue;
+            };
+            let Some(source) = self.entities.entity(*source) else {
+                continue;
+            };
+            (eval.0)(source, &mut sample);
+        }
+        Some(sample)
+    }
+
+    /// Signed distance of the shape rooted at `entity` at `world_position`.
+    pub fn distance(&self, entity: Entity, world_position: Vec2) -> Option<f32> {
+        self.sample(entity, world_position)
+            .map(|sample| sample.distance())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CpuEval, CpuEvaluators, CuttleEvaluator, CuttleSample};
+    use crate::indices::{CuttleIndex, CuttleIndices};
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::world::EntityRef;
+    use bevy_math::Vec2;
+
+    #[derive(Default)]
+    struct Sample {
+        position: Vec2,
+        distance: f32,
+        gradient: Option<Vec2>,
+        needs_gradient: bool,
+    }
+
+    impl CuttleSample for Sample {
+        fn new(world_position: Vec2, _: f32, _: EntityRef) -> Self {
+            Self {
+                position: world_position,
+                ..Default::default()
+            }
+        }
+
+        fn distance(&self) -> f32 {
+            self.distance
+        }
+
+        fn needs_gradient(&self) -> bool {
+            self.needs_gradient
+        }
+
+        fn set_gradient(&mut self, gradient: Vec2) {
+            self.gradient = Some(gradient);
+        }
+    }
+
+    #[derive(Component)]
+    struct Slope;
+
+    #[derive(Component)]
+    struct UsesGradient;
+
+    fn evaluate(components: Vec<Option<CpuEval<Sample>>>, at: Vec2) -> Sample {
+        let mut world = World::new();
+        let config = world.spawn_empty().id();
+        let root = world.spawn((Slope, UsesGradient)).id();
+        let mut indices = CuttleIndices::default();
+        for component_id in 0..components.len() as u8 {
+            let index = CuttleIndex {
+                extension_index: 0,
+                component_id,
+            };
+            indices.indices.insert(index, 0);
+            indices.sources.insert(index, root);
+        }
+        world.entity_mut(root).insert(indices);
+        world.insert_resource(CpuEvaluators::single(config, components));
+
+        let evaluator = CuttleEvaluator::<Sample>::new(&world).unwrap();
+        evaluator.sample(root, at).unwrap()
+    }
+
+    #[test]
+    fn gradient_is_estimated_when_used() {
+        let slope = || {
+            Some(CpuEval::new(|_: &Slope, s: &mut Sample| {
+                s.distance = s.position.x * 2. - s.position.y;
+            }))
+        };
+        let uses_gradient = Some(CpuEval::new(|_: &UsesGradient, s: &mut Sample| {
+            s.needs_gradient = true;
+        }));
+
+        let sample = evaluate(vec![slope(), uses_gradient], Vec2::new(3., 1.));
+        assert_eq!(sample.distance, 5.);
+        let gradient = sample.gradient.unwrap();
+        assert!(gradient.abs_diff_eq(Vec2::new(2., -1.), 1e-3), "{gradient}");
+
+        assert!(evaluate(vec![slope()], Vec2::ONE).gradient.is_none());
+    }
+}
@@ -1,8 +1,9 @@
-use crate::bounding::{BoundingRadius, ComputeGlobalBounding, GlobalBoundingCircle};
+use crate::bounding::{ComputeGlobalBounding, GlobalBoundingBox, LocalBounds};
 use crate::internal_prelude::*;
 use bevy_color::Srgba;
 use bevy_gizmos::prelude::Gizmos;
-use bevy_math::Vec3Swizzles;
+use bevy_math::bounding::BoundingVolume;
+use bevy_math::{EulerRot, Isometry2d, Rot2, Vec3Swizzles};
 
 #[derive(Resource, Reflect, Default, Clone, Copy)]
 #[reflect(Resource)]
@@ -26,14 +27,22 @@ impl Plugin for CuttleDebugPlugin {
     }
 }
 
-fn combined_bounds(mut gizmos: Gizmos, query: Query<&GlobalBoundingCircle>) {
+fn combined_bounds(mut gizmos: Gizmos, query: Query<&GlobalBoundingBox>) {
     for bounding in &query {
-        gizmos.circle_2d(bounding.center, bounding.circle.radius, Srgba::RED);
+        gizmos.rect_2d(bounding.isometry, bounding.half_size * 2., Srgba::RED);
     }
 }
 
-fn local_bounds(mut gizmos: Gizmos, query: Query<(&GlobalTransform, &BoundingRadius)>) {
-    for (transform, radius) in &query {
-        gizmos.circle_2d(transform.translation().xy(), **radius, Srgba::GREEN);
+fn local_bounds(mut gizmos: Gizmos, query: Query<(&GlobalTransform, &LocalBounds)>) {
+    for (transform, bounds) in &query {
+        let (scale, rotation, translation) = transform.to_scale_rotation_translation();
+        let rotation = Rot2::radians(rotation.to_euler(EulerRot::ZYX).0);
+        let center = translation.xy() + rotation * (bounds.center() * scale.xy());
+        let isometry = Isometry2d::new(center, rotation);
+        gizmos.rect_2d(
+            isometry,
+            bounds.half_size() * scale.xy().abs() * 2.,
+            Srgba::GREEN,
+        );
     }
 }
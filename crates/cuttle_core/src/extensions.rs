@@ -1,9 +1,11 @@
-use crate::bounding::BoundingRadius;
+use crate::bounding::LocalBounds;
 use crate::indices::set_flag_indices;
 use crate::pipeline::{specialization::CuttlePipeline, CuttleRenderSet};
 use bevy_app::prelude::*;
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::prelude::*;
+use bevy_log::warn;
+use bevy_platform::collections::HashSet;
 use bevy_reflect::prelude::*;
 use bevy_render::{
     render_resource::{BindGroup, BindGroupEntries, StorageBuffer}, renderer::{RenderDevice, RenderQueue},
@@ -15,10 +17,15 @@ use std::fmt::Debug;
 pub fn plugin(app: &mut App) {
     app.register_type::<Extends>()
         .register_type::<ExtendedBy>()
-        .add_systems(PostUpdate, set_extension_index.before(set_flag_indices));
+        .add_systems(
+            PostUpdate,
+            set_extension_index
+                .in_set(SetExtensionIndex)
+                .before(set_flag_indices),
+        );
 
     app.world_mut()
-        .register_required_components::<Extends, BoundingRadius>();
+        .register_required_components::<Extends, LocalBounds>();
     app.sub_app_mut(RenderApp)
         .init_resource::<CompIndicesBuffer>()
         .init_resource::<CompIndicesBindGroup>()
@@ -35,16 +42,51 @@ pub fn plugin(app: &mut App) {
 #[reflect(Component)]
 pub struct Extends(pub Entity);
 
-#[derive(Debug, Default, Clone, Copy, Reflect, Component)]
+#[derive(Debug, Default, Clone, Copy, Reflect, Component, Deref)]
 #[reflect(Component)]
 pub struct ExtensionIndex(pub(crate) u8);
 
-fn set_extension_index(
+/// Extension index of components drawn after all extensions, like fills and strokes,
+/// through an `extension_index_override`. Extensions use the indices below it.
+pub const MAX_EXTENSION_INDEX: u8 = u8::MAX;
+
+/// Numbers new extensions in `PostUpdate`,
+/// systems spawning extensions there have to run before it.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, SystemSet)]
+pub struct SetExtensionIndex;
+
+/// New extensions take the lowest indices no other extension of the root holds, in the order
+/// they were added. Extensions are evaluated by index, so one added after others were removed
+/// can take the place of a removed one instead of coming last.
+pub(crate) fn set_extension_index(
     roots: Query<&ExtendedBy>,
-    mut leafs: Query<(&Extends, &mut ExtensionIndex), Added<Extends>>,
+    added: Query<&Extends, Added<Extends>>,
+    mut leafs: Query<(Ref<Extends>, &mut ExtensionIndex)>,
 ) -> Result<()> {
-    for (extension, mut target) in &mut leafs {
-        target.0 = roots.get(extension.0)?.len() as u8;
+    let targets: HashSet<Entity> = added.iter().map(|extends| extends.0).collect();
+    for target in targets {
+        let extensions = roots.get(target)?;
+        let taken: HashSet<u8> = extensions
+            .iter()
+            .filter_map(|entity| leafs.get(entity).ok())
+            .filter(|(extends, _)| !extends.is_added())
+            .map(|(_, index)| index.0)
+            .collect();
+        let mut free = (1..MAX_EXTENSION_INDEX).filter(|index| !taken.contains(index));
+        for entity in extensions.iter() {
+            if let Ok((extends, mut index)) = leafs.get_mut(entity)
+                && extends.is_added()
+            {
+                let Some(next) = free.next() else {
+                    warn!(
+                        "{target} has more than {} extensions, {entity} is not drawn",
+                        MAX_EXTENSION_INDEX - 1
+                    );
+                    continue;
+                };
+                index.0 = next;
+            }
+        }
     }
     Ok(())
 }
@@ -74,3 +116,56 @@ fn build_component_indices_bind_group(
     let bind_group = device.create_bind_group("cuttle indices", &pipeline.op_layout, &entries);
     op_bind_group.0 = Some(bind_group);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Extends, ExtensionIndex, MAX_EXTENSION_INDEX, set_extension_index};
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::system::SystemId;
+
+    #[test]
+    fn extension_indices_follow_the_root() {
+        let mut world = World::new();
+        let system: SystemId<(), Result<()>> = world.register_system(set_extension_index);
+        let run = |world: &mut World| world.run_system(system).unwrap().unwrap();
+        let index = |world: &World, entity| world.get::<ExtensionIndex>(entity).unwrap().0;
+
+        let root = world.spawn_empty().id();
+        let extensions: Vec<_> = (0..3).map(|_| world.spawn(Extends(root)).id()).collect();
+        run(&mut world);
+        let indices: Vec<_> = extensions.iter().map(|&e| index(&world, e)).collect();
+        assert_eq!(indices, vec![1, 2, 3]);
+
+        // Added after a removal, the new extension takes the free index
+        world.despawn(extensions[1]);
+        let added = world.spawn(Extends(root)).id();
+        run(&mut world);
+        assert_eq!(index(&world, added), 2);
+        assert_eq!(index(&world, extensions[2]), 3);
+
+        // Replacing the middle extension over and over keeps reusing its index
+        let mut middle = added;
+        for _ in 0..300 {
+            world.despawn(middle);
+            middle = world.spawn(Extends(root)).id();
+            run(&mut world);
+            assert_eq!(index(&world, middle), 2);
+        }
+    }
+
+    #[test]
+    fn extensions_beyond_the_last_index_are_left_out() {
+        let mut world = World::new();
+        let system: SystemId<(), Result<()>> = world.register_system(set_extension_index);
+        let root = world.spawn_empty().id();
+        let extensions: Vec<_> = (0..MAX_EXTENSION_INDEX as usize + 1)
+            .map(|_| world.spawn(Extends(root)).id())
+            .collect();
+        world.run_system(system).unwrap().unwrap();
+
+        let index = |entity| world.get::<ExtensionIndex>(entity).unwrap().0;
+        assert_eq!(index(extensions[253]), MAX_EXTENSION_INDEX - 1);
+        assert_eq!(index(extensions[254]), 0);
+        assert_eq!(index(extensions[255]), 0);
+    }
+}
@@ -1,5 +1,5 @@
-use crate::bounding::BoundingRadius;
-use crate::bounding::GlobalBoundingCircle;
+use crate::bounding::LocalBounds;
+use crate::bounding::{GlobalBoundingBox, GlobalBoundingCircle};
 use crate::components::CuttleComponent;
 use crate::components::arena::IndexArena;
 use crate::components::{ExtensionIndexOverride, Positions};
@@ -10,6 +10,9 @@ use crate::pipeline::extract::CuttleZ;
 use crate::prelude::ComputeBounding;
 use crate::prelude::Extends;
 use bevy_camera::visibility::Visibility;
+use bevy_ecs::archetype::Archetypes;
+use bevy_ecs::component::{ComponentId, Components};
+use bevy_ecs::entity::Entities;
 use bevy_ecs::lifecycle::HookContext;
 use bevy_ecs::prelude::*;
 use bevy_ecs::query::QueryEntityError;
@@ -28,7 +31,8 @@ pub(super) fn plugin(app: &mut App) {
 #[require(
     Visibility,
     ExtendedBy,
-    BoundingRadius,
+    LocalBounds,
+    GlobalBoundingBox,
     GlobalBoundingCircle,
     CuttleZ,
     SyncToRenderWorld
@@ -37,6 +41,9 @@ pub(super) fn plugin(app: &mut App) {
 pub struct CuttleIndices {
     #[deref]
     pub(crate) indices: BTreeMap<CuttleIndex, u32>,
+    /// The entity holding the component behind each index, the root or one of its extensions
+    #[reflect(ignore)]
+    pub(crate) sources: BTreeMap<CuttleIndex, Entity>,
     pub(crate) group_id: usize,
 }
 
@@ -54,6 +61,28 @@ impl CuttleIndices {
     pub fn group_id(&self) -> usize {
         self.group_id
     }
+
+    /// Drops the indices of the component at `position` held by `source`.
+    pub(crate) fn remove_source(&mut self, position: u8, source: Entity) {
+        let removed: Vec<_> = self
+            .sources
+            .iter()
+            .filter(|&(index, &entity)| index.component_id == position && entity == source)
+            .map(|(&index, _)| index)
+            .collect();
+        for index in removed {
+            self.indices.remove(&index);
+            self.sources.remove(&index);
+        }
+    }
+
+    /// Positions of the shape's components in its config's [`ConfigComponents`](crate::components::ConfigComponents),
+    /// together with the entity holding each, in the order the shader evaluates them.
+    pub fn components(&self) -> impl Iterator<Item = (u8, Entity)> + '_ {
+        self.sources
+            .iter()
+            .map(|(index, &entity)| (index.component_id, entity))
+    }
 }
 
 pub fn on_add_config_marker_initialize_indices_config_id<G: CuttleConfig>(
@@ -112,12 +141,23 @@ pub fn set_flag_indices(
     component_meta: Query<(&Positions, Option<&ExtensionIndexOverride>)>,
     extensions: Query<(&Extends, &ExtensionIndex)>,
     mut indices: Query<&mut CuttleIndices>,
+    entities: &Entities,
+    archetypes: &Archetypes,
 ) -> Result<()> {
     for message in messages.read() {
+        // Removed again in the same frame, which already cleared its indices
+        let present = entities.get(message.added_to).is_some_and(|location| {
+            archetypes[location.archetype_id].contains(message.component_id)
+        });
+        if !present {
+            continue;
+        }
         let (positions, extension_index_override) = component_meta
             .get(message.component)
             .inspect_err(|err| println!("INDICES: {err}"))?;
         let (entity, extension_index) = match extensions.get(message.added_to) {
+            // Not numbered, the root has no free extension index left
+            Ok((_, &ExtensionIndex(0))) => continue,
             Ok((&Extends(target), &ExtensionIndex(index))) => (target, index),
             Err(QueryEntityError::QueryDoesNotMatch(ent, _)) => (ent, 0),
             _ => panic!("NO ENTITY"),
@@ -137,6 +177,7 @@ pub fn set_flag_indices(
                 .unwrap_or(extension_index),
         };
         flags.indices.insert(index, message.index);
+        flags.sources.insert(index, message.added_to);
     }
     Ok(())
 }
@@ -144,24 +185,60 @@ pub fn set_flag_indices(
 #[derive(Debug, Message, Reflect)]
 pub struct AddCuttleComponent {
     component: Entity,
+    component_id: ComponentId,
     added_to: Entity,
     index: u32,
 }
 
+impl AddCuttleComponent {
+    pub(crate) fn new(
+        component: Entity,
+        component_id: ComponentId,
+        added_to: Entity,
+        index: u32,
+    ) -> Self {
+        Self {
+            component,
+            component_id,
+            added_to,
+            index,
+        }
+    }
+}
+
 pub(crate) fn added_cuttle_component<C: Component>(
     add: On<Add, C>,
     indices: Query<&CuttleComponentIndex<C>>,
     component_meta: Single<Entity, With<CuttleComponent<C>>>,
+    components: &Components,
     mut events: MessageWriter<AddCuttleComponent>,
 ) {
     let index = indices.get(add.entity).map(|i| i.index).unwrap_or(u32::MAX);
     events.write(AddCuttleComponent {
         component: component_meta.into_inner(),
+        component_id: components.component_id::<C>().unwrap(),
         added_to: add.entity,
         index,
     });
 }
 
+/// Drops the indices of a removed component from its shape,
+/// which includes every component of a despawned extension.
+pub(crate) fn removed_cuttle_component<C: Component>(
+    remove: On<Remove, C>,
+    component_meta: Single<&Positions, With<CuttleComponent<C>>>,
+    extensions: Query<&Extends>,
+    mut indices: Query<&mut CuttleIndices>,
+) {
+    let root = extensions.get(remove.entity).map_or(remove.entity, |e| e.0);
+    let Ok(mut flags) = indices.get_mut(root) else {
+        return;
+    };
+    if let Some(Some(position)) = component_meta.get(flags.group_id).copied() {
+        flags.remove_source(position, remove.entity);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::indices::{CuttleIndex, CuttleIndices};
@@ -8,23 +8,41 @@ use internal_prelude::*;
 use pipeline::PipelinePlugin;
 use shader::ShaderPlugin;
 
+pub mod bake;
 pub mod bounding;
+pub mod cache;
 pub mod components;
 pub mod configs;
+pub mod cpu;
 pub mod debug;
 pub mod extensions;
 pub mod indices;
+#[cfg(feature = "picking")]
+pub mod picking;
 pub mod pipeline;
 pub mod shader;
+pub mod shape;
+pub mod spatial;
 
 pub mod prelude {
+    pub use crate::bake::RenderToImage;
+    pub use crate::bounding::auto::AutoBounds;
     pub use crate::bounding::*;
+    pub use crate::cache::CuttleCache;
+    pub use crate::components::dynamic::layout::DynamicValue;
+    pub use crate::components::dynamic::{DynamicComponentDefinition, DynamicCuttleComponent};
     pub use crate::components::initialization::{Cuttle, CuttleRenderData};
+    pub use crate::components::points::{CuttlePoints, PointsRange};
+    pub use crate::components::textures::{CuttleTexture, TextureSlot};
     pub use crate::configs::builder::CuttleGroupBuilderAppExt;
     pub use crate::configs::CuttleConfig;
+    pub use crate::cpu::{CuttleCpu, CuttleCpuQuery, CuttleSample};
     pub use crate::extensions::ExtendedBy;
     pub use crate::extensions::Extends;
+    pub use crate::extensions::SetExtensionIndex;
     pub use crate::pipeline::extract::CuttleZ;
+    pub use crate::shape::{CuttleShape, CuttleShapeRoot};
+    pub use crate::spatial::{CuttleSpatialQuery, RayHit};
     pub use crate::CuttleCorePlugin;
 }
 
@@ -43,11 +61,15 @@ impl Plugin for CuttleCorePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins((
             ShaderPlugin,
+            bake::plugin,
+            cache::plugin,
             CompPlugin,
             PipelinePlugin,
             extensions::plugin,
             bounding::plugin,
             indices::plugin,
+            spatial::plugin,
+            shape::plugin,
         ));
         use FinishCuttleSetupSet::*;
         app.configure_sets(
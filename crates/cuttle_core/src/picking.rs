@@ -0,0 +1,359 @@
+//! A [`bevy_picking`] backend for cuttle shapes.
+//!
+//! Shapes are first tested against their [`GlobalBoundingCircle`],
+//! then against the signed distance of the composed shape,
+//! evaluated with the [`CuttleCpu`](crate::cpu::CuttleCpu) implementations of its components.
+//! Shapes with components lacking a CPU implementation fall back to their [`GlobalBoundingBox`].
+//!
+//! Hits are reported on the plane at the shape's [`CuttleZ`],
+//! with the normal pointing towards positive z.
+
+use crate::bounding::{GlobalBoundingBox, GlobalBoundingCircle};
+use crate::cpu::{CuttleCpuQuery, CuttleEvaluator, CuttleSample};
+use crate::indices::CuttleIndices;
+use crate::internal_prelude::*;
+use crate::pipeline::extract::CuttleZ;
+use bevy_camera::Camera;
+use bevy_camera::visibility::ViewVisibility;
+use bevy_math::primitives::InfinitePlane3d;
+use bevy_math::{Ray3d, Vec3, Vec3Swizzles};
+use bevy_picking::backend::prelude::*;
+use bevy_window::PrimaryWindow;
+use std::marker::PhantomData;
+
+/// Marks cameras used by the [`CuttlePickingPlugin`] when
+/// [`CuttlePickingSettings::require_markers`] is set.
+#[derive(Debug, Clone, Default, Component, Reflect)]
+#[reflect(Component, Default)]
+pub struct CuttlePickingCamera;
+
+#[derive(Debug, Default, Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct CuttlePickingSettings {
+    /// Only consider cameras marked with [`CuttlePickingCamera`]
+    pub require_markers: bool,
+}
+
+/// Picking backend for the shapes of the sample type `S`,
+/// add it together with bevy's picking plugins.
+pub struct CuttlePickingPlugin<S>(PhantomData<fn() -> S>);
+
+impl<S> Default for CuttlePickingPlugin<S> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<S: CuttleSample> Plugin for CuttlePickingPlugin<S> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CuttlePickingSettings>()
+            .register_type::<(CuttlePickingSettings, CuttlePickingCamera)>()
+            .add_systems(
+                PreUpdate,
+                cuttle_picking::<S>.in_set(PickingSystems::Backend),
+            );
+    }
+}
+
+fn cuttle_picking<S: CuttleSample>(
+    pointers: Query<(&PointerId, &PointerLocation)>,
+    cameras: Query<(Entity, &Camera, &GlobalTransform, Has<CuttlePickingCamera>)>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    settings: Res<CuttlePickingSettings>,
+    shapes: Query<
+        (
+            Entity,
+            &CuttleZ,
+            &GlobalBoundingCircle,
+            &GlobalBoundingBox,
+            &ViewVisibility,
+            Option<&Pickable>,
+        ),
+        With<CuttleIndices>,
+    >,
+    cpu: CuttleCpuQuery<S>,
+    mut hits: MessageWriter<PointerHits>,
+) {
+    let evaluator = cpu.evaluator();
+    let mut sorted_shapes: Vec<PickShape> = shapes
+        .iter()
+        .filter(|(.., visibility, _)| visibility.get())
+        .map(|(entity, z, circle, bounding, _, pickable)| (entity, z, circle, bounding, pickable))
+        .collect();
+    sort_from_top(&mut sorted_shapes);
+
+    let primary_window = primary_window.single().ok();
+
+    for (pointer, location) in pointers
+        .iter()
+        .filter_map(|(pointer, location)| Some((pointer, location.location()?)))
+    {
+        let Some((camera_entity, camera, camera_transform, _)) = cameras
+            .iter()
+            .filter(|(_, camera, _, marker)| {
+                camera.is_active && (*marker || !settings.require_markers)
+            })
+            .find(|(_, camera, ..)| {
+                camera
+                    .target
+                    .normalize(primary_window)
+                    .is_some_and(|target| target == location.target)
+            })
+        else {
+            continue;
+        };
+
+        if let Some(viewport) = camera.logical_viewport_rect()
+            && !viewport.contains(location.position)
+        {
+            continue;
+        }
+
+        let Ok(ray) = camera.viewport_to_world(camera_transform, location.position) else {
+            continue;
+        };
+
+        let picks = pick_shapes(ray, &sorted_shapes, evaluator.as_ref(), camera_entity);
+
+        hits.write(PointerHits::new(*pointer, picks, camera.order as f32));
+    }
+}
+
+/// The parts of a shape a ray is tested against.
+type PickShape<'a> = (
+    Entity,
+    &'a CuttleZ,
+    &'a GlobalBoundingCircle,
+    &'a GlobalBoundingBox,
+    Option<&'a Pickable>,
+);
+
+/// Sorts shapes from the highest [`CuttleZ`], the one drawn on top, to the lowest.
+fn sort_from_top(shapes: &mut [PickShape]) {
+    shapes.sort_by(|(_, a, ..), (_, b, ..)| b.0.total_cmp(&a.0));
+}
+
+/// Hits of `ray` on `shapes`, sorted with [`sort_from_top`],
+/// up to the first one that blocks lower shapes.
+fn pick_shapes<S: CuttleSample>(
+    ray: Ray3d,
+    shapes: &[PickShape],
+    evaluator: Option<&CuttleEvaluator<S>>,
+    camera: Entity,
+) -> Vec<(Entity, HitData)> {
+    let mut blocked = false;
+    shapes
+        .iter()
+        .filter_map(|&(entity, &CuttleZ(z), circle, bounding, pickable)| {
+            if blocked {
+                return None;
+            }
+
+            let depth = ray.intersect_plane(Vec3::Z * z, InfinitePlane3d::new(Vec3::Z))?;
+            let position = ray.get_point(depth);
+            let point = position.xy();
+
+            if circle.center.distance_squared(point) > circle.radius() * circle.radius() {
+                return None;
+            }
+
+            let hit = match evaluator {
+                Some(evaluator) if evaluator.supports(entity) => evaluator
+                    .distance(entity, point)
+                    .is_some_and(|distance| distance <= 0.),
+                _ => {
+                    let local = bounding.isometry.inverse().transform_point(point);
+                    local.abs().cmple(bounding.half_size).all()
+                }
+            };
+            if !hit {
+                return None;
+            }
+
+            blocked = pickable.is_none_or(|pickable| pickable.should_block_lower);
+            let hit = HitData::new(camera, depth, Some(position), Some(Vec3::Z));
+            Some((entity, hit))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PickShape, pick_shapes, sort_from_top};
+    use crate::bounding::{GlobalBoundingBox, GlobalBoundingCircle};
+    use crate::cpu::{CpuEval, CpuEvaluators, CuttleEvaluator, CuttleSample};
+    use crate::indices::{CuttleIndex, CuttleIndices};
+    use crate::pipeline::extract::CuttleZ;
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::world::EntityRef;
+    use bevy_math::bounding::BoundingCircle;
+    use bevy_math::{Dir3, Isometry2d, Ray3d, Rot2, Vec2};
+    use bevy_picking::Pickable;
+
+    struct Sample {
+        position: Vec2,
+        distance: f32,
+    }
+
+    impl CuttleSample for Sample {
+        fn new(world_position: Vec2, _: f32, _: EntityRef) -> Self {
+            Self {
+                position: world_position,
+                distance: f32::INFINITY,
+            }
+        }
+
+        fn distance(&self) -> f32 {
+            self.distance
+        }
+    }
+
+    #[derive(Component)]
+    struct Disk(f32);
+
+    /// Has no CPU implementation
+    #[derive(Component)]
+    struct GpuOnly;
+
+    const DISK: u8 = 0;
+    const GPU_ONLY: u8 = 1;
+
+    struct Shape {
+        entity: Entity,
+        z: CuttleZ,
+        circle: GlobalBoundingCircle,
+        bounding: GlobalBoundingBox,
+        pickable: Option<Pickable>,
+    }
+
+    fn world() -> World {
+        let mut world = World::new();
+        let config = world.spawn_empty().id();
+        let disk = CpuEval::new(|&Disk(radius): &Disk, s: &mut Sample| {
+            s.distance = s.position.length() - radius;
+        });
+        world.insert_resource(CpuEvaluators::single(config, vec![Some(disk), None]));
+        world
+    }
+
+    /// A shape around the origin evaluated through the components `component_ids`,
+    /// bounded by a circle of `radius` and `bounding`.
+    fn shape(
+        world: &mut World,
+        component_ids: &[u8],
+        z: f32,
+        radius: f32,
+        bounding: GlobalBoundingBox,
+    ) -> Shape {
+        let entity = world.spawn((Disk(2.), GpuOnly)).id();
+        let mut indices = CuttleIndices::default();
+        for &component_id in component_ids {
+            let index = CuttleIndex {
+                extension_index: 0,
+                component_id,
+            };
+            indices.indices.insert(index, 0);
+            indices.sources.insert(index, entity);
+        }
+        world.entity_mut(entity).insert(indices);
+
+        let mut circle = GlobalBoundingCircle::default();
+        *circle = BoundingCircle::new(Vec2::ZERO, radius);
+        Shape {
+            entity,
+            z: CuttleZ(z),
+            circle,
+            bounding,
+            pickable: None,
+        }
+    }
+
+    fn bounds(half_size: Vec2) -> GlobalBoundingBox {
+        GlobalBoundingBox {
+            isometry: Isometry2d::IDENTITY,
+            half_size,
+        }
+    }
+
+    /// Entities hit by a ray looking down at `point`, with the depth of the hit.
+    fn pick(world: &World, shapes: &[Shape], point: Vec2) -> Vec<(Entity, f32)> {
+        let mut shapes: Vec<PickShape> = shapes
+            .iter()
+            .map(|s| (s.entity, &s.z, &s.circle, &s.bounding, s.pickable.as_ref()))
+            .collect();
+        sort_from_top(&mut shapes);
+        let evaluator = CuttleEvaluator::<Sample>::new(world);
+        let ray = Ray3d::new(point.extend(10.), Dir3::NEG_Z);
+        pick_shapes(ray, &shapes, evaluator.as_ref(), Entity::PLACEHOLDER)
+            .into_iter()
+            .map(|(entity, hit)| (entity, hit.depth))
+            .collect()
+    }
+
+    #[test]
+    fn bounding_circle_rejects_first() {
+        let mut world = world();
+        // The disk reaches further than its bounding circle
+        let disk = shape(&mut world, &[DISK], 0., 1., bounds(Vec2::splat(2.)));
+        let shapes = [disk];
+
+        assert_eq!(pick(&world, &shapes, Vec2::new(0.5, 0.)).len(), 1);
+        assert!(pick(&world, &shapes, Vec2::new(1.5, 0.)).is_empty());
+    }
+
+    #[test]
+    fn cpu_distance_with_box_fallback() {
+        let mut world = world();
+        let corner = Vec2::splat(1.8);
+
+        // Inside the box, outside the disk
+        let disk = shape(&mut world, &[DISK], 0., 3., bounds(Vec2::splat(2.)));
+        assert!(pick(&world, &[disk], corner).is_empty());
+
+        let mixed = shape(
+            &mut world,
+            &[DISK, GPU_ONLY],
+            0.,
+            3.,
+            bounds(Vec2::splat(2.)),
+        );
+        let entity = mixed.entity;
+        assert_eq!(pick(&world, &[mixed], corner), vec![(entity, 10.)]);
+
+        // The box is oriented
+        let rotated = GlobalBoundingBox {
+            isometry: Isometry2d::from_rotation(Rot2::degrees(90.)),
+            half_size: Vec2::new(3., 0.5),
+        };
+        let shapes = [shape(&mut world, &[GPU_ONLY], 0., 3.1, rotated)];
+        assert_eq!(pick(&world, &shapes, Vec2::new(0., 2.5)).len(), 1);
+        assert!(pick(&world, &shapes, Vec2::new(2.5, 0.)).is_empty());
+    }
+
+    #[test]
+    fn highest_shape_blocks_lower_ones() {
+        let mut world = world();
+        let mut shapes =
+            [0., 2., 1.].map(|z| shape(&mut world, &[DISK], z, 3., bounds(Vec2::splat(2.))));
+        let [bottom, top, middle] = [&shapes[0], &shapes[1], &shapes[2]].map(|s| s.entity);
+
+        assert_eq!(pick(&world, &shapes, Vec2::ZERO), vec![(top, 8.)]);
+        // Outside the disks, only inside their boxes
+        assert!(pick(&world, &shapes, Vec2::splat(1.8)).is_empty());
+
+        shapes[1].pickable = Some(Pickable {
+            should_block_lower: false,
+            ..Default::default()
+        });
+        assert_eq!(
+            pick(&world, &shapes, Vec2::ZERO),
+            vec![(top, 8.), (middle, 9.)]
+        );
+
+        shapes[2].pickable = Some(Pickable::IGNORE);
+        assert_eq!(
+            pick(&world, &shapes, Vec2::ZERO),
+            vec![(top, 8.), (middle, 9.), (bottom, 10.)]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::bounding::GlobalBoundingCircle;
+use crate::bounding::GlobalBoundingBox;
 use crate::components::arena::IndexArena;
 use crate::components::buffer::{CompBuffer, ConfigRenderEntity, GlobalBuffer};
 use crate::components::initialization::CuttleRenderData;
@@ -10,7 +10,6 @@ use bevy_app::{App, PostUpdate};
 use bevy_camera::visibility::ViewVisibility;
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::entity::hash_map::EntityHashMap;
-use bevy_math::bounding::BoundingCircle;
 use bevy_render::sync_world::RenderEntity;
 use bevy_render::{Extract, Render, RenderApp, RenderSystems};
 use bevy_transform::plugins::TransformSystems;
@@ -64,7 +63,7 @@ pub struct Extracted(EntityHashMap<ExtractedCuttle>);
 pub struct ExtractedCuttle {
     pub render_entity: Entity,
     pub group_id: usize,
-    pub bounding: BoundingCircle,
+    pub bounding: GlobalBoundingBox,
     pub indices_start: u32,
     pub indices_end: u32,
     pub z: f32,
@@ -78,7 +77,7 @@ pub fn extract_cuttles<Config: CuttleConfig>(
                 Entity,
                 RenderEntity,
                 &CuttleZ,
-                &GlobalBoundingCircle,
+                &GlobalBoundingBox,
                 &CuttleIndices,
             ),
             With<Config>,
@@ -111,7 +110,7 @@ pub fn extract_cuttles<Config: CuttleConfig>(
                             group_id: indices.group_id,
                             indices_start,
                             indices_end,
-                            bounding: **bounding,
+                            bounding: *bounding,
                             z,
                         },
                     )
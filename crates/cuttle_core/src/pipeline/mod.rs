@@ -22,6 +22,8 @@ pub struct CuttlePipelineKey {
     group_id: ConfigId,
     multisample_count: u32,
     has_depth: bool,
+    /// Replaces the target instead of blending, for views drawing a single shape into an image
+    straight_alpha: bool,
 }
 
 pub trait SortedCuttlePhaseItem: Send + CachedRenderPipelinePhaseItem + SortedPhaseItem {
@@ -1,6 +1,8 @@
 use super::{
     draw::DrawCuttle, specialization::CuttlePipeline, CuttlePipelineKey, SortedCuttlePhaseItem,
 };
+use crate::bake::{ImageTarget, RenderedImages};
+use crate::bounding::LocalBounds;
 use crate::components::buffer::ConfigRenderEntity;
 use crate::configs::{ConfigId, CuttleConfig};
 use crate::internal_prelude::*;
@@ -11,8 +13,7 @@ use bevy_render::render_phase::{DrawFunctions, PhaseItem, ViewSortedRenderPhases
 use bevy_render::render_resource::{
     BufferUsages, PipelineCache, RawBufferVec, SpecializedRenderPipelines,
 };
-use bevy_render::sync_world::MainEntity;
-use bevy_render::view::{ExtractedView, RetainedViewEntity};
+use bevy_render::view::{ExtractedView, Msaa, RenderVisibleEntities, RetainedViewEntity};
 use bytemuck::NoUninit;
 use std::fmt::Debug;
 use std::marker::PhantomData;
@@ -20,19 +21,30 @@ use std::ops::Range;
 
 pub fn cuttle_queue_sorted_for_config<Config: CuttleConfig>(
     extracted: Single<&Extracted, With<ConfigRenderEntity<Config>>>,
-    views: Query<&ExtractedView>,
+    views: Query<(
+        &ExtractedView,
+        &RenderVisibleEntities,
+        &Msaa,
+        Option<&ImageTarget>,
+    )>,
     cuttle_pipeline: Res<CuttlePipeline>,
     draw_functions: Res<DrawFunctions<Config::Phase>>,
     mut pipelines: ResMut<SpecializedRenderPipelines<CuttlePipeline>>,
     cache: Res<PipelineCache>,
     mut render_phases: ResMut<ViewSortedRenderPhases<Config::Phase>>,
+    rendered_images: Res<RenderedImages>,
 ) {
     let draw_function = draw_functions.read().id::<DrawCuttle<Config>>();
-    for view in views.into_iter() {
+    for (view, visible, msaa, image_target) in views.into_iter() {
         let Some(render_phase) = render_phases.get_mut(&view.retained_view_entity) else {
             continue;
         };
-        for (index, (&entity, cuttle)) in extracted.iter().enumerate() {
+        let mut queued = 0;
+        let mut ready = true;
+        for (index, (_, entity)) in visible.iter::<LocalBounds>().enumerate() {
+            let Some(cuttle) = extracted.get(&entity.id()) else {
+                continue;
+            };
             let &ExtractedCuttle {
                 z,
                 render_entity,
@@ -43,19 +55,30 @@ pub fn cuttle_queue_sorted_for_config<Config: CuttleConfig>(
                 &cache,
                 &cuttle_pipeline,
                 CuttlePipelineKey {
-                    multisample_count: Config::Phase::multisample_count(),
+                    multisample_count: msaa.samples(),
                     group_id: ConfigId(group_id),
                     has_depth: Config::Phase::depth(),
+                    straight_alpha: image_target.is_some(),
                 },
             );
+            queued += 1;
+            ready &= cache.get_render_pipeline(pipeline).is_some();
             render_phase.add(Config::Phase::phase_item(
                 index,
                 z,
-                (render_entity, MainEntity::from(entity)),
+                (render_entity, *entity),
                 pipeline,
                 draw_function,
             ));
         }
+
+        if let Some(target) = image_target
+            && queued > 0
+            && ready
+        {
+            let camera = view.retained_view_entity.main_entity.id();
+            rendered_images.insert(camera, target.generation);
+        }
     }
 }
 
@@ -68,7 +91,8 @@ pub struct CuttleBatch {
 #[repr(C)]
 pub struct CuttleInstance {
     pos: Vec2,
-    bounding_radius: f32,
+    half_size: Vec2,
+    rotation: Vec2,
     start: u32,
     end: u32,
 }
@@ -131,8 +155,12 @@ pub fn cuttle_prepare_sorted_for_config<Config: CuttleConfig>(
             }
 
             let instance = CuttleInstance {
-                bounding_radius: bounding.circle.radius,
-                pos: bounding.center,
+                pos: bounding.isometry.translation,
+                half_size: bounding.half_size,
+                rotation: Vec2::new(
+                    bounding.isometry.rotation.cos,
+                    bounding.isometry.rotation.sin,
+                ),
                 start: indices_start,
                 end: indices_end,
             };
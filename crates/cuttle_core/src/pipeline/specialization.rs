@@ -1,5 +1,7 @@
 use super::{CuttlePipelineKey, queue::ConfigInstanceBuffer};
-use crate::components::buffer::{build_buffer_layout, build_comp_layout, build_global_layouts};
+use crate::components::buffer::{
+    STORAGE_BINDING, build_buffer_layout, build_comp_layout, build_global_layouts,
+};
 use crate::configs::{ConfigId, CuttleConfig};
 use crate::internal_prelude::*;
 use crate::shader::CuttleShader;
@@ -62,7 +64,8 @@ impl CuttlePipeline {
             ),
         );
 
-        let op_layout = build_buffer_layout(1, device, "cuttle index buffers layout");
+        let op_layout =
+            build_buffer_layout(&[STORAGE_BINDING], device, "cuttle index buffers layout");
         let comp_layout = world.run_system_once(build_comp_layout).unwrap();
         let global_layouts = world.run_system_once(build_global_layouts).unwrap();
 
@@ -116,7 +119,8 @@ impl SpecializedRenderPipeline for CuttlePipeline {
             VertexStepMode::Instance,
             [
                 VertexFormat::Float32x2,
-                VertexFormat::Float32,
+                VertexFormat::Float32x2,
+                VertexFormat::Float32x2,
                 VertexFormat::Uint32,
                 VertexFormat::Uint32,
             ],
@@ -135,7 +139,10 @@ impl SpecializedRenderPipeline for CuttlePipeline {
                 entry_point: Some("fragment".into()),
                 targets: vec![Some(ColorTargetState {
                     format: TextureFormat::bevy_default(),
-                    blend: Some(BlendState::ALPHA_BLENDING),
+                    blend: Some(match key.straight_alpha {
+                        true => BlendState::REPLACE,
+                        false => BlendState::ALPHA_BLENDING,
+                    }),
                     write_mask: ColorWrites::ALL,
                 })],
             }),
@@ -159,12 +159,17 @@ pub fn collect_component_snippets(
 ) {
     for (mut config, component_entities) in &mut configs {
         for &entity in component_entities.iter() {
-            config.extend_from_slice(components.get(entity).unwrap());
+            // Components sharing a render data type or buffer bring the same declarations
+            for snippet in components.get(entity).unwrap().iter() {
+                if !config.contains(snippet) {
+                    config.push(snippet.clone());
+                }
+            }
         }
     }
 }
 
-#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Reflect, Serialize, Deserialize)]
 pub enum AddSnippet {
     Inline(String),
     File(String),
@@ -4,9 +4,10 @@
 struct VertexIn {
     @builtin(vertex_index) index: u32,
     @location(0) translation: vec2<f32>,
-    @location(1) bounding_radius: f32,
-    @location(2) start: u32,
-    @location(3) end: u32,
+    @location(1) half_size: vec2<f32>,
+    @location(2) rotation: vec2<f32>,
+    @location(3) start: u32,
+    @location(4) end: u32,
 }
 
 @group(0) @binding(0) 
@@ -16,13 +17,21 @@ var<uniform> view: View;
 fn vertex(input: VertexIn) -> VertexOut {
     let direction = vec2<f32>(f32(input.index & 0x1u) - 0.5, f32((input.index & 0x2u) >> 1u) - 0.5);
 
+    // Pad the quad by a few pixels so antialiasing at the shape's edge is not cut off
+    let clip_per_world = length(vec2<f32>(view.clip_from_world[0].x, view.clip_from_world[1].x));
+    let padding = 4.0 / (clip_per_world * view.viewport.z);
+    let local = direction * (input.half_size + padding) * 2.0;
+
     var out: VertexOut;
-    out.world_position = direction * input.bounding_radius * 2.0;
+    out.world_position = vec2<f32>(
+        input.rotation.x * local.x - input.rotation.y * local.y,
+        input.rotation.y * local.x + input.rotation.x * local.y,
+    );
     out.world_position += input.translation;
     out.position = view.clip_from_world * vec4(out.world_position, 0.0, 1.0);
     out.start = input.start;
     out.end = input.end;
-    out.size = input.bounding_radius;
+    out.size = max(input.half_size.x, input.half_size.y);
 
     return out;
 }
@@ -0,0 +1,485 @@
+//! The `.cuttle` shape asset, a RON description of a shape built from reflected components.
+//!
+//! ```ron
+//! (
+//!     components: {
+//!         "Sdf": (),
+//!         "Quad": ((60.0, 30.0)),
+//!         "Rounded": (10.0),
+//!         "Fill": ((red: 0.2, green: 0.6, blue: 1.0, alpha: 1.0)),
+//!     },
+//!     extensions: [
+//!         (
+//!             components: {
+//!                 "Transform": (translation: (60.0, 0.0, 0.0)),
+//!                 "Circle": (20.0),
+//!                 "Subtract": (),
+//!             },
+//!         ),
+//!     ],
+//! )
+//! ```
+//!
+//! Components are named by their short or full type path and must be registered with
+//! [`ReflectComponent`]. Extensions are spawned as children of the root, so their
+//! transforms are relative to it.
+
+use crate::extensions::Extends;
+use crate::internal_prelude::*;
+use bevy_asset::io::Reader;
+use bevy_asset::{Asset, AssetApp, AssetEvent, AssetId, AssetLoader, Assets, Handle, LoadContext};
+use bevy_camera::visibility::Visibility;
+use bevy_ecs::reflect::{AppTypeRegistry, ReflectComponent};
+use bevy_log::warn;
+use bevy_platform::collections::HashSet;
+use bevy_reflect::serde::TypedReflectDeserializer;
+use bevy_reflect::{PartialReflect, TypeRegistration, TypeRegistry, TypeRegistryArc};
+use derive_more::{Display, Error, From};
+use serde::de::{DeserializeSeed, Error as _, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use std::any::TypeId;
+use std::fmt::Formatter;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_asset::<CuttleShape>()
+        .init_asset_loader::<CuttleShapeLoader>()
+        .register_type::<CuttleShapeRoot>()
+        .add_systems(Update, spawn_cuttle_shapes);
+}
+
+/// A shape loaded from a `.cuttle` file, instanced by [`CuttleShapeRoot`].
+#[derive(Asset, TypePath)]
+pub struct CuttleShape {
+    /// Components of the root entity
+    pub components: Vec<Box<dyn PartialReflect>>,
+    pub extensions: Vec<CuttleShapeExtension>,
+}
+
+/// An entity extending the root of a [`CuttleShape`], see [`Extends`].
+#[derive(TypePath)]
+pub struct CuttleShapeExtension {
+    pub components: Vec<Box<dyn PartialReflect>>,
+}
+
+/// Inserts the components of a [`CuttleShape`] into this entity and spawns its extensions
+/// as children. Reloading the asset replaces them on every instance.
+#[derive(Component, Reflect, Debug, Clone, Default, Deref)]
+#[require(Transform, Visibility)]
+#[reflect(Component)]
+pub struct CuttleShapeRoot(pub Handle<CuttleShape>);
+
+/// What was added by the last [`CuttleShape`] applied to an entity.
+#[derive(Component)]
+struct SpawnedCuttleShape {
+    components: Vec<TypeId>,
+    extensions: Vec<Entity>,
+}
+
+fn spawn_cuttle_shapes(
+    mut cmds: Commands,
+    mut messages: MessageReader<AssetEvent<CuttleShape>>,
+    roots: Query<(Entity, Ref<CuttleShapeRoot>)>,
+) {
+    let updated: HashSet<AssetId<CuttleShape>> = messages
+        .read()
+        .filter_map(|message| match message {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (entity, root) in &roots {
+        if root.is_changed() || updated.contains(&root.id()) {
+            let id = root.id();
+            cmds.queue(move |world: &mut World| apply_cuttle_shape(world, entity, id));
+        }
+    }
+}
+
+fn apply_cuttle_shape(world: &mut World, entity: Entity, id: AssetId<CuttleShape>) {
+    world.resource_scope(|world, assets: Mut<Assets<CuttleShape>>| {
+        let Some(shape) = assets.get(id) else {
+            return;
+        };
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let registry = registry.read();
+
+        let previous = world
+            .get_entity_mut(entity)
+            .ok()
+            .and_then(|mut root| root.take::<SpawnedCuttleShape>());
+        if let Some(previous) = previous {
+            for extension in previous.extensions {
+                world.try_despawn(extension).ok();
+            }
+            let Ok(mut root) = world.get_entity_mut(entity) else {
+                return;
+            };
+            for type_id in previous.components {
+                if let Some(reflect) = registry.get_type_data::<ReflectComponent>(type_id) {
+                    reflect.remove(&mut root);
+                }
+            }
+        }
+
+        let Ok(mut root) = world.get_entity_mut(entity) else {
+            return;
+        };
+        let components = insert_components(&mut root, &shape.components, &registry);
+        let extensions = shape
+            .extensions
+            .iter()
+            .map(|extension| {
+                let mut entity = world.spawn((Extends(entity), ChildOf(entity)));
+                insert_components(&mut entity, &extension.components, &registry);
+                entity.id()
+            })
+            .collect();
+        world.entity_mut(entity).insert(SpawnedCuttleShape {
+            components,
+            extensions,
+        });
+    });
+}
+
+fn insert_components(
+    entity: &mut EntityWorldMut,
+    components: &[Box<dyn PartialReflect>],
+    registry: &TypeRegistry,
+) -> Vec<TypeId> {
+    let mut inserted = Vec::new();
+    for component in components {
+        let Some(type_id) = component
+            .get_represented_type_info()
+            .map(|info| info.type_id())
+        else {
+            continue;
+        };
+        let Some(reflect) = registry.get_type_data::<ReflectComponent>(type_id) else {
+            warn!(
+                "{} is no longer a registered component",
+                component.reflect_type_path()
+            );
+            continue;
+        };
+        reflect.insert(entity, component.as_ref(), registry);
+        inserted.push(type_id);
+    }
+    inserted
+}
+
+pub struct CuttleShapeLoader {
+    type_registry: TypeRegistryArc,
+}
+
+impl FromWorld for CuttleShapeLoader {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+        }
+    }
+}
+
+impl AssetLoader for CuttleShapeLoader {
+    type Asset = CuttleShape;
+    type Settings = ();
+    type Error = LoadCuttleShapeError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        _: &mut LoadContext<'_>,
+    ) -> Result<CuttleShape, LoadCuttleShapeError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let registry = self.type_registry.read();
+        Ok(parse_cuttle_shape(&bytes, &registry)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["cuttle"]
+    }
+}
+
+#[derive(Debug, Error, Display, From)]
+pub enum LoadCuttleShapeError {
+    IO(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+/// Parses the RON of a `.cuttle` file.
+pub fn parse_cuttle_shape(
+    bytes: &[u8],
+    registry: &TypeRegistry,
+) -> Result<CuttleShape, ron::error::SpannedError> {
+    let mut deserializer = ron::de::Deserializer::from_bytes(bytes)?;
+    let (components, extensions) = PartSeed {
+        registry,
+        root: true,
+    }
+    .deserialize(&mut deserializer)
+    .map_err(|err| deserializer.span_error(err))?;
+    Ok(CuttleShape {
+        components,
+        extensions,
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum Field {
+    Components,
+    Extensions,
+}
+
+/// Deserializes the root, which may have extensions, or a single extension.
+struct PartSeed<'a> {
+    registry: &'a TypeRegistry,
+    root: bool,
+}
+
+type Part = (Vec<Box<dyn PartialReflect>>, Vec<CuttleShapeExtension>);
+
+impl<'de> DeserializeSeed<'de> for PartSeed<'_> {
+    type Value = Part;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Part, D::Error> {
+        let fields: &[&str] = match self.root {
+            true => &["components", "extensions"],
+            false => &["components"],
+        };
+        deserializer.deserialize_struct("CuttleShape", fields, self)
+    }
+}
+
+impl<'de> Visitor<'de> for PartSeed<'_> {
+    type Value = Part;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a cuttle shape")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Part, A::Error> {
+        let mut components = None;
+        let mut extensions = Vec::new();
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::Components => {
+                    components = Some(map.next_value_seed(ComponentsSeed(self.registry))?);
+                }
+                Field::Extensions if self.root => {
+                    extensions = map.next_value_seed(ExtensionsSeed(self.registry))?;
+                }
+                Field::Extensions => {
+                    return Err(A::Error::custom("extensions can not be extended"));
+                }
+            }
+        }
+        Ok((components.unwrap_or_default(), extensions))
+    }
+}
+
+struct ExtensionsSeed<'a>(&'a TypeRegistry);
+
+impl<'de> DeserializeSeed<'de> for ExtensionsSeed<'_> {
+    type Value = Vec<CuttleShapeExtension>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ExtensionsSeed<'_> {
+    type Value = Vec<CuttleShapeExtension>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a list of extensions")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut extensions = Vec::new();
+        let seed = || PartSeed {
+            registry: self.0,
+            root: false,
+        };
+        while let Some((components, _)) = seq.next_element_seed(seed())? {
+            extensions.push(CuttleShapeExtension { components });
+        }
+        Ok(extensions)
+    }
+}
+
+struct ComponentsSeed<'a>(&'a TypeRegistry);
+
+impl<'de> DeserializeSeed<'de> for ComponentsSeed<'_> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ComponentsSeed<'_> {
+    type Value = Vec<Box<dyn PartialReflect>>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a map of component type paths to values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut components = Vec::new();
+        while let Some(path) = map.next_key::<String>()? {
+            let registration = component_registration(self.0, &path).map_err(A::Error::custom)?;
+            let seed = TypedReflectDeserializer::new(registration, self.0);
+            components.push(map.next_value_seed(seed)?);
+        }
+        Ok(components)
+    }
+}
+
+fn component_registration<'a>(
+    registry: &'a TypeRegistry,
+    path: &str,
+) -> Result<&'a TypeRegistration, String> {
+    let registration = registry
+        .get_with_type_path(path)
+        .or_else(|| registry.get_with_short_type_path(path))
+        .ok_or_else(|| format!("no registered type `{path}`, or its short path is ambiguous"))?;
+    match registration.data::<ReflectComponent>() {
+        Some(_) => Ok(registration),
+        None => Err(format!("`{path}` is not reflected as a component")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CuttleShape, apply_cuttle_shape, parse_cuttle_shape};
+    use crate::components::{CuttleComponent, Positions};
+    use crate::extensions::{ExtendedBy, set_extension_index};
+    use crate::indices::{
+        AddCuttleComponent, CuttleIndices, added_cuttle_component, removed_cuttle_component,
+        set_flag_indices,
+    };
+    use bevy_asset::Assets;
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::reflect::{AppTypeRegistry, ReflectComponent};
+    use bevy_ecs::system::SystemId;
+    use bevy_reflect::{Reflect, TypeRegistry, std_traits::ReflectDefault};
+    use bevy_transform::components::Transform;
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component, Default)]
+    struct Radius(f32);
+
+    #[derive(Component, Reflect, Default, Debug, PartialEq)]
+    #[reflect(Component, Default)]
+    struct Width(f32);
+
+    fn registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::new();
+        registry.register::<Radius>();
+        registry.register::<Width>();
+        registry.register::<Transform>();
+        registry
+    }
+
+    /// A world with `Radius` and `Width` as the first and second component of a config.
+    fn cuttle_world() -> World {
+        let mut world = World::new();
+        let types = AppTypeRegistry::default();
+        *types.write() = registry();
+        world.insert_resource(types);
+        world.init_resource::<Assets<CuttleShape>>();
+        world.init_resource::<Messages<AddCuttleComponent>>();
+        world.spawn((CuttleComponent::<Radius>::new(), Positions(vec![Some(0)])));
+        world.spawn((CuttleComponent::<Width>::new(), Positions(vec![Some(1)])));
+        world.add_observer(added_cuttle_component::<Radius>);
+        world.add_observer(removed_cuttle_component::<Radius>);
+        world.add_observer(added_cuttle_component::<Width>);
+        world.add_observer(removed_cuttle_component::<Width>);
+        world
+    }
+
+    #[test]
+    fn parse_shape() {
+        let registry = registry();
+        let shape = parse_cuttle_shape(
+            br#"(
+                components: { "Radius": (5.0) },
+                extensions: [
+                    (components: {
+                        "bevy_transform::components::transform::Transform": (translation: (1.0, 2.0, 0.0)),
+                        "Radius": (2.0),
+                    }),
+                ],
+            )"#,
+            &registry,
+        )
+        .unwrap();
+
+        assert_eq!(shape.components.len(), 1);
+        assert_eq!(shape.extensions[0].components.len(), 2);
+
+        let mut world = World::new();
+        let mut entity = world.spawn_empty();
+        let reflect = registry.get_type_data::<ReflectComponent>(std::any::TypeId::of::<Radius>());
+        reflect
+            .unwrap()
+            .insert(&mut entity, shape.components[0].as_ref(), &registry);
+        assert_eq!(entity.get::<Radius>(), Some(&Radius(5.)));
+    }
+
+    #[test]
+    fn reject_unknown_and_nested() {
+        let registry = registry();
+        let unknown = parse_cuttle_shape(br#"(components: { "Missing": () })"#, &registry);
+        assert!(unknown.is_err());
+        let nested = parse_cuttle_shape(br#"(extensions: [(extensions: [])])"#, &registry);
+        assert!(nested.is_err());
+    }
+
+    #[test]
+    fn reload_with_removed_parts() {
+        let mut world = cuttle_world();
+        let extension_index: SystemId<(), Result<()>> = world.register_system(set_extension_index);
+        let flag_indices: SystemId<(), Result<()>> = world.register_system(set_flag_indices);
+        let update = |world: &mut World| {
+            world.run_system(extension_index).unwrap().unwrap();
+            world.run_system(flag_indices).unwrap().unwrap();
+        };
+        let parse = |ron: &[u8]| parse_cuttle_shape(ron, &registry()).unwrap();
+
+        let root = world.spawn(CuttleIndices::default()).id();
+        let shape = parse(
+            br#"(
+                components: { "Radius": (5.0), "Width": (1.0) },
+                extensions: [(components: { "Radius": (2.0) })],
+            )"#,
+        );
+        let id = world.resource_mut::<Assets<CuttleShape>>().add(shape).id();
+        apply_cuttle_shape(&mut world, root, id);
+        update(&mut world);
+
+        let extension = world.get::<ExtendedBy>(root).unwrap()[0];
+        let components = |world: &World| {
+            let indices = world.get::<CuttleIndices>(root).unwrap();
+            indices.components().collect::<Vec<_>>()
+        };
+        assert_eq!(
+            components(&world),
+            vec![(0, root), (1, root), (0, extension)]
+        );
+
+        let reloaded = parse(br#"(components: { "Radius": (5.0) })"#);
+        world
+            .resource_mut::<Assets<CuttleShape>>()
+            .insert(id, reloaded)
+            .unwrap();
+        apply_cuttle_shape(&mut world, root, id);
+        update(&mut world);
+
+        assert!(world.get::<Width>(root).is_none());
+        assert_eq!(components(&world), vec![(0, root)]);
+    }
+}
@@ -0,0 +1,315 @@
+use crate::bounding::{ComputeGlobalBounding, GlobalBoundingCircle};
+use crate::cpu::{CuttleCpuQuery, CuttleEvaluator, CuttleSample};
+use crate::indices::CuttleIndices;
+use crate::internal_prelude::*;
+use bevy_ecs::entity::EntityHashSet;
+use bevy_ecs::system::SystemParam;
+use bevy_math::bounding::BoundingCircle;
+use bevy_math::{Dir2, IVec2, Ray2d, Vec2};
+use bevy_platform::collections::HashMap;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CuttleSpatialIndex>().add_systems(
+        PostUpdate,
+        update_spatial_index.after(ComputeGlobalBounding),
+    );
+}
+
+/// Shapes covering more cells than this are tested for every query instead.
+const MAX_CELLS: i32 = 64;
+/// Distance at which sphere tracing counts as a hit.
+const HIT_DISTANCE: f32 = 0.01;
+const MAX_STEPS: u32 = 128;
+
+/// Uniform grid over the [`GlobalBoundingCircle`]s of all shapes, rebuilt every frame in [`PostUpdate`].
+#[derive(Resource, Debug)]
+pub struct CuttleSpatialIndex {
+    pub cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+    large: Vec<Entity>,
+    /// Smallest and largest occupied cell
+    extent: Option<(IVec2, IVec2)>,
+}
+
+impl Default for CuttleSpatialIndex {
+    fn default() -> Self {
+        Self {
+            cell_size: 256.,
+            cells: HashMap::default(),
+            large: Vec::new(),
+            extent: None,
+        }
+    }
+}
+
+impl CuttleSpatialIndex {
+    fn clear(&mut self) {
+        self.cells.values_mut().for_each(Vec::clear);
+        self.large.clear();
+        self.extent = None;
+    }
+
+    fn cell(&self, point: Vec2) -> IVec2 {
+        (point / self.cell_size).floor().as_ivec2()
+    }
+
+    fn insert(&mut self, entity: Entity, circle: BoundingCircle) {
+        let min = self.cell(circle.center - circle.radius());
+        let max = self.cell(circle.center + circle.radius());
+        let extent = max - min + 1;
+        if extent.x * extent.y > MAX_CELLS {
+            self.large.push(entity);
+            return;
+        }
+        self.extent = Some(match self.extent {
+            Some((low, high)) => (low.min(min), high.max(max)),
+            None => (min, max),
+        });
+        for x in min.x..=max.x {
+            for y in min.y..=max.y {
+                self.cells.entry(IVec2::new(x, y)).or_default().push(entity);
+            }
+        }
+    }
+
+    /// Entities whose bounds may contain `point`.
+    pub fn candidates_at(&self, point: Vec2) -> impl Iterator<Item = Entity> + '_ {
+        let cell = self.cells.get(&self.cell(point));
+        self.large.iter().chain(cell.into_iter().flatten()).copied()
+    }
+
+    /// Walks the cells along `ray` in order, passing their entities and the distance along the
+    /// ray at which the cell is left. Stops when `visit` returns `true`.
+    pub fn traverse(
+        &self,
+        ray: Ray2d,
+        max_distance: f32,
+        mut visit: impl FnMut(&[Entity], f32) -> bool,
+    ) {
+        if visit(&self.large, 0.) {
+            return;
+        }
+        let Some((low, high)) = self.extent else {
+            return;
+        };
+
+        let direction = *ray.direction;
+        let mut cell = self.cell(ray.origin);
+        let step = IVec2::new(
+            if direction.x < 0. { -1 } else { 1 },
+            if direction.y < 0. { -1 } else { 1 },
+        );
+        let delta =
+            (self.cell_size / direction.abs()).map(|d| if d.is_nan() { f32::INFINITY } else { d });
+        let boundary = (cell + step.max(IVec2::ZERO)).as_vec2() * self.cell_size;
+        let mut next = ((boundary - ray.origin) / direction)
+            .map(|t| if t.is_nan() { f32::INFINITY } else { t });
+
+        loop {
+            let exit = next.min_element();
+            let entities = self.cells.get(&cell).map(Vec::as_slice).unwrap_or_default();
+            if visit(entities, exit) || exit > max_distance {
+                return;
+            }
+            // Stop once the ray moves away from all occupied cells
+            let outside = cell.cmplt(low) | cell.cmpgt(high);
+            let away = (cell.cmplt(low) & step.cmplt(IVec2::ZERO))
+                | (cell.cmpgt(high) & step.cmpgt(IVec2::ZERO))
+                | direction.cmpeq(Vec2::ZERO);
+            if (outside & away).any() {
+                return;
+            }
+            if next.x < next.y {
+                cell.x += step.x;
+                next.x += delta.x;
+            } else {
+                cell.y += step.y;
+                next.y += delta.y;
+            }
+        }
+    }
+}
+
+fn update_spatial_index(
+    mut index: ResMut<CuttleSpatialIndex>,
+    shapes: Query<(Entity, &GlobalBoundingCircle), With<CuttleIndices>>,
+) {
+    index.clear();
+    for (entity, circle) in &shapes {
+        index.insert(entity, **circle);
+    }
+}
+
+/// First shape hit by a ray, see [`CuttleSpatialQuery::cast_ray`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    pub entity: Entity,
+    /// Distance along the ray
+    pub distance: f32,
+    pub point: Vec2,
+    /// Surface normal at `point`, from the gradient of the signed distance
+    pub normal: Dir2,
+}
+
+/// Spatial queries against the shapes of the sample type `S`,
+/// using their CPU distance functions, see [`CuttleCpu`](crate::cpu::CuttleCpu).
+///
+/// Shapes with components lacking a CPU implementation are ignored.
+/// Candidates are found through the [`CuttleSpatialIndex`], which is updated
+/// after bounds are computed in [`PostUpdate`].
+#[derive(SystemParam)]
+pub struct CuttleSpatialQuery<'w, 's, S: CuttleSample> {
+    index: Res<'w, CuttleSpatialIndex>,
+    circles: Query<'w, 's, &'static GlobalBoundingCircle>,
+    cpu: CuttleCpuQuery<'w, 's, S>,
+}
+
+impl<S: CuttleSample> CuttleSpatialQuery<'_, '_, S> {
+    /// Shapes containing `point`.
+    pub fn shapes_at(&self, point: Vec2) -> Vec<Entity> {
+        let Some(evaluator) = self.cpu.evaluator() else {
+            return Vec::new();
+        };
+        self.index
+            .candidates_at(point)
+            .filter(|&entity| {
+                self.circles
+                    .get(entity)
+                    .is_ok_and(|circle| circle.center.distance(point) <= circle.radius())
+            })
+            .filter(|&entity| evaluator.supports(entity))
+            .filter(|&entity| evaluator.distance(entity, point).is_some_and(|d| d <= 0.))
+            .collect()
+    }
+
+    /// Sphere traces `ray` against all shapes, returning the closest hit within `max_distance`.
+    pub fn cast_ray(&self, ray: Ray2d, max_distance: f32) -> Option<RayHit> {
+        self.cast_ray_filtered(ray, max_distance, |_| true)
+    }
+
+    /// Like [`Self::cast_ray`], only considering shapes for which `filter` returns `true`.
+    pub fn cast_ray_filtered(
+        &self,
+        ray: Ray2d,
+        max_distance: f32,
+        filter: impl Fn(Entity) -> bool,
+    ) -> Option<RayHit> {
+        let evaluator = self.cpu.evaluator()?;
+        let mut tested = EntityHashSet::default();
+        let mut closest: Option<(Entity, f32)> = None;
+
+        self.index.traverse(ray, max_distance, |entities, exit| {
+            for &entity in entities {
+                if !tested.insert(entity) || !filter(entity) || !evaluator.supports(entity) {
+                    continue;
+                }
+                let Ok(circle) = self.circles.get(entity) else {
+                    continue;
+                };
+                let limit = closest.map_or(max_distance, |(_, t)| t);
+                let Some((start, end)) = ray_circle(ray, circle, limit) else {
+                    continue;
+                };
+                let distance = |point| evaluator.distance(entity, point).unwrap_or(f32::MAX);
+                if let Some(t) = sphere_trace(distance, ray, start, end) {
+                    closest = Some((entity, t));
+                }
+            }
+            closest.is_some_and(|(_, t)| t <= exit)
+        });
+
+        let (entity, distance) = closest?;
+        let point = ray.get_point(distance);
+        Some(RayHit {
+            entity,
+            distance,
+            point,
+            normal: normal(&evaluator, entity, point).unwrap_or(-ray.direction),
+        })
+    }
+}
+
+/// The range along `ray` inside `circle`, clamped to `0..=max_distance`.
+fn ray_circle(ray: Ray2d, circle: &BoundingCircle, max_distance: f32) -> Option<(f32, f32)> {
+    let to_center = circle.center - ray.origin;
+    let along = to_center.dot(*ray.direction);
+    let squared = circle.radius() * circle.radius() - (to_center.length_squared() - along * along);
+    if squared < 0. {
+        return None;
+    }
+    let half = squared.sqrt();
+    let (start, end) = ((along - half).max(0.), (along + half).min(max_distance));
+    (start <= end).then_some((start, end))
+}
+
+/// Steps along `ray` by the distance to the closest surface until it is hit,
+/// returning the distance along the ray.
+fn sphere_trace(distance: impl Fn(Vec2) -> f32, ray: Ray2d, start: f32, end: f32) -> Option<f32> {
+    let mut t = start;
+    for _ in 0..MAX_STEPS {
+        let d = distance(ray.get_point(t));
+        if d < HIT_DISTANCE {
+            return Some(t);
+        }
+        t += d;
+        if t > end {
+            return None;
+        }
+    }
+    None
+}
+
+fn normal<S: CuttleSample>(
+    evaluator: &CuttleEvaluator<S>,
+    entity: Entity,
+    point: Vec2,
+) -> Option<Dir2> {
+    let distance = |offset: Vec2| evaluator.distance(entity, point + offset);
+    let (x, y) = (Vec2::X * HIT_DISTANCE, Vec2::Y * HIT_DISTANCE);
+    let gradient = Vec2::new(distance(x)? - distance(-x)?, distance(y)? - distance(-y)?);
+    Dir2::new(gradient).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CuttleSpatialIndex, ray_circle, sphere_trace};
+    use bevy_ecs::entity::Entity;
+    use bevy_math::bounding::BoundingCircle;
+    use bevy_math::{Dir2, Ray2d, Vec2};
+
+    #[test]
+    fn trace_circle() {
+        let ray = Ray2d::new(Vec2::new(-100., 5.), Dir2::X);
+        let circle = |p: Vec2| p.length() - 10.;
+        let bounds = BoundingCircle::new(Vec2::ZERO, 10.);
+
+        let (start, end) = ray_circle(ray, &bounds, 1000.).unwrap();
+        let hit = sphere_trace(circle, ray, start, end).unwrap();
+
+        assert!((hit - (100. - 75f32.sqrt())).abs() < 0.02);
+        assert!(ray_circle(ray, &bounds, 50.).is_none());
+    }
+
+    #[test]
+    fn traverse_in_order() {
+        let mut index = CuttleSpatialIndex {
+            cell_size: 10.,
+            ..Default::default()
+        };
+        let near = Entity::from_raw_u32(1).unwrap();
+        let far = Entity::from_raw_u32(2).unwrap();
+        let behind = Entity::from_raw_u32(3).unwrap();
+        index.insert(far, BoundingCircle::new(Vec2::new(55., 55.), 1.));
+        index.insert(near, BoundingCircle::new(Vec2::new(15., 15.), 1.));
+        index.insert(behind, BoundingCircle::new(Vec2::new(-15., -15.), 1.));
+
+        let mut visited = Vec::new();
+        let ray = Ray2d::new(Vec2::ONE, Dir2::new(Vec2::ONE).unwrap());
+        index.traverse(ray, f32::INFINITY, |entities, _| {
+            visited.extend_from_slice(entities);
+            false
+        });
+
+        assert_eq!(visited, vec![near, far]);
+    }
+}
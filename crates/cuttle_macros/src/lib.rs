@@ -32,7 +32,10 @@ pub fn derive_cuttle(input: TokenStream) -> TokenStream {
     })
 }
 
-fn parse_attributes(attributes: &[Attribute], steps: &mut Vec<TokenStream2>) -> Option<Type> {
+fn parse_attributes(
+    attributes: &[Attribute],
+    steps: &mut Vec<TokenStream2>,
+) -> Option<TokenStream2> {
     let mut result = None;
 
     for attr in attributes {
@@ -59,7 +62,28 @@ fn parse_attributes(attributes: &[Attribute], steps: &mut Vec<TokenStream2>) ->
                 let content;
                 parenthesized!(content in meta.input);
                 let input: Type = content.parse()?;
-                result = Some(input);
+                result = Some(quote! { .render_data_from::<#input>() });
+            }
+
+            if meta.path.is_ident("render_points") {
+                let content;
+                parenthesized!(content in meta.input);
+                let input: Type = content.parse()?;
+                result = Some(quote! { .render_points_from::<#input>() });
+            }
+
+            if meta.path.is_ident("render_texture") {
+                let content;
+                parenthesized!(content in meta.input);
+                let input: Type = content.parse()?;
+                result = Some(quote! { .render_texture_from::<#input>() });
+            }
+
+            if meta.path.is_ident("cpu") {
+                let content;
+                parenthesized!(content in meta.input);
+                let input: Type = content.parse()?;
+                steps.push(quote! { .cpu::<#input>() });
             }
 
             Ok(())
@@ -72,10 +96,10 @@ fn parse_attributes(attributes: &[Attribute], steps: &mut Vec<TokenStream2>) ->
 
 fn data_tokens(
     ast: &DeriveInput,
-    render_data: Option<Type>,
+    render_data: Option<TokenStream2>,
 ) -> Result<Option<TokenStream2>, TokenStream> {
-    if let Some(render_data) = render_data {
-        return Ok(Some(quote! { .render_data_from::<#render_data>() }));
+    if render_data.is_some() {
+        return Ok(render_data);
     }
 
     Ok(match ast.data.clone() {
@@ -0,0 +1,336 @@
+//! Shapes drawn from precomputed distance textures, like the glyphs of an (M)SDF font atlas.
+
+use crate::cpu::SdfSample;
+use crate::{PrepareBase, ScaleDistance, Sdf, SdfOrder};
+use bevy_app::App;
+use bevy_asset::{Asset, AssetApp, Handle, embedded_asset};
+use bevy_ecs::prelude::{Component, ReflectComponent};
+use bevy_image::Image;
+use bevy_math::{Rect, Vec2, Vec4};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::{Reflect, TypePath};
+use bevy_render::render_resource::ShaderType;
+use cuttle_core::prelude::{Bounding, CuttleGroupBuilderAppExt, CuttleTexture, TextureSlot};
+use cuttle_macros::Cuttle;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<(SdfTexture, SdfTextureKind)>()
+        .init_asset::<SdfAtlas>();
+
+    #[cfg(feature = "text")]
+    app.register_asset_loader(loader::SdfAtlasLoader);
+
+    embedded_asset!(app, "atlas.wgsl");
+
+    app.cuttle_config::<Sdf>()
+        .snippet_file("embedded://cuttle_sdf/atlas.wgsl")
+        .component::<SdfTexture>()
+        .affect_bounds(Bounding::Union, |t: &SdfTexture| {
+            t.center.abs() + t.half_size
+        });
+}
+
+/// A base shape whose distance is read from a region of an image.
+///
+/// Picking and other CPU queries can't read the image and use the region's rectangle.
+#[derive(Debug, Clone, Component, Reflect, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[cuttle(render_texture(SdfTextureData))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct SdfTexture {
+    pub image: Handle<Image>,
+    pub kind: SdfTextureKind,
+    /// The part of the image drawn, in uv coordinates
+    pub region: Rect,
+    /// Distance between the values 0 and 1 of the image, as a fraction of its width
+    pub distance_range: f32,
+    /// Where the center of the region is drawn
+    pub center: Vec2,
+    pub half_size: Vec2,
+}
+
+impl SdfTexture {
+    /// Draws the whole image.
+    pub fn new(
+        image: Handle<Image>,
+        kind: SdfTextureKind,
+        distance_range: f32,
+        half_size: Vec2,
+    ) -> Self {
+        Self {
+            image,
+            kind,
+            region: Rect::new(0., 0., 1., 1.),
+            distance_range,
+            center: Vec2::ZERO,
+            half_size,
+        }
+    }
+}
+
+impl CuttleTexture for SdfTexture {
+    fn texture(&self) -> &Handle<Image> {
+        &self.image
+    }
+}
+
+/// How distances are stored in the image, values above 0.5 lie inside the shape.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum SdfTextureKind {
+    /// In the red channel
+    #[default]
+    Sdf,
+    /// As the median of the red, green and blue channels, which keeps corners sharp
+    Msdf,
+}
+
+#[derive(Debug, Default, Clone, Reflect, ShaderType)]
+pub struct SdfTextureData {
+    pub region: Vec4,
+    pub center: Vec2,
+    pub half_size: Vec2,
+    /// Distance between the values 0 and 1 in local units
+    pub range: f32,
+    pub kind: u32,
+    pub texture: u32,
+}
+
+impl From<(&SdfTexture, TextureSlot)> for SdfTextureData {
+    fn from((texture, slot): (&SdfTexture, TextureSlot)) -> Self {
+        let region = texture.region;
+        Self {
+            region: region.min.extend(region.max.x).extend(region.max.y),
+            center: texture.center,
+            half_size: texture.half_size,
+            range: texture.distance_range * texture.half_size.x * 2. / region.width().max(1e-6),
+            kind: texture.kind as u32,
+            texture: *slot,
+        }
+    }
+}
+
+/// An image of distance fields with the regions of the glyphs in it,
+/// as generated by [msdf-atlas-gen](https://github.com/Chlumsky/msdf-atlas-gen).
+///
+/// With the `text` feature, atlases are loaded from the JSON description of msdf-atlas-gen
+/// named `*.sdfatlas.json`, with the image in a png of the same name next to it:
+/// `fonts/mono.sdfatlas.json` reads `fonts/mono.png`.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct SdfAtlas {
+    #[dependency]
+    pub image: Handle<Image>,
+    pub kind: SdfTextureKind,
+    /// Size of the image in pixels
+    pub size: Vec2,
+    /// Distance between the values 0 and 1 of the image, in pixels
+    pub distance_range: f32,
+    pub glyphs: HashMap<char, SdfGlyph>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SdfGlyph {
+    /// Bounds relative to the glyph's origin on the baseline, in ems with y pointing up
+    pub plane: Rect,
+    /// Region of the atlas image in pixels, with y pointing down
+    pub region: Rect,
+}
+
+impl SdfAtlas {
+    /// Draws a region of the image, given in pixels with y pointing down.
+    pub fn region(&self, region: Rect, center: Vec2, half_size: Vec2) -> SdfTexture {
+        SdfTexture {
+            image: self.image.clone(),
+            kind: self.kind,
+            region: Rect::from_corners(region.min / self.size, region.max / self.size),
+            distance_range: self.distance_range / self.size.x,
+            center,
+            half_size,
+        }
+    }
+
+    /// Draws a glyph with its origin at `origin`, or `None` for glyphs
+    /// without a shape like spaces and those missing from the atlas.
+    pub fn glyph(&self, glyph: char, origin: Vec2, font_size: f32) -> Option<SdfTexture> {
+        let glyph = self.glyphs.get(&glyph)?;
+        Some(self.region(
+            glyph.region,
+            origin + glyph.plane.center() * font_size,
+            glyph.plane.half_size() * font_size,
+        ))
+    }
+}
+
+#[cfg(feature = "text")]
+pub mod loader {
+    use super::{SdfAtlas, SdfGlyph, SdfTextureKind};
+    use bevy_asset::io::Reader;
+    use bevy_asset::{AssetLoader, Handle, LoadContext};
+    use bevy_image::Image;
+    use bevy_math::{Rect, Vec2};
+    use derive_more::{Display, Error, From};
+    use serde::Deserialize;
+
+    #[derive(Default)]
+    pub struct SdfAtlasLoader;
+
+    impl AssetLoader for SdfAtlasLoader {
+        type Asset = SdfAtlas;
+        type Settings = ();
+        type Error = LoadSdfAtlasError;
+
+        async fn load(
+            &self,
+            reader: &mut dyn Reader,
+            _: &Self::Settings,
+            load_context: &mut LoadContext<'_>,
+        ) -> Result<SdfAtlas, LoadSdfAtlasError> {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let path = load_context.path();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let stem = name.split('.').next().unwrap_or_default();
+            let image = load_context.load(path.with_file_name(format!("{stem}.png")));
+            parse_atlas(&bytes, image)
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["sdfatlas.json"]
+        }
+    }
+
+    #[derive(Debug, Error, Display, From)]
+    pub enum LoadSdfAtlasError {
+        IO(std::io::Error),
+        Json(serde_json::Error),
+        #[display("unsupported atlas type `{_0}`, expected sdf, psdf, msdf or mtsdf")]
+        #[from(ignore)]
+        UnsupportedType(#[error(not(source))] String),
+    }
+
+    #[derive(Deserialize)]
+    struct AtlasJson {
+        atlas: AtlasInfo,
+        metrics: Metrics,
+        glyphs: Vec<GlyphJson>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct AtlasInfo {
+        #[serde(rename = "type")]
+        kind: String,
+        distance_range: f32,
+        width: f32,
+        height: f32,
+        #[serde(default)]
+        y_origin: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Metrics {
+        em_size: f32,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct GlyphJson {
+        unicode: Option<u32>,
+        plane_bounds: Option<Bounds>,
+        atlas_bounds: Option<Bounds>,
+    }
+
+    #[derive(Deserialize)]
+    struct Bounds {
+        left: f32,
+        bottom: f32,
+        right: f32,
+        top: f32,
+    }
+
+    /// Reads the JSON description of an atlas generated by msdf-atlas-gen.
+    pub fn parse_atlas(json: &[u8], image: Handle<Image>) -> Result<SdfAtlas, LoadSdfAtlasError> {
+        let AtlasJson {
+            atlas,
+            metrics,
+            glyphs,
+        } = serde_json::from_slice(json)?;
+
+        let kind = match atlas.kind.as_str() {
+            "sdf" | "psdf" => SdfTextureKind::Sdf,
+            "msdf" | "mtsdf" => SdfTextureKind::Msdf,
+            _ => return Err(LoadSdfAtlasError::UnsupportedType(atlas.kind)),
+        };
+        let flip = atlas.y_origin.as_deref() == Some("bottom");
+        let em_size = metrics.em_size.max(1e-6);
+
+        let glyphs = glyphs
+            .into_iter()
+            .filter_map(|glyph| {
+                let (plane, region) = (glyph.plane_bounds?, glyph.atlas_bounds?);
+                let (top, bottom) = match flip {
+                    true => (atlas.height - region.top, atlas.height - region.bottom),
+                    false => (region.top, region.bottom),
+                };
+                let glyph_data = SdfGlyph {
+                    plane: Rect::from_corners(
+                        Vec2::new(plane.left, plane.bottom) / em_size,
+                        Vec2::new(plane.right, plane.top) / em_size,
+                    ),
+                    region: Rect::new(region.left, top, region.right, bottom),
+                };
+                Some((char::from_u32(glyph.unicode?)?, glyph_data))
+            })
+            .collect();
+
+        Ok(SdfAtlas {
+            image,
+            kind,
+            size: Vec2::new(atlas.width, atlas.height),
+            distance_range: atlas.distance_range,
+            glyphs,
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::parse_atlas;
+        use crate::atlas::SdfTextureKind;
+        use bevy_asset::Handle;
+        use bevy_math::{Rect, Vec2};
+
+        #[test]
+        fn glyphs_from_msdf_atlas_gen_json() {
+            let json = br#"{
+                "atlas": {"type": "mtsdf", "distanceRange": 4, "size": 32,
+                          "width": 128, "height": 64, "yOrigin": "bottom"},
+                "metrics": {"emSize": 2, "lineHeight": 2.4},
+                "glyphs": [
+                    {"unicode": 32, "advance": 0.5},
+                    {"unicode": 65, "advance": 1.2,
+                     "planeBounds": {"left": 0, "bottom": -0.2, "right": 1, "top": 1.6},
+                     "atlasBounds": {"left": 8, "bottom": 4, "right": 40, "top": 60}}
+                ]
+            }"#;
+            let atlas = parse_atlas(json, Handle::default()).unwrap();
+
+            assert_eq!(atlas.kind, SdfTextureKind::Msdf);
+            assert_eq!(atlas.size, Vec2::new(128., 64.));
+            assert_eq!(atlas.glyphs.len(), 1);
+            let glyph = atlas.glyphs[&'A'];
+            assert_eq!(glyph.plane, Rect::new(0., -0.1, 0.5, 0.8));
+            assert_eq!(glyph.region, Rect::new(8., 4., 40., 60.));
+
+            let shape = atlas.glyph('A', Vec2::new(10., 0.), 20.).unwrap();
+            assert!(shape.center.abs_diff_eq(Vec2::new(15., 7.), 1e-5));
+            assert!(shape.half_size.abs_diff_eq(Vec2::new(5., 9.), 1e-5));
+            assert_eq!(
+                shape.region,
+                Rect::new(1. / 16., 1. / 16., 5. / 16., 15. / 16.)
+            );
+            assert!(atlas.glyph(' ', Vec2::ZERO, 20.).is_none());
+        }
+    }
+}
@@ -0,0 +1,13 @@
+fn sdf_texture(input: SdfTextureData) {
+    let p = position - input.center;
+    // images have y pointing down
+    let t = clamp(p / (2.0 * input.half_size), vec2(-0.5), vec2(0.5)) * vec2(1.0, -1.0) + 0.5;
+    let texel = cuttle_texture(input.texture, mix(input.region.xy, input.region.zw, t));
+    var value = texel.r;
+    if input.kind == 1u {
+        value = max(min(texel.r, texel.g), min(max(texel.r, texel.g), texel.b));
+    }
+    // points outside the region add their distance to it
+    let outside = length(max(abs(p) - input.half_size, vec2(0.0)));
+    distance = (0.5 - value) * input.range + outside;
+}
@@ -0,0 +1,197 @@
+//! Rasterizing [`Sdf`](crate::Sdf) shapes into images on the CPU.
+//!
+//! Without a GPU this gives the same images anywhere, e.g. for thumbnails or golden image tests.
+//! To bake with the render pipeline instead, see [`RenderToImage`](cuttle_core::bake::RenderToImage).
+
+use crate::cpu::SdfSample;
+use bevy_asset::RenderAssetUsages;
+use bevy_color::{ColorToComponents, ColorToPacked, Srgba};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::world::World;
+use bevy_image::Image;
+use bevy_math::{Rect, UVec2, Vec2, Vec4};
+use bevy_render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+use cuttle_core::bake::image_area;
+use cuttle_core::bounding::GlobalBoundingBox;
+use cuttle_core::cpu::CuttleEvaluator;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BakeSettings {
+    /// Size of the image in pixels
+    pub size: UVec2,
+    /// Part of the world drawn, the shape's bounds if `None`.
+    /// Grows to the aspect ratio of the image.
+    pub area: Option<Rect>,
+    pub output: BakeOutput,
+    /// Samples along each axis of a pixel, averaged to antialias the edges of [`BakeOutput::Color`]
+    pub samples: u32,
+}
+
+impl Default for BakeSettings {
+    fn default() -> Self {
+        Self {
+            size: UVec2::splat(256),
+            area: None,
+            output: BakeOutput::Color,
+            samples: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BakeOutput {
+    /// The shape's colors with straight alpha, as `Rgba8UnormSrgb`
+    #[default]
+    Color,
+    /// The shape's distance as `R8Unorm`, mapped to `0.5 - distance / range` like the images of
+    /// [`SdfTexture`](crate::SdfTexture), which draws it with a `distance_range` of
+    /// `range / area.width()`
+    Distance { range: f32 },
+}
+
+/// Rasterizes the [`Sdf`](crate::Sdf) shape rooted at `entity`, including its extensions.
+///
+/// Returns `None` if `entity` is not a shape or has no bounds yet.
+/// Components without a CPU implementation are skipped.
+pub fn bake_image(world: &World, entity: Entity, settings: &BakeSettings) -> Option<Image> {
+    let bounds = world.get::<GlobalBoundingBox>(entity)?;
+    let evaluator = CuttleEvaluator::<SdfSample>::new(world)?;
+    let area = image_area(bounds, settings.area, settings.size);
+    Some(rasterize(area, settings, |point| {
+        evaluator.sample(entity, point)
+    }))
+}
+
+/// Rasterizes `area` with the given function sampling a shape at world positions,
+/// where `settings.area` is ignored. Row zero of the image is the top of `area`.
+pub fn rasterize(
+    area: Rect,
+    settings: &BakeSettings,
+    sample: impl Fn(Vec2) -> Option<SdfSample>,
+) -> Image {
+    let UVec2 {
+        x: width,
+        y: height,
+    } = settings.size;
+    let pixel = area.size() / settings.size.as_vec2();
+    let samples = settings.samples.max(1);
+    let point = |x: u32, y: u32, offset: Vec2| {
+        let corner = Vec2::new(area.min.x, area.max.y);
+        corner + Vec2::new(x as f32 + offset.x, -(y as f32 + offset.y)) * pixel
+    };
+
+    let mut data = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            match settings.output {
+                BakeOutput::Color => {
+                    let mut sum = Vec4::ZERO;
+                    for i in 0..samples * samples {
+                        let offset = (Vec2::new((i % samples) as f32, (i / samples) as f32) + 0.5)
+                            / samples as f32;
+                        let color = sample(point(x, y, offset)).map_or(Vec4::ZERO, |s| s.color);
+                        let alpha = color.w.clamp(0., 1.);
+                        sum += (color.truncate() * alpha).extend(alpha);
+                    }
+                    let alpha = sum.w / (samples * samples) as f32;
+                    let rgb = sum.truncate() / sum.w.max(f32::EPSILON);
+                    data.extend(Srgba::from_f32_array(rgb.extend(alpha).into()).to_u8_array());
+                }
+                BakeOutput::Distance { range } => {
+                    let distance =
+                        sample(point(x, y, Vec2::splat(0.5))).map_or(f32::INFINITY, |s| s.distance);
+                    let value = (0.5 - distance / range).clamp(0., 1.);
+                    data.push((value * 255.).round() as u8);
+                }
+            }
+        }
+    }
+
+    let format = match settings.output {
+        BakeOutput::Color => TextureFormat::Rgba8UnormSrgb,
+        BakeOutput::Distance { .. } => TextureFormat::R8Unorm,
+    };
+    Image::new(
+        Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+        RenderAssetUsages::default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BakeOutput, BakeSettings, rasterize};
+    use crate::cpu::SdfSample;
+    use crate::{Circle, Fill, PrepareBase, ScaleDistance, Sdf};
+    use bevy_color::palettes::css;
+    use bevy_math::{Rect, UVec2, Vec2};
+    use cuttle_core::prelude::CuttleCpu;
+
+    fn circle(point: Vec2) -> Option<SdfSample> {
+        let mut sample = SdfSample {
+            world_position: point,
+            ..Default::default()
+        };
+        PrepareBase.eval(&mut sample);
+        Circle(3.).eval(&mut sample);
+        ScaleDistance.eval(&mut sample);
+        Fill(css::RED).eval(&mut sample);
+        Sdf.eval(&mut sample);
+        Some(sample)
+    }
+
+    #[test]
+    fn golden_circle() {
+        let settings = BakeSettings {
+            size: UVec2::splat(8),
+            ..Default::default()
+        };
+        let image = rasterize(Rect::new(-4., -4., 4., 4.), &settings, circle);
+
+        let pixels: String = image
+            .data
+            .unwrap()
+            .chunks(4 * 8)
+            .map(|row| {
+                let row: String = row
+                    .chunks(4)
+                    .map(|pixel| match pixel {
+                        [255, 0, 0, 255] => '#',
+                        [_, _, _, 0] => '.',
+                        _ => '?',
+                    })
+                    .collect();
+                row + "\n"
+            })
+            .collect();
+        let golden = "\
+            ........\n\
+            ..####..\n\
+            .######.\n\
+            .######.\n\
+            .######.\n\
+            .######.\n\
+            ..####..\n\
+            ........\n";
+        assert_eq!(pixels, golden);
+    }
+
+    #[test]
+    fn distances_around_the_edge() {
+        let settings = BakeSettings {
+            size: UVec2::new(4, 1),
+            output: BakeOutput::Distance { range: 8. },
+            ..Default::default()
+        };
+        let image = rasterize(Rect::new(0., -0.5, 4., 0.5), &settings, circle);
+
+        // Pixel centers at 0.5, 1.5, 2.5 and 3.5, the edge at 3
+        assert_eq!(image.data.unwrap(), vec![207, 175, 143, 112]);
+    }
+}
@@ -0,0 +1,326 @@
+//! Fluent spawning of composite [`Sdf`] shapes.
+//!
+//! ```
+//! # use bevy_color::palettes::css;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_math::Vec2;
+//! # use cuttle_sdf::builder::SpawnShapeExt;
+//! # use cuttle_sdf::{Circle, Quad};
+//! fn spawn(mut cmds: Commands) {
+//!     let parts = cmds
+//!         .spawn_shape(Circle(30.))
+//!         .fill(css::SKY_BLUE)
+//!         .smooth_union(15., Quad(Vec2::splat(25.)))
+//!         .at(Vec2::new(40., 0.))
+//!         .fill(css::PINK)
+//!         .build();
+//!     // `parts.extensions[0]` is the quad, e.g. to animate its transform later on
+//! }
+//! ```
+//!
+//! Each operation spawns an extension of the root with its own base shape,
+//! modifiers like [`ShapeCommands::fill`] apply to the most recently added part.
+//! Extensions are children of the root, so their transforms are relative to it.
+//!
+//! Operations apply to everything before them, [`ShapeCommands::begin_group`] and
+//! [`ShapeCommands::end_group`] nest operands to build trees like `(A ∪ B) − (C ∩ D)`:
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_math::Vec2;
+//! # use cuttle_sdf::builder::SpawnShapeExt;
+//! # use cuttle_sdf::{Circle, Quad, Subtract};
+//! fn spawn(mut cmds: Commands) {
+//!     cmds.spawn_shape(Circle(30.))
+//!         .union(Circle(30.))
+//!         .at(Vec2::new(40., 0.))
+//!         .begin_group(Quad(Vec2::splat(20.)))
+//!         .intersect(Circle(25.))
+//!         .end_group(Subtract);
+//! }
+//! ```
+
+use crate::atlas::SdfTexture;
+use crate::morph::{MorphTarget, MorphWeights};
+use crate::paths::{BezierPath, Polygon, Polyline};
+use crate::primitives::*;
+use crate::{
+    Annular, Circle, Fill, Flame, Intersect, Line, PopOperand, PushOperand, Quad, Rounded, Sdf,
+    SmoothIntersect, SmoothSubtract, SmoothUnion, SmoothXor, Subtract, Unioni, Xor,
+};
+use bevy_color::{Color, Srgba};
+use bevy_ecs::prelude::*;
+use bevy_math::{Quat, Vec2};
+use bevy_transform::prelude::Transform;
+use cuttle_core::extensions::Extends;
+
+/// Components that describe the base shape of a part.
+pub trait SdfBase: Component {}
+
+impl SdfBase for Circle {}
+impl SdfBase for Quad {}
+impl SdfBase for Line {}
+impl SdfBase for Flame {}
+impl SdfBase for Ellipse {}
+impl SdfBase for EquilateralTriangle {}
+impl SdfBase for Triangle {}
+impl SdfBase for Ngon {}
+impl SdfBase for Star {}
+impl SdfBase for Arc {}
+impl SdfBase for Pie {}
+impl SdfBase for Capsule {}
+impl SdfBase for RoundedBox {}
+impl SdfBase for Rhombus {}
+impl SdfBase for Trapezoid {}
+impl SdfBase for Heart {}
+impl SdfBase for Cross {}
+impl SdfBase for Vesica {}
+impl SdfBase for Horseshoe {}
+impl SdfBase for Polygon {}
+impl SdfBase for Polyline {}
+impl SdfBase for BezierPath {}
+impl SdfBase for SdfTexture {}
+
+pub trait SpawnShapeExt<'w> {
+    /// Spawns the root of a shape with the given base shape.
+    fn spawn_shape(&mut self, base: impl SdfBase) -> ShapeCommands<'w, '_>;
+}
+
+impl<'w> SpawnShapeExt<'w> for Commands<'w, '_> {
+    fn spawn_shape(&mut self, base: impl SdfBase) -> ShapeCommands<'w, '_> {
+        let root = self.spawn((Sdf, base, Transform::default())).id();
+        ShapeCommands::new(self.reborrow(), root)
+    }
+}
+
+pub trait InsertShapeExt {
+    /// Turns the entity into the root of a shape with the given base shape.
+    fn insert_shape(&mut self, base: impl SdfBase) -> ShapeCommands<'_, '_>;
+}
+
+impl InsertShapeExt for EntityCommands<'_> {
+    fn insert_shape(&mut self, base: impl SdfBase) -> ShapeCommands<'_, '_> {
+        let root = self.insert((Sdf, base)).id();
+        ShapeCommands::new(self.commands(), root)
+    }
+}
+
+/// Entities spawned by [`ShapeCommands`], in the order they were added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapeParts {
+    pub root: Entity,
+    pub extensions: Vec<Entity>,
+}
+
+/// Builds a shape from a root and its extensions, see the [module docs](self).
+pub struct ShapeCommands<'w, 's> {
+    commands: Commands<'w, 's>,
+    parts: ShapeParts,
+    current: Entity,
+    morph_targets: u32,
+}
+
+impl<'w, 's> ShapeCommands<'w, 's> {
+    fn new(commands: Commands<'w, 's>, root: Entity) -> Self {
+        Self {
+            commands,
+            parts: ShapeParts {
+                root,
+                extensions: Vec::new(),
+            },
+            current: root,
+            morph_targets: 0,
+        }
+    }
+
+    /// The root of the shape.
+    pub fn root(&self) -> Entity {
+        self.parts.root
+    }
+
+    /// The most recently added part.
+    pub fn id(&self) -> Entity {
+        self.current
+    }
+
+    pub fn build(self) -> ShapeParts {
+        self.parts
+    }
+
+    /// Inserts a bundle into the most recently added part.
+    pub fn insert(mut self, bundle: impl Bundle) -> Self {
+        self.commands.entity(self.current).insert(bundle);
+        self
+    }
+
+    pub fn fill(self, color: impl Into<Color>) -> Self {
+        self.insert(Fill(Srgba::from(color.into())))
+    }
+
+    pub fn rounded(self, rounded: f32) -> Self {
+        self.insert(Rounded(rounded))
+    }
+
+    pub fn annular(self, annular: f32) -> Self {
+        self.insert(Annular(annular))
+    }
+
+    pub fn transform(self, transform: Transform) -> Self {
+        self.insert(transform)
+    }
+
+    pub fn at(self, position: Vec2) -> Self {
+        self.transform(Transform::from_translation(position.extend(0.)))
+    }
+
+    pub fn at_rotated(self, position: Vec2, angle: f32) -> Self {
+        self.transform(
+            Transform::from_translation(position.extend(0.))
+                .with_rotation(Quat::from_rotation_z(angle)),
+        )
+    }
+
+    /// Adds an extension with its own base shape, combined with the shape through `operation`.
+    pub fn operation(mut self, operation: impl Component, base: impl SdfBase) -> Self {
+        let root = self.parts.root;
+        self.current = self
+            .commands
+            .spawn((
+                Extends(root),
+                ChildOf(root),
+                Transform::default(),
+                operation,
+                base,
+            ))
+            .id();
+        self.parts.extensions.push(self.current);
+        self
+    }
+
+    /// Starts a nested operand with the given base shape, see [`PushOperand`].
+    /// Following operations apply to it until the matching [`ShapeCommands::end_group`].
+    pub fn begin_group(self, base: impl SdfBase) -> Self {
+        self.operation(PushOperand, base)
+    }
+
+    /// Combines the nested operand of the matching [`ShapeCommands::begin_group`] with the
+    /// shape before it through `operation`, see [`PopOperand`].
+    pub fn end_group(mut self, operation: impl Component) -> Self {
+        let root = self.parts.root;
+        self.current = self
+            .commands
+            .spawn((Extends(root), ChildOf(root), PopOperand, operation))
+            .id();
+        self.parts.extensions.push(self.current);
+        self
+    }
+
+    /// Starts a morph on the most recently added part, blending it with the following
+    /// [`ShapeCommands::morph_target`]s.
+    pub fn morph(mut self, weights: MorphWeights) -> Self {
+        self.morph_targets = 0;
+        self.insert(weights)
+    }
+
+    /// Adds the next target of the morph started by the last [`ShapeCommands::morph`],
+    /// the first one added is target `1`.
+    pub fn morph_target(mut self, base: impl SdfBase) -> Self {
+        self.morph_targets += 1;
+        let target = MorphTarget(self.morph_targets);
+        self.operation(target, base)
+    }
+
+    pub fn union(self, base: impl SdfBase) -> Self {
+        self.operation(Unioni, base)
+    }
+
+    pub fn subtract(self, base: impl SdfBase) -> Self {
+        self.operation(Subtract, base)
+    }
+
+    pub fn intersect(self, base: impl SdfBase) -> Self {
+        self.operation(Intersect, base)
+    }
+
+    pub fn xor(self, base: impl SdfBase) -> Self {
+        self.operation(Xor, base)
+    }
+
+    pub fn smooth_union(self, k: f32, base: impl SdfBase) -> Self {
+        self.operation(SmoothUnion(k), base)
+    }
+
+    pub fn smooth_subtract(self, k: f32, base: impl SdfBase) -> Self {
+        self.operation(SmoothSubtract(k), base)
+    }
+
+    pub fn smooth_intersect(self, k: f32, base: impl SdfBase) -> Self {
+        self.operation(SmoothIntersect(k), base)
+    }
+
+    pub fn smooth_xor(self, k: f32, base: impl SdfBase) -> Self {
+        self.operation(SmoothXor(k), base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ShapeParts, SpawnShapeExt};
+    use crate::morph::{MorphTarget, MorphWeights};
+    use crate::{Circle, PushOperand, Quad, Sdf, SmoothUnion, Subtract};
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::Vec2;
+    use cuttle_core::extensions::Extends;
+
+    #[test]
+    fn builds_extension_tree() {
+        let mut world = World::new();
+        let parts = world
+            .run_system_once(|mut cmds: Commands| {
+                cmds.spawn_shape(Circle(30.))
+                    .smooth_union(10., Quad(Vec2::ONE))
+                    .subtract(Circle(5.))
+                    .build()
+            })
+            .unwrap();
+        let ShapeParts { root, extensions } = parts;
+
+        assert!(world.entity(root).contains::<Sdf>());
+        assert_eq!(extensions.len(), 2);
+        for &extension in &extensions {
+            assert_eq!(world.get::<Extends>(extension).map(|e| e.0), Some(root));
+            assert_eq!(
+                world.get::<ChildOf>(extension).map(ChildOf::parent),
+                Some(root)
+            );
+        }
+        assert!(world.entity(extensions[0]).contains::<SmoothUnion>());
+        assert!(world.entity(extensions[1]).contains::<Subtract>());
+    }
+
+    #[test]
+    fn morph_targets_count_from_their_morph() {
+        let mut world = World::new();
+        let parts = world
+            .run_system_once(|mut cmds: Commands| {
+                cmds.spawn_shape(Circle(30.))
+                    .morph(MorphWeights::new([1., 0.]))
+                    .morph_target(Quad(Vec2::ONE))
+                    .begin_group(Circle(10.))
+                    .morph(MorphWeights::new([1., 0., 0.]))
+                    .morph_target(Quad(Vec2::ONE))
+                    .morph_target(Circle(5.))
+                    .build()
+            })
+            .unwrap();
+
+        let targets: Vec<_> = parts
+            .extensions
+            .iter()
+            .map(|&part| world.get::<MorphTarget>(part).map(|target| target.0))
+            .collect();
+        assert_eq!(targets, vec![Some(1), None, Some(1), Some(2)]);
+        assert!(world.entity(parts.extensions[1]).contains::<PushOperand>());
+    }
+}
@@ -0,0 +1,1478 @@
+use crate::atlas::SdfTexture;
+use crate::gradient::GradientData;
+use crate::morph::MAX_MORPH_TARGETS;
+use crate::paths::{BezierPath, Polygon, Polyline};
+use crate::*;
+use bevy_ecs::world::EntityRef;
+use cuttle_core::prelude::{CuttleCpu, CuttleSample};
+use std::f32::consts::{PI, SQRT_2};
+
+/// CPU mirror of the private variables of `sdf.wgsl`,
+/// used to evaluate the distance and color of [`Sdf`] shapes at a point.
+///
+/// Every component of this crate implements [`CuttleCpu`] for it,
+/// so shapes can be evaluated with [`CuttleCpuQuery`](cuttle_core::prelude::CuttleCpuQuery).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct SdfSample {
+    /// The point the shape is evaluated at
+    pub world_position: Vec2,
+    pub position: Vec2,
+    pub distance: f32,
+    /// Largest half extent of the shape's bounds, the vertex `size` of the shader
+    pub size: f32,
+    pub distance_scale: f32,
+    pub prev_distance: f32,
+    pub prev_color: Vec4,
+    /// Shapes pushed by [`PushOperand`], up to `operand_depth` of them
+    pub operand_distances: [f32; MAX_OPERANDS],
+    pub operand_colors: [Vec4; MAX_OPERANDS],
+    pub operand_depth: u32,
+    /// Weighted sums of the parts of a [`MorphWeights`] so far
+    pub morph_target_weights: [f32; MAX_MORPH_TARGETS],
+    pub morph_distance: f32,
+    pub morph_color: Vec4,
+    pub morph_total: f32,
+    pub color: Vec4,
+    pub elapsed_time: f32,
+    /// Gradient of the distance at the point, estimated by the evaluator for the components
+    /// that set `needs_gradient`, like the shader's derivatives
+    pub gradient: Vec2,
+    pub needs_gradient: bool,
+}
+
+impl SdfSample {
+    pub fn color(&self) -> Srgba {
+        Srgba::from_vec4(self.color)
+    }
+
+    /// Whether the point lies inside the shape
+    pub fn inside(&self) -> bool {
+        self.distance <= 0.
+    }
+}
+
+impl CuttleSample for SdfSample {
+    fn new(world_position: Vec2, size: f32, config: EntityRef) -> Self {
+        Self {
+            world_position,
+            size,
+            elapsed_time: config.get::<ElapsedTime>().map(|t| t.0).unwrap_or_default(),
+            ..Default::default()
+        }
+    }
+
+    fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    fn needs_gradient(&self) -> bool {
+        self.needs_gradient
+    }
+
+    fn set_gradient(&mut self, gradient: Vec2) {
+        self.gradient = gradient;
+    }
+}
+
+/// `mix` of wgsl
+fn mix<T>(a: T, b: T, t: f32) -> T
+where
+    T: std::ops::Mul<f32, Output = T> + std::ops::Add<Output = T>,
+{
+    a * (1. - t) + b * t
+}
+
+/// `smoothstep` of wgsl
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0., 1.);
+    t * t * (3. - 2. * t)
+}
+
+/// `step` of wgsl
+fn step(edge: f32, x: f32) -> f32 {
+    if x >= edge { 1. } else { 0. }
+}
+
+/// `round` of wgsl, which rounds half to even
+fn round(value: Vec2) -> Vec2 {
+    Vec2::new(value.x.round_ties_even(), value.y.round_ties_even())
+}
+
+/// Without derivatives on the CPU, the antialiased edge of the shader becomes a hard one.
+impl CuttleCpu<SdfSample> for Sdf {
+    fn eval(&self, s: &mut SdfSample) {
+        s.color.w *= step(0., -s.distance);
+    }
+}
+
+impl CuttleCpu<SdfSample> for PrepareBase {
+    fn eval(&self, s: &mut SdfSample) {
+        s.position = s.world_position;
+        s.distance_scale = 1.;
+    }
+}
+
+impl CuttleCpu<SdfSample> for Circle {
+    fn eval(&self, s: &mut SdfSample) {
+        s.distance = s.position.length() - self.0;
+    }
+}
+
+impl CuttleCpu<SdfSample> for Line {
+    fn eval(&self, s: &mut SdfSample) {
+        let x = s.position.x.abs() - self.0;
+        s.distance = Vec2::new(x.max(0.), s.position.y).length();
+    }
+}
+
+impl CuttleCpu<SdfSample> for Quad {
+    fn eval(&self, s: &mut SdfSample) {
+        let d = s.position.abs() - self.0;
+        s.distance = d.max(Vec2::ZERO).length() + d.x.max(d.y).min(0.);
+    }
+}
+
+impl CuttleCpu<SdfSample> for GlobalTransform {
+    fn eval(&self, s: &mut SdfSample) {
+        let transform = SdfTransform::from(self);
+        s.position = transform
+            .local_from_world
+            .transform_point3(s.position.extend(0.))
+            .truncate();
+        s.distance_scale *= transform.scale;
+    }
+}
+
+impl CuttleCpu<SdfSample> for ScaleDistance {
+    fn eval(&self, s: &mut SdfSample) {
+        s.distance *= s.distance_scale;
+    }
+}
+
+impl CuttleCpu<SdfSample> for Rounded {
+    fn eval(&self, s: &mut SdfSample) {
+        s.distance -= self.0;
+    }
+}
+
+impl CuttleCpu<SdfSample> for Annular {
+    fn eval(&self, s: &mut SdfSample) {
+        s.distance = s.distance.abs() - self.0;
+    }
+}
+
+impl CuttleCpu<SdfSample> for Fill {
+    fn eval(&self, s: &mut SdfSample) {
+        s.color = Vec4::from(self);
+    }
+}
+
+impl CuttleCpu<SdfSample> for Gradient {
+    fn eval(&self, s: &mut SdfSample) {
+        let data = GradientData::from(self);
+        let point = match self.space {
+            GradientSpace::Local => s.position,
+            GradientSpace::World => s.world_position,
+        };
+        let t = data.t(point);
+
+        let count = data.count as usize;
+        if count == 0 {
+            return;
+        }
+        let mut mixed = data.colors[0];
+        for i in 1..count {
+            let (start, end) = (data.offset(i - 1), data.offset(i));
+            if t > start {
+                let local = ((t - start) / (end - start).max(1e-5)).clamp(0., 1.);
+                mixed = mix(data.colors[i - 1], data.colors[i], local);
+            }
+        }
+        s.color = data.to_srgb(mixed);
+    }
+}
+
+impl CuttleCpu<SdfSample> for DistanceGradient {
+    fn eval(&self, s: &mut SdfSample) {
+        s.color = mix(s.color, self.color, (s.distance * self.interval).cos());
+    }
+}
+
+/// Like [`Sdf`], the antialiased edges become hard ones.
+impl CuttleCpu<SdfSample> for Stroke {
+    fn eval(&self, s: &mut SdfSample) {
+        let data = StrokeData::from(self);
+        let outer = s.distance - data.outset;
+        let mut dash = 1.;
+        if data.dash.x > 0. {
+            let along = s.position.y.atan2(s.position.x) * s.position.length() + data.dash.z;
+            let phase = along.rem_euclid(data.dash.x + data.dash.y);
+            dash = step(phase, data.dash.x);
+        }
+        let band = step(0., outer + data.width);
+        s.color = mix(s.color, data.color, band * dash);
+        s.distance = mix(s.distance, outer, dash);
+    }
+}
+
+impl CuttleCpu<SdfSample> for ForceFieldAlpha {
+    fn eval(&self, s: &mut SdfSample) {
+        s.color.w = smoothstep(0., -s.distance, 1.);
+    }
+}
+
+/// Composites `under` beneath the color of the sample, both with straight alpha.
+fn composite_under(color: Vec4, under: Vec3, under_alpha: f32) -> Vec4 {
+    let alpha = color.w + under_alpha * (1. - color.w);
+    if alpha <= 0. {
+        return color;
+    }
+    let rgb = (color.xyz() * color.w + under * under_alpha * (1. - color.w)) / alpha;
+    rgb.extend(alpha)
+}
+
+/// Distance to the shape shifted by the shadow's offset, extrapolated along the gradient
+/// like the shader does, and how much of the shadow it covers.
+fn shadow(s: &mut SdfSample, offset: Vec2, blur: f32) -> f32 {
+    s.needs_gradient = true;
+    let shadow_distance = s.distance - s.gradient.dot(offset);
+    let blur = blur.max(1e-5);
+    smoothstep(-blur, blur, shadow_distance)
+}
+
+/// The shader widens the blur to at least a pixel, the CPU doesn't know pixel sizes.
+impl CuttleCpu<SdfSample> for DropShadow {
+    fn eval(&self, s: &mut SdfSample) {
+        let shadow = self.color.alpha * (1. - shadow(s, self.offset, self.blur));
+        s.color = composite_under(s.color, self.color.to_vec4().xyz(), shadow);
+    }
+}
+
+impl CuttleCpu<SdfSample> for OuterGlow {
+    fn eval(&self, s: &mut SdfSample) {
+        let glow = self.color.alpha * (1. - smoothstep(0., self.radius, s.distance.max(0.)));
+        s.color = composite_under(s.color, self.color.to_vec4().xyz(), glow);
+    }
+}
+
+impl CuttleCpu<SdfSample> for InnerShadow {
+    fn eval(&self, s: &mut SdfSample) {
+        let shadow = self.color.alpha * shadow(s, self.offset, self.blur);
+        let rgb = s.color.xyz().lerp(self.color.to_vec4().xyz(), shadow);
+        s.color = rgb.extend(s.color.w);
+    }
+}
+
+impl CuttleCpu<SdfSample> for PrepareOperation {
+    fn eval(&self, s: &mut SdfSample) {
+        s.prev_distance = s.distance;
+        s.prev_color = s.color;
+    }
+}
+
+impl CuttleCpu<SdfSample> for PushOperand {
+    fn eval(&self, s: &mut SdfSample) {
+        let depth = s.operand_depth as usize;
+        if depth < MAX_OPERANDS {
+            s.operand_distances[depth] = s.distance;
+            s.operand_colors[depth] = s.color;
+        }
+        s.operand_depth += 1;
+    }
+}
+
+impl CuttleCpu<SdfSample> for PopOperand {
+    fn eval(&self, s: &mut SdfSample) {
+        if s.operand_depth == 0 {
+            return;
+        }
+        s.operand_depth -= 1;
+        let depth = s.operand_depth as usize;
+        if depth < MAX_OPERANDS {
+            s.prev_distance = s.operand_distances[depth];
+            s.prev_color = s.operand_colors[depth];
+        }
+    }
+}
+
+impl CuttleCpu<SdfSample> for Unioni {
+    fn eval(&self, s: &mut SdfSample) {
+        if s.prev_distance < s.distance {
+            s.distance = s.prev_distance;
+            s.color = s.prev_color;
+        }
+    }
+}
+
+impl CuttleCpu<SdfSample> for Subtract {
+    fn eval(&self, s: &mut SdfSample) {
+        s.color = s.prev_color;
+        s.distance = s.prev_distance.max(-s.distance);
+    }
+}
+
+impl CuttleCpu<SdfSample> for Intersect {
+    fn eval(&self, s: &mut SdfSample) {
+        if s.prev_distance > s.distance {
+            s.distance = s.prev_distance;
+            s.color = s.prev_color;
+        }
+    }
+}
+
+impl CuttleCpu<SdfSample> for Xor {
+    fn eval(&self, s: &mut SdfSample) {
+        let inter = s.prev_distance.max(s.distance);
+        if s.prev_distance < s.distance {
+            s.distance = s.prev_distance;
+            s.color = s.prev_color;
+        }
+        s.distance = s.distance.max(-inter);
+    }
+}
+
+impl CuttleCpu<SdfSample> for SmoothUnion {
+    fn eval(&self, s: &mut SdfSample) {
+        let mix_factor = (0.5 + 0.5 * (s.distance - s.prev_distance) / self.0).clamp(0., 1.);
+        let distance_correction = self.0 * mix_factor * (1. - mix_factor);
+        s.distance = mix(s.distance, s.prev_distance, mix_factor) - distance_correction;
+        s.color = mix(s.color, s.prev_color, mix_factor);
+    }
+}
+
+impl CuttleCpu<SdfSample> for SmoothSubtract {
+    fn eval(&self, s: &mut SdfSample) {
+        let mix_factor = (0.5 - 0.5 * (s.distance + s.prev_distance) / self.0).clamp(0., 1.);
+        let distance_correction = self.0 * mix_factor * (1. - mix_factor);
+        s.distance = mix(s.prev_distance, -s.distance, mix_factor) + distance_correction;
+        s.color = s.prev_color;
+    }
+}
+
+impl CuttleCpu<SdfSample> for SmoothIntersect {
+    fn eval(&self, s: &mut SdfSample) {
+        let mix_factor = (0.5 - 0.5 * (s.distance - s.prev_distance) / self.0).clamp(0., 1.);
+        let distance_correction = self.0 * mix_factor * (1. - mix_factor);
+        s.distance = mix(s.distance, s.prev_distance, mix_factor) + distance_correction;
+        s.color = mix(s.color, s.prev_color, mix_factor);
+    }
+}
+
+impl CuttleCpu<SdfSample> for SmoothXor {
+    fn eval(&self, s: &mut SdfSample) {
+        let inter = s.prev_distance.max(s.distance);
+        if s.prev_distance > s.distance {
+            s.prev_distance = s.distance;
+        } else {
+            s.color = s.prev_color;
+        }
+        s.distance = inter;
+        let mix_factor = (0.5 - 0.5 * (s.distance + s.prev_distance) / self.0).clamp(0., 1.);
+        let distance_correction = self.0 * mix_factor * (1. - mix_factor);
+        s.distance = mix(s.prev_distance, -s.distance, mix_factor) + distance_correction;
+    }
+}
+
+impl CuttleCpu<SdfSample> for Repetition {
+    fn eval(&self, s: &mut SdfSample) {
+        let scale = s.size / self.repetitions;
+        let clamp = self.repetitions - 1.;
+        s.position -= scale * round(s.position / scale).clamp(-clamp, clamp);
+    }
+}
+
+impl CuttleCpu<SdfSample> for Morph {
+    fn eval(&self, s: &mut SdfSample) {
+        s.distance = mix(s.prev_distance, s.distance, self.0);
+        s.color = mix(s.prev_color, s.color, self.0);
+    }
+}
+
+impl SdfSample {
+    /// Adds the current distance and color to the blend of a morph with the given weight.
+    fn add_morph_target(&mut self, weight: f32) {
+        self.morph_distance += weight * self.distance;
+        self.morph_color += weight * self.color;
+        self.morph_total += weight;
+        if self.morph_total.abs() > 1e-5 {
+            self.distance = self.morph_distance / self.morph_total;
+            self.color = self.morph_color / self.morph_total;
+        }
+    }
+}
+
+impl CuttleCpu<SdfSample> for MorphWeights {
+    fn eval(&self, s: &mut SdfSample) {
+        s.morph_target_weights = self.padded();
+        s.morph_distance = 0.;
+        s.morph_color = Vec4::ZERO;
+        s.morph_total = 0.;
+        s.add_morph_target(s.morph_target_weights[0]);
+    }
+}
+
+impl CuttleCpu<SdfSample> for MorphTarget {
+    fn eval(&self, s: &mut SdfSample) {
+        let weight = s
+            .morph_target_weights
+            .get(self.0 as usize)
+            .copied()
+            .unwrap_or_default();
+        s.add_morph_target(weight);
+    }
+}
+
+impl CuttleCpu<SdfSample> for Stretch {
+    fn eval(&self, s: &mut SdfSample) {
+        s.position /= s.position.normalize().dot(self.0.normalize()) * self.0.length();
+    }
+}
+
+impl CuttleCpu<SdfSample> for Mirror {
+    fn eval(&self, s: &mut SdfSample) {
+        let n = self.0.normalize();
+        s.position -= 2. * s.position.dot(n).min(0.) * n;
+    }
+}
+
+impl CuttleCpu<SdfSample> for PolarRepetition {
+    fn eval(&self, s: &mut SdfSample) {
+        let sector = 2. * PI / self.count.max(1) as f32;
+        let angle = s.position.y.atan2(s.position.x);
+        let folded = angle - sector * (angle / sector).round_ties_even();
+        s.position = s.position.length() * Vec2::from_angle(folded) - Vec2::X * self.radius;
+    }
+}
+
+impl CuttleCpu<SdfSample> for ClippedRepetition {
+    fn eval(&self, s: &mut SdfSample) {
+        s.position -=
+            self.spacing * round(s.position / self.spacing).clamp(-self.copies, self.copies);
+    }
+}
+
+impl CuttleCpu<SdfSample> for Twist {
+    fn eval(&self, s: &mut SdfSample) {
+        s.position = Vec2::from_angle(self.0 * s.position.length()).rotate(s.position);
+    }
+}
+
+impl CuttleCpu<SdfSample> for Bend {
+    fn eval(&self, s: &mut SdfSample) {
+        s.position = Vec2::from_angle(self.0 * s.position.x).rotate(s.position);
+    }
+}
+
+impl CuttleCpu<SdfSample> for Onion {
+    fn eval(&self, s: &mut SdfSample) {
+        let last = (self.count.max(1) - 1) as f32;
+        let shell = (s.distance / self.spacing)
+            .round_ties_even()
+            .clamp(0., last);
+        s.distance = (s.distance - shell * self.spacing).abs() - self.thickness * 0.5;
+    }
+}
+
+impl CuttleCpu<SdfSample> for NoiseDisplacement {
+    fn eval(&self, s: &mut SdfSample) {
+        let point = s.position * self.frequency;
+        let cell = point.floor();
+        let f = point - cell;
+        let t = f * f * (3. - 2. * f);
+
+        let corners = [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, y)| {
+            let corner = cell.as_ivec2() + IVec2::new(x, y);
+            let mut h = (corner.x as u32).wrapping_mul(0x8da6b343)
+                ^ (corner.y as u32).wrapping_mul(0xd8163841)
+                ^ self.seed;
+            h = (h ^ (h >> 16)).wrapping_mul(0x7feb352d);
+            h ^= h >> 15;
+            h as f32 / u32::MAX as f32
+        });
+
+        let noise = mix(
+            mix(corners[0], corners[1], t.x),
+            mix(corners[2], corners[3], t.x),
+            t.y,
+        );
+        s.distance += self.amplitude * (noise * 2. - 1.);
+    }
+}
+
+impl CuttleCpu<SdfSample> for Flame {
+    fn eval(&self, s: &mut SdfSample) {
+        let p = s.position;
+        let profile =
+            self.base * (1. - p.y.powf(self.sharpness)) + self.tip * p.y.powf(self.sharpness);
+
+        let flicker = (s.elapsed_time * 6. + p.x * 12.).sin()
+            + 0.5 * (s.elapsed_time * 13. - p.y * 10.).sin();
+        let flicker_falloff = (-4. * p.y).exp();
+        let r = profile * (1. + self.flicker * flicker * flicker_falloff);
+
+        s.distance = Vec2::new(p.x, p.y * 0.5).length() - r;
+    }
+}
+
+impl CuttleCpu<SdfSample> for Ellipse {
+    fn eval(&self, s: &mut SdfSample) {
+        let mut p = s.position.abs();
+        let mut ab = self.0;
+        if p.x > p.y {
+            p = p.yx();
+            ab = ab.yx();
+        }
+        let l = ab.y * ab.y - ab.x * ab.x;
+        if l.abs() < 1e-5 {
+            s.distance = p.length() - ab.x;
+            return;
+        }
+        let m = ab.x * p.x / l;
+        let m2 = m * m;
+        let n = ab.y * p.y / l;
+        let n2 = n * n;
+        let c = (m2 + n2 - 1.) / 3.;
+        let c3 = c * c * c;
+        let q = c3 + m2 * n2 * 2.;
+        let d = c3 + m2 * n2;
+        let g = m + m * n2;
+        let co = if d < 0. {
+            let h = (q / c3).acos() / 3.;
+            let (t, s) = h.sin_cos();
+            let t = t * 3f32.sqrt();
+            let rx = (-c * (s + t + 2.) + m2).sqrt();
+            let ry = (-c * (s - t + 2.) + m2).sqrt();
+            (ry + l.signum() * rx + g.abs() / (rx * ry) - m) / 2.
+        } else {
+            let h = 2. * m * n * d.sqrt();
+            let s = (q + h).cbrt();
+            let u = (q - h).cbrt();
+            let rx = -s - u - c * 4. + 2. * m2;
+            let ry = (s - u) * 3f32.sqrt();
+            let rm = (rx * rx + ry * ry).sqrt();
+            (ry / (rm - rx).sqrt() + 2. * g / rm - m) / 2.
+        };
+        let r = ab * Vec2::new(co, (1. - co * co).max(0.).sqrt());
+        s.distance = (r - p).length() * (p.y - r.y).signum();
+    }
+}
+
+impl CuttleCpu<SdfSample> for EquilateralTriangle {
+    fn eval(&self, s: &mut SdfSample) {
+        let k = 3f32.sqrt();
+        let mut p = Vec2::new(s.position.x.abs() - self.0, s.position.y + self.0 / k);
+        if p.x + k * p.y > 0. {
+            p = Vec2::new(p.x - k * p.y, -k * p.x - p.y) / 2.;
+        }
+        p.x -= p.x.clamp(-2. * self.0, 0.);
+        s.distance = -p.length() * p.y.signum();
+    }
+}
+
+impl CuttleCpu<SdfSample> for Triangle {
+    fn eval(&self, s: &mut SdfSample) {
+        let edge = |v: Vec2, e: Vec2| {
+            let pq = v - e * (v.dot(e) / e.dot(e)).clamp(0., 1.);
+            (pq.length_squared(), v.x * e.y - v.y * e.x)
+        };
+        let e0 = self.b - self.a;
+        let sign = (e0.x * (self.a - self.c).y - e0.y * (self.a - self.c).x).signum();
+        let (d, inside) = [
+            edge(s.position - self.a, e0),
+            edge(s.position - self.b, self.c - self.b),
+            edge(s.position - self.c, self.a - self.c),
+        ]
+        .into_iter()
+        .fold((f32::MAX, f32::MAX), |(d, inside), (e, cross)| {
+            (d.min(e), inside.min(sign * cross))
+        });
+        s.distance = -d.sqrt() * inside.signum();
+    }
+}
+
+/// Folds `position` into a single sector of `2 * an` centered on the up axis
+fn polygon_sector(position: Vec2, an: f32) -> Vec2 {
+    let bn = position.x.atan2(position.y).rem_euclid(2. * an) - an;
+    position.length() * Vec2::new(bn.cos(), bn.sin().abs())
+}
+
+impl CuttleCpu<SdfSample> for Ngon {
+    fn eval(&self, s: &mut SdfSample) {
+        let an = PI / self.sides.max(3) as f32;
+        let acs = Vec2::new(an.cos(), an.sin());
+        let mut p = polygon_sector(s.position, an) - self.radius * acs;
+        p.y += (-p.y).clamp(0., self.radius * acs.y);
+        s.distance = p.length() * p.x.signum();
+    }
+}
+
+impl CuttleCpu<SdfSample> for Star {
+    fn eval(&self, s: &mut SdfSample) {
+        let points = self.points.max(2) as f32;
+        let an = PI / points;
+        let en = PI / self.sharpness.clamp(2., points);
+        let acs = Vec2::new(an.cos(), an.sin());
+        let ecs = Vec2::new(en.cos(), en.sin());
+        let mut p = polygon_sector(s.position, an) - self.radius * acs;
+        p += ecs * (-p.dot(ecs)).clamp(0., self.radius * acs.y / ecs.y);
+        s.distance = p.length() * p.x.signum();
+    }
+}
+
+impl CuttleCpu<SdfSample> for Arc {
+    fn eval(&self, s: &mut SdfSample) {
+        let sc = Vec2::new(self.aperture.sin(), self.aperture.cos());
+        let p = Vec2::new(s.position.x.abs(), s.position.y);
+        s.distance = if sc.y * p.x > sc.x * p.y {
+            (p - sc * self.radius).length()
+        } else {
+            (p.length() - self.radius).abs()
+        } - self.thickness;
+    }
+}
+
+impl CuttleCpu<SdfSample> for Pie {
+    fn eval(&self, s: &mut SdfSample) {
+        let c = Vec2::new(self.aperture.sin(), self.aperture.cos());
+        let p = Vec2::new(s.position.x.abs(), s.position.y);
+        let l = p.length() - self.radius;
+        let m = (p - c * p.dot(c).clamp(0., self.radius)).length();
+        s.distance = l.max(m * (c.y * p.x - c.x * p.y).signum());
+    }
+}
+
+impl CuttleCpu<SdfSample> for Capsule {
+    fn eval(&self, s: &mut SdfSample) {
+        let pa = s.position - self.a;
+        let ba = self.b - self.a;
+        let h = (pa.dot(ba) / ba.length_squared().max(1e-8)).clamp(0., 1.);
+        s.distance = (pa - ba * h).length() - self.radius;
+    }
+}
+
+impl CuttleCpu<SdfSample> for RoundedBox {
+    fn eval(&self, s: &mut SdfSample) {
+        let p = s.position;
+        let right = if p.x > 0. {
+            self.radii.xy()
+        } else {
+            self.radii.zw()
+        };
+        let r = if p.y > 0. { right.x } else { right.y };
+        let q = p.abs() - self.half_size + r;
+        s.distance = q.x.max(q.y).min(0.) + q.max(Vec2::ZERO).length() - r;
+    }
+}
+
+impl CuttleCpu<SdfSample> for Rhombus {
+    fn eval(&self, s: &mut SdfSample) {
+        let p = s.position.abs();
+        let b = self.0;
+        let n = b - 2. * p;
+        let h = ((n.x * b.x - n.y * b.y) / b.dot(b)).clamp(-1., 1.);
+        let d = (p - 0.5 * b * Vec2::new(1. - h, 1. + h)).length();
+        s.distance = d * (p.x * b.y + p.y * b.x - b.x * b.y).signum();
+    }
+}
+
+impl CuttleCpu<SdfSample> for Trapezoid {
+    fn eval(&self, s: &mut SdfSample) {
+        let k1 = Vec2::new(self.top, self.half_height);
+        let k2 = Vec2::new(self.top - self.bottom, 2. * self.half_height);
+        let p = Vec2::new(s.position.x.abs(), s.position.y);
+        let width = if p.y < 0. { self.bottom } else { self.top };
+        let ca = Vec2::new(p.x - p.x.min(width), p.y.abs() - self.half_height);
+        let cb = p - k1 + k2 * ((k1 - p).dot(k2) / k2.dot(k2)).clamp(0., 1.);
+        let sign = if cb.x < 0. && ca.y < 0. { -1. } else { 1. };
+        s.distance = sign * ca.length_squared().min(cb.length_squared()).sqrt();
+    }
+}
+
+impl CuttleCpu<SdfSample> for Heart {
+    fn eval(&self, s: &mut SdfSample) {
+        let p = Vec2::new(s.position.x.abs(), s.position.y) / self.0
+            + Vec2::new(0., (0.75 + SQRT_2 / 4.) / 2.);
+        s.distance = if p.y + p.x > 1. {
+            (p - Vec2::new(0.25, 0.75)).length() - SQRT_2 / 4.
+        } else {
+            let a = p - Vec2::new(0., 1.);
+            let b = p - 0.5 * (p.x + p.y).max(0.);
+            a.length_squared().min(b.length_squared()).sqrt() * (p.x - p.y).signum()
+        } * self.0;
+    }
+}
+
+impl CuttleCpu<SdfSample> for Cross {
+    fn eval(&self, s: &mut SdfSample) {
+        let mut p = s.position.abs();
+        if p.y > p.x {
+            p = p.yx();
+        }
+        let q = p - self.0;
+        let k = q.y.max(q.x);
+        let w = if k > 0. {
+            q
+        } else {
+            Vec2::new(self.0.y - p.x, -k)
+        };
+        s.distance = k.signum() * w.max(Vec2::ZERO).length();
+    }
+}
+
+impl CuttleCpu<SdfSample> for Vesica {
+    fn eval(&self, s: &mut SdfSample) {
+        let p = s.position.abs();
+        let b = (self.radius * self.radius - self.offset * self.offset).sqrt();
+        s.distance = if (p.y - b) * self.offset > p.x * b {
+            (p - Vec2::new(0., b)).length()
+        } else {
+            (p - Vec2::new(-self.offset, 0.)).length() - self.radius
+        };
+    }
+}
+
+impl CuttleCpu<SdfSample> for Horseshoe {
+    fn eval(&self, s: &mut SdfSample) {
+        let (sin, cos) = self.aperture.sin_cos();
+        let mut p = Vec2::new(s.position.x.abs(), s.position.y);
+        let l = p.length();
+        p = Mat2::from_cols(Vec2::new(-cos, sin), Vec2::new(sin, cos)) * p;
+        p = Vec2::new(
+            if p.y > 0. || p.x > 0. {
+                p.x
+            } else {
+                l * (-cos).signum()
+            },
+            if p.x > 0. { p.y } else { l },
+        );
+        p = Vec2::new(p.x, (p.y - self.radius).abs()) - Vec2::new(self.length, self.thickness);
+        s.distance = p.max(Vec2::ZERO).length() + p.x.max(p.y).min(0.);
+    }
+}
+
+/// Squared distance to the closest piece and the even-odd sign of `point` for closed outlines
+fn outline_distance(point: Vec2, pieces: impl Iterator<Item = (Vec2, Vec2)>) -> (f32, f32) {
+    pieces.fold((1e10, 1.), |(d, sign), (a, b)| {
+        let e = b - a;
+        let w = point - a;
+        let h = w - e * (w.dot(e) / e.length_squared().max(1e-8)).clamp(0., 1.);
+        let crossing = [point.y >= a.y, point.y < b.y, e.x * w.y > e.y * w.x];
+        let flip = crossing.iter().all(|&c| c) || crossing.iter().all(|&c| !c);
+        (d.min(h.length_squared()), if flip { -sign } else { sign })
+    })
+}
+
+impl CuttleCpu<SdfSample> for Polygon {
+    fn eval(&self, s: &mut SdfSample) {
+        let previous = self.0.iter().cycle().skip(self.0.len().saturating_sub(1));
+        let (d, sign) = outline_distance(s.position, previous.copied().zip(self.0.iter().copied()));
+        s.distance = sign * d.sqrt();
+    }
+}
+
+impl CuttleCpu<SdfSample> for Polyline {
+    fn eval(&self, s: &mut SdfSample) {
+        let previous = self.points.first().into_iter().chain(&self.points);
+        let pieces = previous.copied().zip(self.points.iter().copied());
+        let (d, _) = outline_distance(s.position, pieces);
+        s.distance = d.sqrt() - self.width * 0.5;
+    }
+}
+
+impl CuttleCpu<SdfSample> for BezierPath {
+    fn eval(&self, s: &mut SdfSample) {
+        let (d, sign) = outline_distance(s.position, self.pieces());
+        s.distance = if self.closed { sign } else { 1. } * d.sqrt();
+    }
+}
+
+/// The image is not available on the CPU, the region's rectangle stands in for the shape.
+impl CuttleCpu<SdfSample> for SdfTexture {
+    fn eval(&self, s: &mut SdfSample) {
+        let d = (s.position - self.center).abs() - self.half_size;
+        s.distance = d.max(Vec2::ZERO).length() + d.x.max(d.y).min(0.);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_transform::components::Transform;
+    use cuttle_core::shader::FunctionName;
+    use std::collections::BTreeSet;
+
+    fn eval(point: Vec2, size: f32, components: &[&dyn Fn(&mut SdfSample)]) -> SdfSample {
+        let mut sample = SdfSample {
+            world_position: point,
+            size,
+            ..Default::default()
+        };
+        for component in components {
+            component(&mut sample);
+        }
+        sample
+    }
+
+    fn cpu<C: CuttleCpu<SdfSample>>(component: C) -> impl Fn(&mut SdfSample) {
+        move |sample| component.eval(sample)
+    }
+
+    type Step = Box<dyn Fn(&mut SdfSample)>;
+
+    fn circle_at(center: Vec2, radius: f32) -> [Step; 4] {
+        [
+            Box::new(cpu(PrepareBase)),
+            Box::new(cpu(GlobalTransform::from_translation(center.extend(0.)))),
+            Box::new(cpu(Circle(radius))),
+            Box::new(cpu(ScaleDistance)),
+        ]
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-4, "{a} != {b}");
+    }
+
+    macro_rules! cpu_function_names {
+        ($($C:ident),* $(,)?) => {{
+            fn implements_cpu<C: CuttleCpu<SdfSample>>() {}
+            BTreeSet::from([$({
+                implements_cpu::<$C>();
+                FunctionName::from_type_name(stringify!($C)).0
+            }),*])
+        }};
+    }
+
+    #[test]
+    fn every_wgsl_function_has_a_cpu_mirror() {
+        let wgsl: BTreeSet<_> = [
+            include_str!("sdf.wgsl"),
+            include_str!("primitives.wgsl"),
+            include_str!("domain.wgsl"),
+            include_str!("morph.wgsl"),
+            include_str!("paths.wgsl"),
+            include_str!("atlas.wgsl"),
+        ]
+        .into_iter()
+        .flat_map(str::lines)
+        .filter_map(|line| line.strip_prefix("fn "))
+        .filter_map(|line| line.split_once('('))
+        .map(|(name, _)| name.to_string())
+        .collect();
+
+        let cpu = cpu_function_names!(
+            Sdf,
+            PrepareBase,
+            Circle,
+            Line,
+            Quad,
+            GlobalTransform,
+            ScaleDistance,
+            Rounded,
+            Annular,
+            Fill,
+            Gradient,
+            DistanceGradient,
+            Stroke,
+            DropShadow,
+            OuterGlow,
+            InnerShadow,
+            ForceFieldAlpha,
+            PrepareOperation,
+            PushOperand,
+            PopOperand,
+            Unioni,
+            Subtract,
+            Intersect,
+            Xor,
+            SmoothUnion,
+            SmoothSubtract,
+            SmoothIntersect,
+            SmoothXor,
+            Repetition,
+            Morph,
+            MorphWeights,
+            MorphTarget,
+            Stretch,
+            Flame,
+            Mirror,
+            PolarRepetition,
+            ClippedRepetition,
+            Twist,
+            Bend,
+            Onion,
+            NoiseDisplacement,
+            Ellipse,
+            EquilateralTriangle,
+            Triangle,
+            Ngon,
+            Star,
+            Arc,
+            Pie,
+            Capsule,
+            RoundedBox,
+            Rhombus,
+            Trapezoid,
+            Heart,
+            Cross,
+            Vesica,
+            Horseshoe,
+            Polygon,
+            Polyline,
+            BezierPath,
+            SdfTexture,
+        );
+
+        assert_eq!(wgsl, cpu);
+    }
+
+    /// Evaluates an effect on a circle of radius 2 at `(x, 0)`, with the given gradient and color.
+    fn effect_at(
+        x: f32,
+        gradient: Vec2,
+        color: Vec4,
+        effect: &dyn Fn(&mut SdfSample),
+    ) -> SdfSample {
+        let circle = circle_at(Vec2::ZERO, 2.);
+        let prepare = |s: &mut SdfSample| {
+            s.gradient = gradient;
+            s.color = color;
+        };
+        let mut steps: Vec<&dyn Fn(&mut SdfSample)> =
+            circle.iter().map(|step| step.as_ref() as _).collect();
+        steps.push(&prepare);
+        steps.push(effect);
+        eval(Vec2::new(x, 0.), 0., &steps)
+    }
+
+    #[test]
+    fn effects() {
+        let red = Srgba::new(1., 0., 0., 0.5);
+
+        // The shadow's edge is 3 to the right, blurred over 1 to either side
+        let drop = cpu(DropShadow {
+            offset: Vec2::new(3., 0.),
+            blur: 1.,
+            color: red,
+        });
+        let at = |x: f32| effect_at(x, Vec2::X, Vec4::ZERO, &drop);
+        assert!(at(4.).needs_gradient);
+        assert_close(at(4.).color.w, 0.5);
+        assert_eq!(at(4.).color.xyz(), Vec3::X);
+        assert_close(at(5.).color.w, 0.25);
+        assert_close(at(6.).color.w, 0.);
+
+        let glow = cpu(OuterGlow {
+            radius: 2.,
+            color: red,
+        });
+        let at = |x: f32| effect_at(x, Vec2::X, Vec4::ZERO, &glow);
+        assert_close(at(3.).color.w, 0.25);
+        assert_close(at(4.).color.w, 0.);
+
+        // Darkens the left edge when lit from the left
+        let inner = cpu(InnerShadow {
+            offset: Vec2::new(1., 0.),
+            blur: 0.5,
+            color: red,
+        });
+        let blue = Vec4::new(0., 0., 1., 1.);
+        let at = |x: f32| effect_at(x, Vec2::new(x.signum(), 0.), blue, &inner).color;
+        assert_eq!(at(-1.5), Vec4::new(0.5, 0., 0.5, 1.));
+        assert_eq!(at(1.5), blue);
+    }
+
+    #[test]
+    fn base_shapes() {
+        let base = cpu(PrepareBase);
+        let at = Vec2::new(3., 4.);
+        assert_close(eval(at, 0., &[&base, &cpu(Circle(2.))]).distance, 3.);
+        assert_close(
+            eval(at, 0., &[&base, &cpu(Quad(Vec2::ONE))]).distance,
+            13f32.sqrt(),
+        );
+        assert_close(
+            eval(at, 0., &[&base, &cpu(Line(1.))]).distance,
+            20f32.sqrt(),
+        );
+        assert_close(
+            eval(Vec2::ZERO, 0., &[&base, &cpu(Quad(Vec2::ONE))]).distance,
+            -1.,
+        );
+        assert_close(
+            eval(at, 0., &[&base, &cpu(Circle(2.)), &cpu(Rounded(1.))]).distance,
+            2.,
+        );
+        assert_close(
+            eval(at, 0., &[&base, &cpu(Circle(5.)), &cpu(Annular(1.))]).distance,
+            -1.,
+        );
+    }
+
+    #[test]
+    fn paths() {
+        let base = cpu(PrepareBase);
+        let distance =
+            |at: Vec2, shape: &dyn Fn(&mut SdfSample)| eval(at, 0., &[&base, shape]).distance;
+        let square = [
+            Vec2::new(-1., -1.),
+            Vec2::new(1., -1.),
+            Vec2::new(1., 1.),
+            Vec2::new(-1., 1.),
+        ];
+
+        let polygon = cpu(Polygon(square.to_vec()));
+        assert_close(distance(Vec2::new(3., 0.), &polygon), 2.);
+        assert_close(distance(Vec2::new(0., 0.5), &polygon), -0.5);
+
+        let line = cpu(Polyline::new(square, 1.));
+        assert_close(distance(Vec2::new(3., 0.), &line), 1.5);
+        assert_close(distance(Vec2::new(-1., 0.), &line), 0.5);
+
+        // Straight curves trace the same square
+        let cubic = BezierPath::cubic(square.iter().flat_map(|&p| [p, p, p]).skip(2)).closed();
+        assert_close(distance(Vec2::new(3., 0.5), &cpu(cubic.clone())), 2.);
+        assert_close(distance(Vec2::new(0., 0.5), &cpu(cubic)), -0.5);
+    }
+
+    #[test]
+    fn primitive_shapes() {
+        let base = cpu(PrepareBase);
+        let distance =
+            |at: Vec2, shape: &dyn Fn(&mut SdfSample)| eval(at, 0., &[&base, shape]).distance;
+
+        assert_close(
+            distance(Vec2::new(5., 0.), &cpu(Ellipse(Vec2::new(3., 1.)))),
+            2.,
+        );
+        assert_close(
+            distance(Vec2::new(0., 4.), &cpu(Ellipse(Vec2::new(3., 1.)))),
+            3.,
+        );
+        assert_close(
+            distance(Vec2::new(4., 0.), &cpu(Ellipse(Vec2::splat(2.)))),
+            2.,
+        );
+
+        let triangle = Triangle {
+            a: Vec2::ZERO,
+            b: Vec2::new(4., 0.),
+            c: Vec2::new(0., 4.),
+        };
+        assert_close(distance(Vec2::new(1., -2.), &cpu(triangle)), 2.);
+        assert_close(distance(Vec2::ONE, &cpu(triangle)), -1.);
+
+        let hexagon = Ngon {
+            radius: 2.,
+            sides: 6,
+        };
+        assert_close(distance(Vec2::new(0., 5.), &cpu(hexagon)), 3.);
+        assert_close(distance(Vec2::ZERO, &cpu(hexagon)), -3f32.sqrt());
+
+        let capsule = Capsule {
+            a: Vec2::new(-2., 0.),
+            b: Vec2::new(2., 0.),
+            radius: 1.,
+        };
+        assert_close(distance(Vec2::new(0., 3.), &cpu(capsule)), 2.);
+        assert_close(distance(Vec2::new(5., 0.), &cpu(capsule)), 2.);
+
+        let rounded = RoundedBox {
+            half_size: Vec2::ONE,
+            radii: Vec4::new(1., 0., 0., 0.),
+        };
+        assert_close(
+            distance(Vec2::splat(2.), &cpu(rounded)),
+            2f32.sqrt() * 2. - 1.,
+        );
+        assert_close(distance(Vec2::new(-2., -2.), &cpu(rounded)), 2f32.sqrt());
+
+        let cross = cpu(Cross(Vec2::new(3., 1.)));
+        assert_close(distance(Vec2::new(5., 0.), &cross), 2.);
+        assert_close(distance(Vec2::new(2., 2.), &cross), 1.);
+
+        let eq = EquilateralTriangle(1.);
+        assert_close(
+            distance(Vec2::new(0., -1.), &cpu(eq)),
+            1. - 1. / 3f32.sqrt(),
+        );
+    }
+
+    /// Every sampled point inside the shape lies within the half extents of its bounds.
+    fn assert_within_bounds<C: CuttleCpu<SdfSample> + Copy>(
+        shape: C,
+        half_extents: fn(&C) -> Vec2,
+    ) {
+        let base = cpu(PrepareBase);
+        let extents = half_extents(&shape);
+        let range = extents.max_element() * 2.;
+        for x in -50..=50 {
+            for y in -50..=50 {
+                let point = Vec2::new(x as f32, y as f32) / 50. * range;
+                let distance = eval(point, 0., &[&base, &cpu(shape)]).distance;
+                assert!(
+                    distance >= 0. || point.abs().cmple(extents + 1e-4).all(),
+                    "{point} lies inside the shape but outside its bounds {extents}",
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn primitive_bounds() {
+        assert_within_bounds(Cross(Vec2::new(3., 1.)), Cross::half_extents);
+        assert_within_bounds(Cross(Vec2::new(1., 3.)), Cross::half_extents);
+        assert_within_bounds(Heart(10.), Heart::half_extents);
+        assert_within_bounds(
+            Vesica {
+                radius: 5.,
+                offset: 3.,
+            },
+            Vesica::half_extents,
+        );
+        assert_within_bounds(
+            Horseshoe {
+                aperture: 1.,
+                radius: 4.,
+                length: 3.,
+                thickness: 1.,
+            },
+            Horseshoe::half_extents,
+        );
+        assert_within_bounds(EquilateralTriangle(2.), EquilateralTriangle::half_extents);
+    }
+
+    #[test]
+    fn transform_scales_distance() {
+        let transform = GlobalTransform::from(
+            Transform::from_xyz(10., 0., 0.).with_scale(Vec3::new(2., 2., 1.)),
+        );
+        let sample = eval(
+            Vec2::new(30., 0.),
+            0.,
+            &[
+                &cpu(PrepareBase),
+                &cpu(transform),
+                &cpu(Circle(5.)),
+                &cpu(ScaleDistance),
+            ],
+        );
+        assert_close(sample.position.x, 10.);
+        assert_close(sample.distance, 10.);
+    }
+
+    #[test]
+    fn operations() {
+        let run = |operation: &dyn Fn(&mut SdfSample)| {
+            let [a, b, c, d] = circle_at(Vec2::new(-2., 0.), 1.5);
+            let [e, f, g, h] = circle_at(Vec2::new(2., 0.), 1.);
+            let red = cpu(Fill(Srgba::RED));
+            let blue = cpu(Fill(Srgba::BLUE));
+            let prepare = cpu(PrepareOperation);
+            eval(
+                Vec2::ZERO,
+                0.,
+                &[
+                    &*a, &*b, &*c, &*d, &red, &prepare, &*e, &*f, &*g, &*h, &blue, operation,
+                ],
+            )
+        };
+
+        let union = run(&cpu(Unioni));
+        assert_close(union.distance, 0.5);
+        assert_eq!(union.color(), Srgba::RED);
+
+        let subtract = run(&cpu(Subtract));
+        assert_close(subtract.distance, 0.5);
+        assert_eq!(subtract.color(), Srgba::RED);
+
+        let intersect = run(&cpu(Intersect));
+        assert_close(intersect.distance, 1.);
+        assert_eq!(intersect.color(), Srgba::BLUE);
+
+        assert_close(run(&cpu(Xor)).distance, 0.5);
+
+        // mix = 0.75, correction = 0.1875
+        assert_close(run(&cpu(SmoothUnion(1.))).distance, 0.4375);
+        // mix = 0.425, correction = 2.44375
+        assert_close(run(&cpu(SmoothSubtract(10.))).distance, 2.30625);
+        // mix = 0.475, correction = 2.49375
+        assert_close(run(&cpu(SmoothIntersect(10.))).distance, 3.25625);
+        // Smoothly subtracts the intersection from the union: mix = 0.425, correction = 2.44375
+        assert_close(run(&cpu(SmoothXor(10.))).distance, 2.30625);
+
+        let morph = run(&cpu(Morph(0.25)));
+        assert_close(morph.distance, 0.625);
+        assert_close(morph.color.x, 0.75);
+    }
+
+    #[test]
+    fn nested_operations() {
+        // (A ∪ B) − (C ∩ D) at the origin, where C ∩ D covers it but A ∪ B does not
+        let [a0, a1, a2, a3] = circle_at(Vec2::new(-3., 0.), 2.);
+        let [b0, b1, b2, b3] = circle_at(Vec2::new(3., 0.), 2.);
+        let [c0, c1, c2, c3] = circle_at(Vec2::new(-1., 0.), 2.);
+        let [d0, d1, d2, d3] = circle_at(Vec2::new(1., 0.), 2.);
+        let (prepare, union, intersect) = (cpu(PrepareOperation), cpu(Unioni), cpu(Intersect));
+        let (push, pop, subtract) = (cpu(PushOperand), cpu(PopOperand), cpu(Subtract));
+        let steps: [&dyn Fn(&mut SdfSample); _] = [
+            &*a0, &*a1, &*a2, &*a3, &prepare, &*b0, &*b1, &*b2, &*b3, &union, &push, &*c0, &*c1,
+            &*c2, &*c3, &prepare, &*d0, &*d1, &*d2, &*d3, &intersect, &prepare, &pop, &subtract,
+        ];
+
+        // A ∪ B is 1 away and C ∩ D reaches 1 beyond the origin
+        let at_origin = eval(Vec2::ZERO, 0., &steps);
+        assert_close(at_origin.distance, 1.);
+        assert_eq!(at_origin.operand_depth, 0);
+
+        // Inside A and outside of C ∩ D
+        assert_close(eval(Vec2::new(-4., 0.), 0., &steps).distance, -1.);
+        // Inside A, 0.5 within C ∩ D
+        assert_close(eval(Vec2::new(-0.5, 0.), 0., &steps).distance, 0.5);
+    }
+
+    #[test]
+    fn weighted_morph() {
+        let run = |weights: &[f32]| {
+            let [a, b, c, d] = circle_at(Vec2::ZERO, 2.);
+            let [e, f, g, h] = circle_at(Vec2::ZERO, 4.);
+            let [i, j, k, l] = circle_at(Vec2::ZERO, 8.);
+            let morph = cpu(MorphWeights::new(weights.iter().copied()));
+            eval(
+                Vec2::ZERO,
+                0.,
+                &[
+                    &*a,
+                    &*b,
+                    &*c,
+                    &*d,
+                    &cpu(Fill(Srgba::RED)),
+                    &morph,
+                    &*e,
+                    &*f,
+                    &*g,
+                    &*h,
+                    &cpu(Fill(Srgba::BLUE)),
+                    &cpu(MorphTarget(1)),
+                    &*i,
+                    &*j,
+                    &*k,
+                    &*l,
+                    &cpu(Fill(Srgba::GREEN)),
+                    &cpu(MorphTarget(2)),
+                ],
+            )
+        };
+
+        let first = run(&[1., 0., 0.]);
+        assert_close(first.distance, -2.);
+        assert_eq!(first.color(), Srgba::RED);
+
+        let blend = run(&[1., 1., 2.]);
+        assert_close(blend.distance, -5.5);
+        assert_eq!(blend.color, Vec4::new(0.25, 0.5, 0.25, 1.));
+
+        // Missing weights count as zero
+        assert_close(run(&[0., 1.]).distance, -4.);
+    }
+
+    #[test]
+    fn repetition_rounds_half_to_even() {
+        let repetition = cpu(Repetition {
+            scale: 1.,
+            repetitions: Vec2::splat(4.),
+        });
+        // size 40 and 4 repetitions gives cells of 10, 25 lies between the cells at 20 and 30
+        let sample = eval(Vec2::new(25., 3.), 40., &[&cpu(PrepareBase), &repetition]);
+        assert_close(sample.position.x, 5.);
+        assert_close(sample.position.y, 3.);
+
+        // Clamped to the outermost cell
+        let sample = eval(Vec2::new(100., 0.), 40., &[&cpu(PrepareBase), &repetition]);
+        assert_close(sample.position.x, 70.);
+    }
+
+    #[test]
+    fn stretch_along_axis() {
+        let sample = eval(
+            Vec2::new(6., 0.),
+            0.,
+            &[&cpu(PrepareBase), &cpu(Stretch(Vec2::new(3., 0.)))],
+        );
+        assert_close(sample.position.x, 2.);
+    }
+
+    #[test]
+    fn domain_operations() {
+        let position = |at: Vec2, operation: &dyn Fn(&mut SdfSample)| {
+            eval(at, 0., &[&cpu(PrepareBase), operation]).position
+        };
+
+        let mirror = cpu(Mirror(Vec2::X));
+        assert_eq!(position(Vec2::new(-3., 2.), &mirror), Vec2::new(3., 2.));
+        assert_eq!(position(Vec2::new(3., 2.), &mirror), Vec2::new(3., 2.));
+
+        // The copy at a quarter turn is moved back onto the first one
+        let polar = cpu(PolarRepetition {
+            count: 4,
+            radius: 10.,
+        });
+        let p = position(Vec2::new(0., 12.), &polar);
+        assert_close(p.x, 2.);
+        assert_close(p.y, 0.);
+
+        let clipped = cpu(ClippedRepetition {
+            spacing: Vec2::splat(10.),
+            copies: Vec2::splat(1.),
+        });
+        assert_eq!(position(Vec2::new(12., -9.), &clipped), Vec2::new(2., 1.));
+        assert_eq!(position(Vec2::new(32., 0.), &clipped), Vec2::new(22., 0.));
+
+        let twisted = position(Vec2::new(2., 0.), &cpu(Twist(PI / 4.)));
+        assert_close(twisted.x, 0.);
+        assert_close(twisted.y, 2.);
+        assert_eq!(
+            position(Vec2::new(0., 3.), &cpu(Bend(1.))),
+            Vec2::new(0., 3.)
+        );
+
+        let onion = Onion {
+            thickness: 2.,
+            spacing: 6.,
+            count: 2,
+        };
+        let distance = |at: f32| {
+            eval(
+                Vec2::X * at,
+                0.,
+                &[&cpu(PrepareBase), &cpu(Circle(10.)), &cpu(onion)],
+            )
+            .distance
+        };
+        assert_close(distance(10.), -1.);
+        assert_close(distance(13.), 2.);
+        assert_close(distance(16.), -1.);
+        assert_close(distance(20.), 3.);
+        assert_close(distance(5.), 4.);
+
+        let noise = NoiseDisplacement {
+            amplitude: 2.,
+            frequency: 0.5,
+            seed: 7,
+        };
+        for at in [Vec2::ZERO, Vec2::new(3.3, -7.1), Vec2::new(-12.8, 4.4)] {
+            let displaced = eval(at, 0., &[&cpu(PrepareBase), &cpu(Circle(10.)), &cpu(noise)]);
+            assert!((displaced.distance - (at.length() - 10.)).abs() <= 2.);
+        }
+    }
+
+    #[test]
+    fn result_alpha() {
+        let fill = cpu(Fill(Srgba::GREEN));
+        let inside = eval(
+            Vec2::ZERO,
+            0.,
+            &[&cpu(PrepareBase), &cpu(Circle(1.)), &fill, &cpu(Sdf)],
+        );
+        let outside = eval(
+            Vec2::X * 2.,
+            0.,
+            &[&cpu(PrepareBase), &cpu(Circle(1.)), &fill, &cpu(Sdf)],
+        );
+        assert!(inside.inside());
+        assert_close(inside.color.w, 1.);
+        assert_close(outside.color.w, 0.);
+
+        let force_field = eval(
+            Vec2::ZERO,
+            0.,
+            &[
+                &cpu(PrepareBase),
+                &cpu(Circle(2.)),
+                &fill,
+                &cpu(ForceFieldAlpha),
+            ],
+        );
+        assert_close(force_field.color.w, smoothstep(0., 2., 1.));
+    }
+
+    #[test]
+    fn gradients() {
+        let stops = || GradientStop::evenly([Srgba::RED, Srgba::BLUE]);
+        let color_at = |gradient: &Gradient, point: Vec2| {
+            eval(point, 0., &[&cpu(PrepareBase), &cpu(gradient.clone())]).color
+        };
+
+        let linear = Gradient::linear(Vec2::ZERO, Vec2::X * 10., stops());
+        assert_eq!(
+            color_at(&linear, Vec2::new(5., 3.)),
+            Vec4::new(0.5, 0., 0.5, 1.)
+        );
+        assert_eq!(color_at(&linear, Vec2::X * -5.), Vec4::new(1., 0., 0., 1.));
+        assert_eq!(color_at(&linear, Vec2::X * 20.), Vec4::new(0., 0., 1., 1.));
+
+        let radial = Gradient::radial(Vec2::ZERO, 4., stops());
+        assert_close(color_at(&radial, Vec2::Y * 3.).z, 0.75);
+
+        let conic = Gradient::conic(Vec2::ZERO, 0., stops());
+        assert_close(color_at(&conic, Vec2::Y).z, 0.25);
+
+        let oklab = linear.with_interpolation(GradientInterpolation::Oklab);
+        let end = color_at(&oklab, Vec2::X * 10.);
+        assert_close(end.x, 0.);
+        assert_close(end.z, 1.);
+    }
+
+    #[test]
+    fn stroke_alignment() {
+        let stroke = |alignment, point: Vec2| {
+            let stroke = Stroke::new(Srgba::RED, 2.).aligned(alignment);
+            eval(
+                point,
+                0.,
+                &[
+                    &cpu(PrepareBase),
+                    &cpu(Circle(10.)),
+                    &cpu(Fill(Srgba::BLUE)),
+                    &cpu(stroke),
+                    &cpu(Sdf),
+                ],
+            )
+        };
+
+        let inside = stroke(StrokeAlignment::Inside, Vec2::X * 9.5);
+        assert_eq!(inside.color, Vec4::new(1., 0., 0., 1.));
+        let fill = stroke(StrokeAlignment::Inside, Vec2::X * 7.5);
+        assert_eq!(fill.color, Vec4::new(0., 0., 1., 1.));
+
+        let outside = stroke(StrokeAlignment::Outside, Vec2::X * 11.5);
+        assert!(outside.inside());
+        assert_eq!(outside.color, Vec4::new(1., 0., 0., 1.));
+        assert!(!stroke(StrokeAlignment::Center, Vec2::X * 11.5).inside());
+    }
+
+    #[test]
+    fn outer_glow_beneath_shape() {
+        let glow = OuterGlow {
+            radius: 4.,
+            color: Srgba::WHITE,
+        };
+        let sample = |point: Vec2| {
+            eval(
+                point,
+                0.,
+                &[
+                    &cpu(PrepareBase),
+                    &cpu(Circle(10.)),
+                    &cpu(Fill(Srgba::RED)),
+                    &cpu(Sdf),
+                    &cpu(glow.clone()),
+                ],
+            )
+            .color
+        };
+
+        assert_eq!(sample(Vec2::ZERO), Vec4::new(1., 0., 0., 1.));
+        assert_close(sample(Vec2::X * 12.).w, 0.5);
+        assert_eq!(sample(Vec2::X * 15.), Vec4::new(1., 0., 0., 0.));
+    }
+}
@@ -0,0 +1,213 @@
+//! Operations on the space a shape is evaluated in, like mirroring or repeating it,
+//! and on the distance of its base shape, like onion shells or noise.
+//!
+//! Operations on the position are sorted at [`SdfOrder::Domain`], after the entity's transform
+//! and before its base shape, so they apply in the local space of the entity and only to the
+//! base shape of the same entity, not to the rest of an extended shape.
+//! [`Onion`] and [`NoiseDisplacement`] change the distance at [`SdfOrder::Distance`],
+//! after the distance was converted to world units.
+//!
+//! Except for [`Mirror`] and [`ClippedRepetition`], the resulting distances are approximations,
+//! which can soften or sharpen the antialiased edge for strong settings.
+
+use crate::cpu::SdfSample;
+use crate::{Sdf, SdfOrder};
+use bevy_app::App;
+use bevy_asset::embedded_asset;
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::prelude::{Component, ReflectComponent};
+use bevy_math::Vec2;
+use bevy_math::bounding::Aabb2d;
+use bevy_reflect::Reflect;
+use bevy_render::render_resource::ShaderType;
+use cuttle_core::prelude::{Bounding, CuttleGroupBuilderAppExt};
+use cuttle_macros::Cuttle;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<(
+        Mirror,
+        PolarRepetition,
+        ClippedRepetition,
+        Twist,
+        Bend,
+        Onion,
+        NoiseDisplacement,
+    )>();
+
+    embedded_asset!(app, "domain.wgsl");
+
+    app.cuttle_config::<Sdf>()
+        .snippet_file("embedded://cuttle_sdf/domain.wgsl")
+        .components::<(
+            Mirror,
+            PolarRepetition,
+            ClippedRepetition,
+            Twist,
+            Bend,
+            Onion,
+            NoiseDisplacement,
+        )>()
+        .affect_bounds(Bounding::Add, |r: &ClippedRepetition| {
+            r.spacing.abs() * r.copies.max(Vec2::ZERO)
+        })
+        .affect_bounds(Bounding::Add, Onion::outset)
+        .affect_bounds(Bounding::Add, |n: &NoiseDisplacement| n.amplitude.abs())
+        .combine_bounds(Bounding::Multiply, mirror_bounds)
+        .combine_bounds(Bounding::Multiply, |r: &PolarRepetition, bounds| {
+            *bounds = rotated_bounds(bounds, r.radius.abs())
+        })
+        .combine_bounds(Bounding::Multiply, |_: &Twist, bounds| {
+            *bounds = rotated_bounds(bounds, 0.)
+        })
+        .combine_bounds(Bounding::Multiply, |_: &Bend, bounds| {
+            *bounds = rotated_bounds(bounds, 0.)
+        });
+}
+
+/// The shape and its reflection, the shape is only kept on one side of the mirror.
+fn mirror_bounds(&Mirror(normal): &Mirror, bounds: &mut Aabb2d) {
+    let normal = normal.normalize_or_zero();
+    let corners = [
+        bounds.min,
+        bounds.max,
+        Vec2::new(bounds.min.x, bounds.max.y),
+        Vec2::new(bounds.max.x, bounds.min.y),
+    ];
+    let reflected = corners.map(|corner| corner - 2. * corner.dot(normal) * normal);
+    let min = reflected.into_iter().fold(bounds.min, Vec2::min);
+    let max = reflected.into_iter().fold(bounds.max, Vec2::max);
+    *bounds = Aabb2d { min, max };
+}
+
+/// Rotating points around the origin keeps their distance to it, so any rotation of the
+/// shape, moved `offset` away from the origin, stays within the circle around both.
+fn rotated_bounds(bounds: &Aabb2d, offset: f32) -> Aabb2d {
+    let radius = bounds.min.abs().max(bounds.max.abs()).length();
+    Aabb2d::new(Vec2::ZERO, Vec2::splat(radius + offset))
+}
+
+/// Reflects the shape across the line through the origin perpendicular to `normal`.
+/// The side `normal` points to is kept and mirrored onto the other side.
+#[derive(Debug, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
+#[cuttle(sort(SdfOrder::Domain))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+pub struct Mirror(pub Vec2);
+
+impl Default for Mirror {
+    fn default() -> Self {
+        Self(Vec2::X)
+    }
+}
+
+/// Repeats the shape `count` times around the origin. The first copy is the shape moved
+/// `radius` along the x axis, the others are rotated around the origin in equal steps.
+#[derive(Debug, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Domain))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+pub struct PolarRepetition {
+    pub count: u32,
+    pub radius: f32,
+}
+
+impl Default for PolarRepetition {
+    fn default() -> Self {
+        Self {
+            count: 6,
+            radius: 50.,
+        }
+    }
+}
+
+/// Repeats the shape on a grid with cells of `spacing`, `copies` times to each side of the
+/// original along each axis. Unlike [`Repetition`](crate::Repetition) the spacing does not
+/// depend on the size of the shape. Shapes larger than a cell are cut off at its border.
+#[derive(Debug, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Domain))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+pub struct ClippedRepetition {
+    pub spacing: Vec2,
+    pub copies: Vec2,
+}
+
+impl Default for ClippedRepetition {
+    fn default() -> Self {
+        Self {
+            spacing: Vec2::splat(50.),
+            copies: Vec2::splat(2.),
+        }
+    }
+}
+
+/// Rotates the shape around the origin by an angle growing with the distance to it,
+/// in radians per unit.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
+#[cuttle(sort(SdfOrder::Domain))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+pub struct Twist(pub f32);
+
+/// Bends the x axis of the shape into an arc with the given curvature, in radians per unit.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
+#[cuttle(sort(SdfOrder::Domain))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+pub struct Bend(pub f32);
+
+/// Turns the edge of the shape into `count` concentric shells of `thickness`,
+/// the first on the edge and the others `spacing` further outwards each.
+///
+/// Sorted with [`Rounded`](crate::Rounded) and [`Annular`](crate::Annular) and registered
+/// after them, so the shells follow the rounded or annular shape.
+#[derive(Debug, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Distance))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+pub struct Onion {
+    pub thickness: f32,
+    pub spacing: f32,
+    pub count: u32,
+}
+
+impl Default for Onion {
+    fn default() -> Self {
+        Self {
+            thickness: 2.,
+            spacing: 6.,
+            count: 3,
+        }
+    }
+}
+
+impl Onion {
+    fn outset(&self) -> f32 {
+        self.count.saturating_sub(1) as f32 * self.spacing.abs() + self.thickness.abs() * 0.5
+    }
+}
+
+/// Moves the edge of the shape by up to `amplitude` in or out, following smooth value noise
+/// of the local position with `frequency` cells per unit. `seed` picks a different pattern.
+///
+/// The distance changes by up to about `1.5 * amplitude * frequency` per unit,
+/// keep it below one for a clean edge.
+#[derive(Debug, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Distance))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+pub struct NoiseDisplacement {
+    pub amplitude: f32,
+    pub frequency: f32,
+    pub seed: u32,
+}
+
+impl Default for NoiseDisplacement {
+    fn default() -> Self {
+        Self {
+            amplitude: 4.,
+            frequency: 0.1,
+            seed: 0,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+fn mirror(normal: vec2<f32>) {
+    let n = normalize(normal);
+    position -= 2.0 * min(dot(position, n), 0.0) * n;
+}
+
+fn polar_repetition(input: PolarRepetition) {
+    let sector = 6.28318530718 / f32(max(input.count, 1u));
+    let angle = atan2(position.y, position.x);
+    let folded = angle - sector * round(angle / sector);
+    position = length(position) * vec2(cos(folded), sin(folded)) - vec2(input.radius, 0.0);
+}
+
+fn clipped_repetition(input: ClippedRepetition) {
+    position -= input.spacing * clamp(round(position / input.spacing), -input.copies, input.copies);
+}
+
+fn twist(twist: f32) {
+    let angle = twist * length(position);
+    let c = cos(angle);
+    let s = sin(angle);
+    position = vec2(c * position.x - s * position.y, s * position.x + c * position.y);
+}
+
+fn bend(bend: f32) {
+    let angle = bend * position.x;
+    let c = cos(angle);
+    let s = sin(angle);
+    position = vec2(c * position.x - s * position.y, s * position.x + c * position.y);
+}
+
+fn onion(input: Onion) {
+    let last = f32(max(input.count, 1u) - 1u);
+    let shell = clamp(round(distance / input.spacing), 0.0, last);
+    distance = abs(distance - shell * input.spacing) - input.thickness * 0.5;
+}
+
+fn noise_displacement(input: NoiseDisplacement) {
+    let point = position * input.frequency;
+    let cell = floor(point);
+    let f = point - cell;
+    let t = f * f * (3.0 - 2.0 * f);
+
+    // Hashes the corners of the cell to values between 0 and 1
+    var corners: vec4<f32>;
+    for (var i = 0u; i < 4u; i++) {
+        let corner = vec2<i32>(cell) + vec2(i32(i & 1u), i32(i >> 1u));
+        var h = (bitcast<u32>(corner.x) * 0x8da6b343u) ^ (bitcast<u32>(corner.y) * 0xd8163841u) ^ input.seed;
+        h = (h ^ (h >> 16u)) * 0x7feb352du;
+        h ^= h >> 15u;
+        corners[i] = f32(h) / 4294967295.0;
+    }
+
+    let noise = mix(mix(corners.x, corners.y, t.x), mix(corners.z, corners.w, t.x), t.y);
+    distance += input.amplitude * (noise * 2.0 - 1.0);
+}
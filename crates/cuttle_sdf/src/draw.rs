@@ -0,0 +1,435 @@
+//! Immediate mode drawing of [`Sdf`] shapes, similar to bevy's `Gizmos`.
+//!
+//! ```
+//! # use bevy_color::palettes::css;
+//! # use bevy_math::Vec2;
+//! # use cuttle_sdf::draw::{CuttleDraw, DrawPart};
+//! fn debug_draw(mut draw: CuttleDraw) {
+//!     draw.shape()
+//!         .circle(10.)
+//!         .fill(css::RED)
+//!         .at(Vec2::new(50., 0.))
+//!         .smooth_union(5., |part| part.quad(Vec2::splat(8.)).at(Vec2::new(0., 12.)));
+//! }
+//! ```
+//!
+//! Shapes are drawn for a single frame. Unlike `Gizmos`, they do not write to the
+//! render world directly: each shape is backed by pooled main world entities marked
+//! [`Drawn`], so bounds, visibility, extension indices and the component buffers
+//! go through the same systems as spawned shapes.
+//!
+//! A pooled shape is reused by later shapes made of the same components, so drawing
+//! the same shapes every frame does not spawn entities or allocate buffer indices.
+//! A pooled shape that was not drawn in a frame is hidden, and despawned once it
+//! went unused for [`POOL_FRAMES`] frames. Shapes whose components change from frame
+//! to frame therefore still spawn entities, until the pool holds every variant.
+
+use crate::{
+    Annular, Circle, Fill, Intersect, Line, Quad, Rounded, Sdf, SmoothIntersect, SmoothSubtract,
+    SmoothUnion, SmoothXor, Subtract, Unioni, Xor,
+};
+use bevy_app::{App, PostUpdate};
+use bevy_camera::visibility::Visibility;
+use bevy_color::{Color, Srgba};
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::SystemParam;
+use bevy_math::{Quat, Vec2};
+use bevy_platform::collections::HashMap;
+use bevy_transform::TransformSystems;
+use bevy_transform::prelude::Transform;
+use cuttle_core::extensions::{Extends, SetExtensionIndex};
+use std::any::TypeId;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<CuttleDrawQueue>()
+        .init_resource::<DrawPool>()
+        .add_systems(
+            PostUpdate,
+            apply_draws
+                .before(TransformSystems::Propagate)
+                .before(SetExtensionIndex),
+        );
+}
+
+/// Pooled entities are despawned after going unused for this many frames.
+pub const POOL_FRAMES: u32 = 60;
+
+/// Queues [`Sdf`] shapes to be drawn for one frame, see the [module docs](self).
+/// Shapes queued after `PostUpdate` started are drawn the next frame.
+#[derive(SystemParam)]
+pub struct CuttleDraw<'w> {
+    queue: ResMut<'w, CuttleDrawQueue>,
+}
+
+impl CuttleDraw<'_> {
+    /// Starts a shape, which is queued once the returned builder is dropped.
+    pub fn shape(&mut self) -> DrawShape<'_> {
+        DrawShape {
+            queue: &mut self.queue.shapes,
+            shape: DrawnShape::default(),
+        }
+    }
+
+    pub fn circle(&mut self, position: Vec2, radius: f32, color: impl Into<Color>) {
+        self.shape().circle(radius).fill(color).at(position);
+    }
+
+    pub fn quad(&mut self, position: Vec2, half_size: Vec2, color: impl Into<Color>) {
+        self.shape().quad(half_size).fill(color).at(position);
+    }
+
+    /// Draws a line from `start` to `end` with round caps of radius `width / 2`.
+    pub fn line(&mut self, start: Vec2, end: Vec2, width: f32, color: impl Into<Color>) {
+        let delta = end - start;
+        self.shape()
+            .line(delta.length() / 2.)
+            .rounded(width / 2.)
+            .fill(color)
+            .at((start + end) / 2.)
+            .rotated(delta.to_angle());
+    }
+}
+
+#[derive(Resource, Default)]
+struct CuttleDrawQueue {
+    shapes: Vec<DrawnShape>,
+}
+
+#[derive(Default)]
+struct DrawnShape {
+    root: DrawnPart,
+    extensions: Vec<DrawnPart>,
+}
+
+impl DrawnShape {
+    fn signature(&self) -> Vec<Vec<TypeId>> {
+        std::iter::once(&self.root)
+            .chain(&self.extensions)
+            .map(DrawnPart::signature)
+            .collect()
+    }
+}
+
+type InsertFn = Box<dyn FnOnce(&mut EntityWorldMut) + Send + Sync>;
+
+/// One entity of a drawn shape, either its root or an extension.
+#[derive(Default)]
+pub struct DrawnPart {
+    transform: Transform,
+    components: Vec<(TypeId, InsertFn)>,
+}
+
+impl DrawnPart {
+    fn signature(&self) -> Vec<TypeId> {
+        let mut signature: Vec<_> = self.components.iter().map(|(id, _)| *id).collect();
+        signature.sort();
+        signature
+    }
+
+    fn apply(self, entity: &mut EntityWorldMut) {
+        entity.insert(self.transform);
+        for (_, insert) in self.components {
+            insert(entity);
+        }
+    }
+}
+
+/// Builder methods shared by [`DrawShape`] and the [`DrawnPart`]s of its extensions.
+pub trait DrawPart: Sized {
+    fn part(&mut self) -> &mut DrawnPart;
+
+    /// Adds any component to the part, replacing one of the same type.
+    fn with<C: Component>(mut self, component: C) -> Self {
+        let id = TypeId::of::<C>();
+        let insert: InsertFn = Box::new(move |entity: &mut EntityWorldMut| {
+            entity.insert(component);
+        });
+        let components = &mut self.part().components;
+        match components.iter_mut().find(|(other, _)| *other == id) {
+            Some((_, existing)) => *existing = insert,
+            None => components.push((id, insert)),
+        }
+        self
+    }
+
+    fn circle(self, radius: f32) -> Self {
+        self.with(Circle(radius))
+    }
+
+    fn quad(self, half_size: Vec2) -> Self {
+        self.with(Quad(half_size))
+    }
+
+    fn line(self, half_length: f32) -> Self {
+        self.with(Line(half_length))
+    }
+
+    fn rounded(self, rounded: f32) -> Self {
+        self.with(Rounded(rounded))
+    }
+
+    fn annular(self, annular: f32) -> Self {
+        self.with(Annular(annular))
+    }
+
+    fn fill(self, color: impl Into<Color>) -> Self {
+        self.with(Fill(Srgba::from(color.into())))
+    }
+
+    fn at(mut self, position: Vec2) -> Self {
+        let translation = &mut self.part().transform.translation;
+        *translation = position.extend(translation.z);
+        self
+    }
+
+    fn z(mut self, z: f32) -> Self {
+        self.part().transform.translation.z = z;
+        self
+    }
+
+    fn rotated(mut self, angle: f32) -> Self {
+        self.part().transform.rotation = Quat::from_rotation_z(angle);
+        self
+    }
+
+    fn scaled(mut self, scale: Vec2) -> Self {
+        self.part().transform.scale = scale.extend(1.);
+        self
+    }
+
+    fn transform(mut self, transform: Transform) -> Self {
+        self.part().transform = transform;
+        self
+    }
+}
+
+impl DrawPart for DrawnPart {
+    fn part(&mut self) -> &mut DrawnPart {
+        self
+    }
+}
+
+/// A shape being drawn with [`CuttleDraw::shape`], extensions are placed relative to it.
+pub struct DrawShape<'a> {
+    queue: &'a mut Vec<DrawnShape>,
+    shape: DrawnShape,
+}
+
+impl DrawPart for DrawShape<'_> {
+    fn part(&mut self) -> &mut DrawnPart {
+        &mut self.shape.root
+    }
+}
+
+impl Drop for DrawShape<'_> {
+    fn drop(&mut self) {
+        self.queue.push(std::mem::take(&mut self.shape));
+    }
+}
+
+impl DrawShape<'_> {
+    /// Adds an extension combined with the shape through `operation`.
+    pub fn extend(
+        mut self,
+        operation: impl Component,
+        part: impl FnOnce(DrawnPart) -> DrawnPart,
+    ) -> Self {
+        let part = part(DrawnPart::default()).with(operation);
+        self.shape.extensions.push(part);
+        self
+    }
+
+    pub fn union(self, part: impl FnOnce(DrawnPart) -> DrawnPart) -> Self {
+        self.extend(Unioni, part)
+    }
+
+    pub fn subtract(self, part: impl FnOnce(DrawnPart) -> DrawnPart) -> Self {
+        self.extend(Subtract, part)
+    }
+
+    pub fn intersect(self, part: impl FnOnce(DrawnPart) -> DrawnPart) -> Self {
+        self.extend(Intersect, part)
+    }
+
+    pub fn xor(self, part: impl FnOnce(DrawnPart) -> DrawnPart) -> Self {
+        self.extend(Xor, part)
+    }
+
+    pub fn smooth_union(self, k: f32, part: impl FnOnce(DrawnPart) -> DrawnPart) -> Self {
+        self.extend(SmoothUnion(k), part)
+    }
+
+    pub fn smooth_subtract(self, k: f32, part: impl FnOnce(DrawnPart) -> DrawnPart) -> Self {
+        self.extend(SmoothSubtract(k), part)
+    }
+
+    pub fn smooth_intersect(self, k: f32, part: impl FnOnce(DrawnPart) -> DrawnPart) -> Self {
+        self.extend(SmoothIntersect(k), part)
+    }
+
+    pub fn smooth_xor(self, k: f32, part: impl FnOnce(DrawnPart) -> DrawnPart) -> Self {
+        self.extend(SmoothXor(k), part)
+    }
+}
+
+/// Marks the pooled entities backing shapes drawn with [`CuttleDraw`].
+#[derive(Component, Debug, Default)]
+pub struct Drawn;
+
+#[derive(Resource, Default)]
+struct DrawPool {
+    shapes: HashMap<Vec<Vec<TypeId>>, Vec<PooledShape>>,
+}
+
+struct PooledShape {
+    root: Entity,
+    extensions: Vec<Entity>,
+    unused_frames: u32,
+}
+
+fn apply_draws(world: &mut World) {
+    let shapes = std::mem::take(&mut world.resource_mut::<CuttleDrawQueue>().shapes);
+    world.resource_scope(|world, mut pool: Mut<DrawPool>| {
+        for pooled in pool.shapes.values_mut().flatten() {
+            pooled.unused_frames += 1;
+        }
+
+        for shape in shapes {
+            let signature = shape.signature();
+            let pooled = pool.shapes.entry(signature).or_default();
+            let pooled = match pooled.iter().position(|p| p.unused_frames > 0) {
+                Some(index) => &mut pooled[index],
+                None => {
+                    pooled.push(spawn_pooled(world, shape.extensions.len()));
+                    pooled.last_mut().unwrap()
+                }
+            };
+            pooled.unused_frames = 0;
+
+            let mut root = world.entity_mut(pooled.root);
+            root.insert(Visibility::Inherited);
+            shape.root.apply(&mut root);
+            for (part, &entity) in shape.extensions.into_iter().zip(&pooled.extensions) {
+                part.apply(&mut world.entity_mut(entity));
+            }
+        }
+
+        for pooled in pool.shapes.values_mut() {
+            pooled.retain(|pooled| {
+                let keep = pooled.unused_frames <= POOL_FRAMES;
+                match pooled.unused_frames {
+                    0 => {}
+                    1 => {
+                        world.entity_mut(pooled.root).insert(Visibility::Hidden);
+                    }
+                    _ if !keep => {
+                        world.entity_mut(pooled.root).despawn();
+                    }
+                    _ => {}
+                }
+                keep
+            });
+        }
+        pool.shapes.retain(|_, pooled| !pooled.is_empty());
+    });
+}
+
+fn spawn_pooled(world: &mut World, extensions: usize) -> PooledShape {
+    let root = world.spawn((Drawn, Sdf)).id();
+    let extensions = (0..extensions)
+        .map(|_| world.spawn((Drawn, Extends(root), ChildOf(root))).id())
+        .collect();
+    PooledShape {
+        root,
+        extensions,
+        unused_frames: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CuttleDraw, CuttleDrawQueue, DrawPart, DrawPool, Drawn, POOL_FRAMES, apply_draws};
+    use crate::{Circle, Quad};
+    use bevy_app::{App, SubApp, Update};
+    use bevy_camera::visibility::Visibility;
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::Vec2;
+    use bevy_render::RenderApp;
+    use cuttle_core::extensions::ExtensionIndex;
+
+    fn draw_frame(world: &mut World, draw: fn(CuttleDraw)) {
+        world.run_system_once(draw).unwrap();
+        apply_draws(world);
+    }
+
+    fn drawn(world: &mut World) -> Vec<(Entity, Visibility)> {
+        let mut query =
+            world.query_filtered::<(Entity, &Visibility), (With<Drawn>, Without<ChildOf>)>();
+        query.iter(world).map(|(e, v)| (e, *v)).collect()
+    }
+
+    #[test]
+    fn reuses_pooled_entities() {
+        let mut world = World::new();
+        world.init_resource::<CuttleDrawQueue>();
+        world.init_resource::<DrawPool>();
+
+        draw_frame(&mut world, |mut draw| {
+            draw.shape().circle(5.).union(|part| part.quad(Vec2::ONE));
+        });
+        let [(entity, Visibility::Inherited)] = drawn(&mut world)[..] else {
+            panic!("expected one visible shape");
+        };
+
+        draw_frame(&mut world, |mut draw| {
+            draw.shape().circle(8.).union(|part| part.quad(Vec2::ONE));
+        });
+        assert_eq!(drawn(&mut world), vec![(entity, Visibility::Inherited)]);
+        assert_eq!(world.get::<Circle>(entity).map(|c| c.0), Some(8.));
+
+        draw_frame(&mut world, |mut draw| {
+            draw.shape().quad(Vec2::ONE);
+        });
+        let shapes = drawn(&mut world);
+        assert_eq!(shapes.len(), 2);
+        assert!(shapes.contains(&(entity, Visibility::Hidden)));
+        assert!(world.query::<&Quad>().iter(&world).count() == 2);
+    }
+
+    #[test]
+    fn unused_shapes_are_despawned() {
+        let mut world = World::new();
+        world.init_resource::<CuttleDrawQueue>();
+        world.init_resource::<DrawPool>();
+
+        draw_frame(&mut world, |mut draw| {
+            draw.shape().circle(5.).union(|part| part.quad(Vec2::ONE));
+        });
+        for _ in 0..POOL_FRAMES {
+            draw_frame(&mut world, |_| {});
+        }
+        assert_eq!(drawn(&mut world).len(), 1);
+
+        draw_frame(&mut world, |_| {});
+        assert!(drawn(&mut world).is_empty());
+        assert_eq!(world.query::<&Drawn>().iter(&world).count(), 0);
+    }
+
+    #[test]
+    fn drawn_extensions_are_indexed_in_the_same_frame() {
+        let mut app = App::new();
+        app.insert_sub_app(RenderApp, SubApp::new());
+        app.add_plugins((cuttle_core::extensions::plugin, super::plugin))
+            .add_systems(Update, |mut draw: CuttleDraw| {
+                draw.shape().circle(5.).union(|part| part.quad(Vec2::ONE));
+            });
+
+        app.update();
+        let mut query = app
+            .world_mut()
+            .query_filtered::<&ExtensionIndex, (With<Drawn>, With<ChildOf>)>();
+        let index = query.single(app.world()).unwrap();
+        assert_eq!(**index, 1);
+    }
+}
@@ -0,0 +1,208 @@
+//! Linear, radial and conic [`Gradient`] fills.
+
+use crate::SdfOrder;
+use crate::cpu::SdfSample;
+use bevy_color::{Color, ColorToComponents, LinearRgba, Oklaba, Srgba};
+use bevy_ecs::prelude::{Component, ReflectComponent};
+use bevy_log::warn_once;
+use bevy_math::{Vec2, Vec3, Vec4};
+use bevy_reflect::Reflect;
+use bevy_render::render_resource::ShaderType;
+use cuttle_macros::Cuttle;
+
+/// Stops beyond this count are ignored.
+pub const MAX_GRADIENT_STOPS: usize = 8;
+
+/// Colors a shape with a gradient, replacing its [`Fill`](crate::Fill).
+///
+/// Stops are sorted by offset, before the first and after the last stop
+/// the color of that stop is used.
+#[derive(Debug, Clone, Component, Reflect, Cuttle)]
+#[cuttle(sort(SdfOrder::Color))]
+#[cuttle(cpu(SdfSample))]
+#[cuttle(render_data(GradientData))]
+#[reflect(Component)]
+pub struct Gradient {
+    pub shape: GradientShape,
+    /// At most [`MAX_GRADIENT_STOPS`], the ones with the highest offsets are dropped
+    pub stops: Vec<GradientStop>,
+    pub space: GradientSpace,
+    pub interpolation: GradientInterpolation,
+}
+
+impl Gradient {
+    /// Goes from offset 0 at `start` to offset 1 at `end`.
+    pub fn linear(start: Vec2, end: Vec2, stops: impl IntoIterator<Item = GradientStop>) -> Self {
+        Self::new(GradientShape::Linear { start, end }, stops)
+    }
+
+    /// Goes from offset 0 at `center` to offset 1 at `radius` from it.
+    pub fn radial(
+        center: Vec2,
+        radius: f32,
+        stops: impl IntoIterator<Item = GradientStop>,
+    ) -> Self {
+        Self::new(GradientShape::Radial { center, radius }, stops)
+    }
+
+    /// Goes from offset 0 to 1 counterclockwise around `center`, starting at `angle`.
+    pub fn conic(center: Vec2, angle: f32, stops: impl IntoIterator<Item = GradientStop>) -> Self {
+        Self::new(GradientShape::Conic { center, angle }, stops)
+    }
+
+    pub fn new(shape: GradientShape, stops: impl IntoIterator<Item = GradientStop>) -> Self {
+        Self {
+            shape,
+            stops: stops.into_iter().collect(),
+            space: GradientSpace::default(),
+            interpolation: GradientInterpolation::default(),
+        }
+    }
+
+    pub fn in_space(mut self, space: GradientSpace) -> Self {
+        self.space = space;
+        self
+    }
+
+    pub fn with_interpolation(mut self, interpolation: GradientInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum GradientShape {
+    Linear { start: Vec2, end: Vec2 },
+    Radial { center: Vec2, radius: f32 },
+    Conic { center: Vec2, angle: f32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: Color,
+}
+
+impl GradientStop {
+    pub fn new(offset: f32, color: impl Into<Color>) -> Self {
+        Self {
+            offset,
+            color: color.into(),
+        }
+    }
+
+    /// Spreads the colors evenly from offset 0 to 1.
+    pub fn evenly(colors: impl IntoIterator<Item = impl Into<Color>>) -> Vec<Self> {
+        let colors: Vec<Color> = colors.into_iter().map(Into::into).collect();
+        let last = colors.len().saturating_sub(1).max(1) as f32;
+        colors
+            .into_iter()
+            .enumerate()
+            .map(|(i, color)| Self::new(i as f32 / last, color))
+            .collect()
+    }
+}
+
+/// The space the [`GradientShape`] is placed in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum GradientSpace {
+    /// Moves, rotates and scales with the shape's transform
+    #[default]
+    Local,
+    /// Stays in place while shapes move through it
+    World,
+}
+
+/// The color space stops are mixed in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum GradientInterpolation {
+    #[default]
+    Srgb,
+    LinearRgb,
+    /// Perceptually uniform, avoids the grey middle between complementary colors
+    Oklab,
+}
+
+/// Render data of [`Gradient`], the stop colors are converted to the interpolation space.
+#[derive(Debug, Default, Clone, Reflect, ShaderType)]
+pub struct GradientData {
+    pub colors: [Vec4; MAX_GRADIENT_STOPS],
+    pub offsets: [Vec4; MAX_GRADIENT_STOPS / 4],
+    /// Linear: start and end, radial: center and radius, conic: center and angle
+    pub params: Vec4,
+    pub shape: u32,
+    pub space: u32,
+    pub interpolation: u32,
+    pub count: u32,
+}
+
+impl From<&Gradient> for GradientData {
+    fn from(gradient: &Gradient) -> Self {
+        let (shape, params) = match gradient.shape {
+            GradientShape::Linear { start, end } => (0, start.extend(end.x).extend(end.y)),
+            GradientShape::Radial { center, radius } => (1, center.extend(radius).extend(0.)),
+            GradientShape::Conic { center, angle } => (2, center.extend(angle).extend(0.)),
+        };
+
+        let mut stops = gradient.stops.clone();
+        stops.sort_by(|a, b| a.offset.total_cmp(&b.offset));
+        if stops.len() > MAX_GRADIENT_STOPS {
+            warn_once!(
+                "Gradients have at most {MAX_GRADIENT_STOPS} stops, the ones with the highest offsets are dropped"
+            );
+            stops.truncate(MAX_GRADIENT_STOPS);
+        }
+
+        let mut data = GradientData {
+            params,
+            shape,
+            space: gradient.space as u32,
+            interpolation: gradient.interpolation as u32,
+            count: stops.len() as u32,
+            ..Default::default()
+        };
+        for (i, stop) in stops.iter().enumerate() {
+            data.colors[i] = match gradient.interpolation {
+                GradientInterpolation::Srgb => Srgba::from(stop.color).to_vec4(),
+                GradientInterpolation::LinearRgb => LinearRgba::from(stop.color).to_vec4(),
+                GradientInterpolation::Oklab => Oklaba::from(stop.color).to_vec4(),
+            };
+            data.offsets[i / 4][i % 4] = stop.offset;
+        }
+        data
+    }
+}
+
+impl GradientData {
+    pub(crate) fn offset(&self, i: usize) -> f32 {
+        self.offsets[i / 4][i % 4]
+    }
+
+    /// Position along the gradient of `point`, not clamped
+    pub(crate) fn t(&self, point: Vec2) -> f32 {
+        let origin = self.params.truncate().truncate();
+        match self.shape {
+            0 => {
+                let direction = Vec2::new(self.params.z, self.params.w) - origin;
+                (point - origin).dot(direction) / direction.dot(direction)
+            }
+            1 => (point - origin).length() / self.params.z,
+            _ => {
+                let d = point - origin;
+                ((d.y.atan2(d.x) - self.params.z) / std::f32::consts::TAU).rem_euclid(1.)
+            }
+        }
+    }
+
+    /// Converts a color mixed in the interpolation space back to sRGB.
+    /// Channels out of gamut below zero are clamped, like the shader does.
+    pub(crate) fn to_srgb(&self, color: Vec4) -> Vec4 {
+        let linear = match self.interpolation {
+            0 => return color,
+            1 => LinearRgba::from_vec4(color),
+            _ => LinearRgba::from(Oklaba::from_vec4(color)),
+        };
+        let linear = linear.to_vec3().max(Vec3::ZERO).extend(linear.alpha);
+        Srgba::from(LinearRgba::from_vec4(linear)).to_vec4()
+    }
+}
@@ -1,3 +1,6 @@
+#![allow(clippy::type_complexity)]
+#![allow(clippy::too_many_arguments)]
+
 use bevy_app::Update;
 use bevy_app::{App, Plugin};
 use bevy_asset::embedded_asset;
@@ -8,12 +11,55 @@ use bevy_ecs::prelude::Component;
 use bevy_ecs::prelude::ReflectComponent;
 use bevy_ecs::system::Res;
 use bevy_ecs::system::Single;
+use bevy_math::bounding::Aabb2d;
 use bevy_math::prelude::*;
 use bevy_reflect::Reflect;
 use bevy_render::render_resource::ShaderType;
 use bevy_transform::prelude::GlobalTransform;
+use cpu::SdfSample;
 use cuttle_core::prelude::{Bounding, CuttleConfig, CuttleGroupBuilderAppExt};
+use cuttle_core::shader::wgsl_struct::RegisterWgslTypeExt;
 use cuttle_macros::Cuttle;
+use gradient::MAX_GRADIENT_STOPS;
+
+pub mod atlas;
+pub mod bake;
+pub mod builder;
+pub mod cpu;
+pub mod domain;
+pub mod draw;
+pub mod gradient;
+pub mod morph;
+#[cfg(test)]
+mod parity;
+pub mod paths;
+pub mod primitives;
+pub mod svg;
+#[cfg(feature = "text")]
+pub mod text;
+
+pub use atlas::{SdfAtlas, SdfTexture, SdfTextureKind};
+pub use builder::{InsertShapeExt, SpawnShapeExt};
+pub use domain::{
+    Bend, ClippedRepetition, Mirror, NoiseDisplacement, Onion, PolarRepetition, Twist,
+};
+pub use draw::{CuttleDraw, DrawPart};
+pub use gradient::{Gradient, GradientInterpolation, GradientShape, GradientSpace, GradientStop};
+pub use morph::{MorphStroke, MorphTarget, MorphWeights};
+pub use paths::{BezierDegree, BezierPath, Polygon, Polyline};
+pub use primitives::{
+    Arc, Capsule, Cross, Ellipse, EquilateralTriangle, Heart, Horseshoe, Ngon, Pie, Rhombus,
+    RoundedBox, Star, Trapezoid, Triangle, Vesica,
+};
+#[cfg(feature = "text")]
+pub use text::SdfText;
+
+/// Ray casts and point queries against [`Sdf`] shapes.
+pub type SdfSpatialQuery<'w, 's> = cuttle_core::spatial::CuttleSpatialQuery<'w, 's, SdfSample>;
+
+/// Picking backend for [`Sdf`] shapes, add it together with bevy's picking plugins.
+#[cfg(feature = "picking")]
+pub type SdfPickingPlugin = cuttle_core::picking::CuttlePickingPlugin<SdfSample>;
 
 pub struct SdfPlugin;
 impl Plugin for SdfPlugin {
@@ -45,18 +91,35 @@ impl Plugin for SdfPlugin {
             Morph,
             Flame,
         )>()
-        .register_type::<ElapsedTime>();
+        .register_type::<(PushOperand, PopOperand)>()
+        .register_type::<(ScaleDistance, SdfTransform, ElapsedTime)>()
+        .register_type::<(Stroke, StrokeAlignment, StrokeDash)>()
+        .register_type::<(DropShadow, OuterGlow, InnerShadow)>()
+        .register_type::<(
+            Gradient,
+            GradientShape,
+            GradientStop,
+            GradientSpace,
+            GradientInterpolation,
+        )>()
+        .register_wgsl_type::<[Vec4; MAX_GRADIENT_STOPS]>("array<vec4<f32>, 8>")
+        .register_wgsl_type::<[Vec4; MAX_GRADIENT_STOPS / 4]>("array<vec4<f32>, 2>");
 
         embedded_asset!(app, "sdf.wgsl");
 
         app.cuttle_config::<Sdf>()
             .snippet_file("embedded://cuttle_sdf/sdf.wgsl")
+            .snippet(format!("const MAX_OPERANDS: u32 = {MAX_OPERANDS}u;"))
             .variable("world_position", "vec2<f32>")
             .variable("position", "vec2<f32>")
             .variable("distance", "f32")
             .variable("size", "f32")
+            .variable("distance_scale", "f32")
             .variable("prev_distance", "f32")
             .variable("prev_color", "vec4<f32>")
+            .variable("operand_distances", "array<f32, MAX_OPERANDS>")
+            .variable("operand_colors", "array<vec4<f32>, MAX_OPERANDS>")
+            .variable("operand_depth", "u32")
             .global::<ElapsedTime>()
             .components::<(
                 Sdf,
@@ -71,6 +134,7 @@ impl Plugin for SdfPlugin {
                 Flame,
                 Stretch,
                 Rounded,
+                ScaleDistance,
             )>()
             .components::<(
                 PrepareOperation,
@@ -84,32 +148,89 @@ impl Plugin for SdfPlugin {
                 SmoothXor,
                 Repetition,
                 Morph,
+                Gradient,
+                Stroke,
             )>()
+            .components::<(PushOperand, PopOperand)>()
+            .components::<(DropShadow, OuterGlow, InnerShadow)>()
+            .affect_bounds(Bounding::Union, |&Circle(c)| c)
+            .affect_bounds(Bounding::Union, |&Line(l)| Vec2::new(l, 0.))
+            .affect_bounds(Bounding::Union, |&Quad(q)| q)
+            .affect_bounds(Bounding::Union, flame_bounds)
             .affect_bounds(Bounding::Add, |&Annular(a)| a)
-            .affect_bounds(Bounding::Add, |&Flame { .. }| 100.)
-            .affect_bounds(Bounding::Multiply, |&Stretch(s)| (s.length() + 1.) * 20.)
-            .affect_bounds(Bounding::Add, |&Circle(c)| c)
-            .affect_bounds(Bounding::Add, |&Line(l)| l)
-            .affect_bounds(Bounding::Add, |&Quad(q)| q.length())
-            .affect_bounds(Bounding::Add, |&Rounded(r)| r);
+            .affect_bounds(Bounding::Add, |&Rounded(r)| r)
+            .affect_bounds(Bounding::Add, Stroke::outset)
+            .affect_bounds(Bounding::Add, |s: &DropShadow| s.offset.abs() + s.blur)
+            .affect_bounds(Bounding::Add, |g: &OuterGlow| g.radius)
+            .combine_bounds(Bounding::Multiply, stretch_bounds);
 
         app.cuttle_config::<Sdf>()
             .component_manual::<GlobalTransform>()
             .name("GlobalTransform")
             .sort(SdfOrder::Translation)
-            .render_data_manual(transform_to_mat4);
-
-        app.add_systems(Update, update_time);
+            .render_data_from::<SdfTransform>()
+            .cpu::<SdfSample>();
+
+        app.add_systems(Update, update_time).add_plugins((
+            primitives::plugin,
+            domain::plugin,
+            morph::plugin,
+            paths::plugin,
+            atlas::plugin,
+            draw::plugin,
+        ));
+
+        #[cfg(feature = "svg_import")]
+        app.add_plugins(svg::import::plugin);
+        #[cfg(feature = "text")]
+        app.add_plugins(text::plugin);
     }
 }
 
-fn transform_to_mat4(t: &GlobalTransform) -> Mat4 {
-    t.to_matrix().inverse()
+fn flame_bounds(flame: &Flame) -> Vec2 {
+    let radius = flame.base.max(flame.tip).abs() * (1. + 1.5 * flame.flicker.abs());
+    Vec2::new(radius, radius * 2.)
+}
+
+/// `stretch` scales each direction by the cosine to the stretch axis,
+/// which turns a circle of radius r into two circles of radius `r * |s| / 2`
+/// touching at the origin along the stretch axis.
+fn stretch_bounds(&Stretch(stretch): &Stretch, bounds: &mut Aabb2d) {
+    let radius = bounds.min.abs().max(bounds.max.abs()).length();
+    let axis = stretch.normalize_or_zero().abs();
+    let half_size = radius * stretch.length() * 0.5 * (axis + 1.);
+    *bounds = Aabb2d::new(Vec2::ZERO, half_size);
+}
+
+/// Render data of [`GlobalTransform`].
+/// `scale` converts distances computed in the entity's local space back to world units,
+/// for non-uniform scale the smallest axis is used so distances are never overestimated.
+#[derive(Debug, Default, Clone, Reflect, ShaderType)]
+pub struct SdfTransform {
+    pub local_from_world: Mat4,
+    pub scale: f32,
+}
+
+impl From<&GlobalTransform> for SdfTransform {
+    fn from(transform: &GlobalTransform) -> Self {
+        let matrix = transform.to_matrix();
+        let scale = matrix
+            .x_axis
+            .truncate()
+            .length()
+            .min(matrix.y_axis.truncate().length());
+        Self {
+            local_from_world: matrix.inverse(),
+            scale,
+        }
+    }
 }
 
 #[derive(Component, Debug, Default, Clone, Reflect, Cuttle)]
 #[cuttle(extension_index_override(255u8))]
 #[cuttle(sort(SdfOrder::Result))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
 pub struct Sdf;
 
 impl CuttleConfig for Sdf {
@@ -119,14 +240,21 @@ impl CuttleConfig for Sdf {
 #[derive(Copy, Clone)]
 pub enum SdfOrder {
     Prepare = 1000,
+    /// Takes the first operand of an operation from the operand stack, see [`PopOperand`]
+    Operand = 1500,
     Translation = 2000,
+    /// Warps the local position before the base shape, see [`domain`]
+    Domain = 2500,
     Base = 3000,
+    Scale = 3500,
     Distance = 4000,
     Color = 5000,
     Alpha = 6000,
     Operations = 7000,
     Last = 8000,
     Result = 9999,
+    /// After the antialiased [`Sdf`] result, for effects outside the shape
+    Effects = 10000,
 }
 
 impl From<SdfOrder> for u32 {
@@ -145,22 +273,35 @@ fn update_time(time: Res<bevy_time::Time>, mut elapsed_time: Single<&mut Elapsed
 
 #[derive(Debug, Component, Reflect, Default, Cuttle)]
 #[cuttle(sort(SdfOrder::Prepare))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 pub struct PrepareBase;
 
+/// Converts the distance of a base shape from the entity's local space to world units,
+/// so that [`Rounded`], [`Annular`] and antialiasing are unaffected by the transform's scale.
+#[derive(Debug, Component, Reflect, Default, Cuttle)]
+#[cuttle(sort(SdfOrder::Scale))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+pub struct ScaleDistance;
+
 #[derive(Debug, Default, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Distance))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 pub struct Rounded(pub f32);
 
 #[derive(Debug, Default, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Distance))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 pub struct Annular(pub f32);
 
 #[derive(Debug, Default, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
-#[cuttle(sort(SdfOrder::Distance))]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
 pub struct Flame {
     pub sharpness: f32,
     pub tip: f32,
@@ -170,24 +311,28 @@ pub struct Flame {
 
 #[derive(Debug, Default, Copy, Clone, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
-#[require(PrepareBase)]
+#[require(PrepareBase, ScaleDistance)]
 pub struct Circle(pub f32);
 
 #[derive(Debug, Default, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
-#[require(PrepareBase)]
+#[require(PrepareBase, ScaleDistance)]
 pub struct Line(pub f32);
 
 #[derive(Debug, Default, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
-#[require(PrepareBase)]
+#[require(PrepareBase, ScaleDistance)]
 pub struct Quad(pub Vec2);
 
 #[derive(Debug, Default, Clone, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Color))]
+#[cuttle(cpu(SdfSample))]
 #[cuttle(render_data(Vec4))]
 #[reflect(Component)]
 pub struct Fill(pub Srgba);
@@ -198,8 +343,190 @@ impl From<&Fill> for Vec4 {
     }
 }
 
+/// Outlines the shape with a band of its own color, keeping the fill inside it.
+///
+/// Applies to the result of all operations of the shape, like [`Sdf`] itself.
+#[derive(Debug, Clone, PartialEq, Component, Reflect, Cuttle)]
+#[cuttle(extension_index_override(255u8))]
+#[cuttle(sort(SdfOrder::Last))]
+#[cuttle(cpu(SdfSample))]
+#[cuttle(render_data(StrokeData))]
+#[reflect(Component)]
+pub struct Stroke {
+    pub color: Srgba,
+    pub width: f32,
+    pub alignment: StrokeAlignment,
+    pub dash: Option<StrokeDash>,
+}
+
+impl Stroke {
+    pub fn new(color: impl Into<Srgba>, width: f32) -> Self {
+        Self {
+            color: color.into(),
+            width,
+            alignment: StrokeAlignment::default(),
+            dash: None,
+        }
+    }
+
+    pub fn aligned(mut self, alignment: StrokeAlignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    pub fn dashed(mut self, length: f32, gap: f32) -> Self {
+        self.dash = Some(StrokeDash {
+            length,
+            gap,
+            offset: 0.,
+        });
+        self
+    }
+
+    /// How far the stroke reaches beyond the edge of the shape.
+    pub fn outset(&self) -> f32 {
+        match self.alignment {
+            StrokeAlignment::Inside => 0.,
+            StrokeAlignment::Center => self.width / 2.,
+            StrokeAlignment::Outside => self.width,
+        }
+    }
+}
+
+/// Where the stroke lies relative to the edge of the shape.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum StrokeAlignment {
+    Inside,
+    #[default]
+    Center,
+    Outside,
+}
+
+/// Splits a [`Stroke`] into dashes.
+///
+/// Dashes are measured by the angle around the local origin times the distance to it,
+/// which is exact along circles and approximates the perimeter of other shapes.
+/// For shapes with extensions, the local space of the last evaluated extension is used.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
+pub struct StrokeDash {
+    pub length: f32,
+    pub gap: f32,
+    /// Shifts the dashes along the perimeter
+    pub offset: f32,
+}
+
+/// Render data of [`Stroke`], a dash length of zero draws a solid stroke.
+#[derive(Debug, Default, Clone, Reflect, ShaderType)]
+pub struct StrokeData {
+    pub color: Vec4,
+    pub width: f32,
+    pub outset: f32,
+    pub dash: Vec3,
+}
+
+impl From<&Stroke> for StrokeData {
+    fn from(stroke: &Stroke) -> Self {
+        let dash = stroke.dash.unwrap_or_default();
+        Self {
+            color: stroke.color.to_vec4(),
+            width: stroke.width,
+            outset: stroke.outset(),
+            dash: Vec3::new(dash.length, dash.gap, dash.offset),
+        }
+    }
+}
+
+/// Casts a blurred shadow of the shape, drawn beneath it.
+///
+/// The distance at the offset point is extrapolated along the gradient of the distance field,
+/// which is exact for straight edges and close for offsets small compared to the shape.
+/// On the CPU the gradient is estimated from the distances around the point.
+#[derive(Debug, Clone, Component, Reflect, Cuttle)]
+#[cuttle(extension_index_override(255u8))]
+#[cuttle(sort(SdfOrder::Effects))]
+#[cuttle(cpu(SdfSample))]
+#[cuttle(render_data(ShadowData))]
+#[reflect(Component)]
+pub struct DropShadow {
+    pub offset: Vec2,
+    pub blur: f32,
+    pub color: Srgba,
+}
+
+/// Lights up the surroundings of the shape, fading out over `radius`.
+#[derive(Debug, Clone, Component, Reflect, Cuttle)]
+#[cuttle(extension_index_override(255u8))]
+#[cuttle(sort(SdfOrder::Effects))]
+#[cuttle(cpu(SdfSample))]
+#[cuttle(render_data(GlowData))]
+#[reflect(Component)]
+pub struct OuterGlow {
+    pub radius: f32,
+    pub color: Srgba,
+}
+
+/// Darkens the inside of the shape along the edges facing away from `offset`,
+/// like the shape was cut out of a surface, drawn over the shape's color.
+/// Like [`DropShadow`] the offset is extrapolated along the gradient of the distance.
+#[derive(Debug, Clone, Component, Reflect, Cuttle)]
+#[cuttle(extension_index_override(255u8))]
+#[cuttle(sort(SdfOrder::Effects))]
+#[cuttle(cpu(SdfSample))]
+#[cuttle(render_data(ShadowData))]
+#[reflect(Component)]
+pub struct InnerShadow {
+    pub offset: Vec2,
+    pub blur: f32,
+    pub color: Srgba,
+}
+
+/// Render data of [`DropShadow`] and [`InnerShadow`].
+#[derive(Debug, Default, Clone, Reflect, ShaderType)]
+pub struct ShadowData {
+    pub color: Vec4,
+    pub offset: Vec2,
+    pub blur: f32,
+}
+
+impl From<&DropShadow> for ShadowData {
+    fn from(shadow: &DropShadow) -> Self {
+        Self {
+            color: shadow.color.to_vec4(),
+            offset: shadow.offset,
+            blur: shadow.blur,
+        }
+    }
+}
+
+impl From<&InnerShadow> for ShadowData {
+    fn from(shadow: &InnerShadow) -> Self {
+        Self {
+            color: shadow.color.to_vec4(),
+            offset: shadow.offset,
+            blur: shadow.blur,
+        }
+    }
+}
+
+/// Render data of [`OuterGlow`].
+#[derive(Debug, Default, Clone, Reflect, ShaderType)]
+pub struct GlowData {
+    pub color: Vec4,
+    pub radius: f32,
+}
+
+impl From<&OuterGlow> for GlowData {
+    fn from(glow: &OuterGlow) -> Self {
+        Self {
+            color: glow.color.to_vec4(),
+            radius: glow.radius,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Component, ShaderType, Reflect, Cuttle)]
 #[cuttle(sort(SdfOrder::Last))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 pub struct DistanceGradient {
     pub interval: f32,
@@ -208,40 +535,73 @@ pub struct DistanceGradient {
 
 #[derive(Debug, Default, Clone, Component, Reflect, Cuttle)]
 #[cuttle(sort(SdfOrder::Alpha))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 pub struct ForceFieldAlpha;
 
 #[derive(Debug, Component, Reflect, Default, Cuttle)]
 #[cuttle(sort(SdfOrder::Prepare))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 pub struct PrepareOperation;
 
+/// Nested operands on the stack of [`PushOperand`] at most, deeper ones are lost.
+pub const MAX_OPERANDS: usize = 4;
+
+/// Starts a nested operand of a shape, e.g. the `(C ∩ D)` of `(A ∪ B) − (C ∩ D)`.
+///
+/// The shape so far is pushed onto an operand stack and this extension's base shape starts
+/// a new one, which the following extensions combine with as usual. The extension with the
+/// matching [`PopOperand`] combines it with the pushed shape. This extension has no operation.
+#[derive(Debug, Clone, Copy, Default, Component, Reflect, Cuttle)]
+#[cuttle(sort(SdfOrder::Prepare))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+pub struct PushOperand;
+
+/// Ends the nested operand of the matching [`PushOperand`], combining the shape pushed there
+/// as the first operand with the nested one as the second through this extension's operation,
+/// e.g. [`Subtract`] for `(A ∪ B) − (C ∩ D)`.
+///
+/// Sorted at [`SdfOrder::Operand`], after the [`PrepareOperation`] required by the operation.
+/// The extension has no base shape of its own, which would replace the nested one.
+#[derive(Debug, Clone, Copy, Default, Component, Reflect, Cuttle)]
+#[cuttle(sort(SdfOrder::Operand))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+pub struct PopOperand;
+
 #[derive(Debug, Default, Component, Reflect, Cuttle)]
 #[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 #[require(PrepareOperation)]
 pub struct Unioni;
 
 #[derive(Debug, Default, Component, Reflect, Cuttle)]
 #[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 #[require(PrepareOperation)]
 pub struct Subtract;
 
 #[derive(Debug, Default, Component, Reflect, Cuttle)]
 #[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 #[require(PrepareOperation)]
 pub struct Intersect;
 
 #[derive(Debug, Default, Component, Reflect, Cuttle)]
 #[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 #[require(PrepareOperation)]
 pub struct Xor;
 
 #[derive(Debug, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 #[require(PrepareOperation)]
 pub struct SmoothUnion(pub f32);
@@ -254,6 +614,7 @@ impl Default for SmoothUnion {
 
 #[derive(Debug, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 #[require(PrepareOperation)]
 pub struct SmoothSubtract(pub f32);
@@ -266,6 +627,7 @@ impl Default for SmoothSubtract {
 
 #[derive(Debug, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 #[require(PrepareOperation)]
 pub struct SmoothIntersect(pub f32);
@@ -278,6 +640,7 @@ impl Default for SmoothIntersect {
 
 #[derive(Debug, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 #[require(PrepareOperation)]
 pub struct SmoothXor(pub f32);
@@ -290,6 +653,7 @@ impl Default for SmoothXor {
 
 #[derive(Debug, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
 #[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 pub struct Repetition {
     pub scale: f32,
@@ -307,11 +671,13 @@ impl Default for Repetition {
 
 #[derive(Debug, Clone, Copy, Default, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 #[require(PrepareOperation)]
 pub struct Morph(pub f32);
 
 #[derive(Debug, Clone, Copy, Default, Component, Reflect, Deref, DerefMut, Cuttle)]
 #[cuttle(sort(SdfOrder::Translation))]
+#[cuttle(cpu(SdfSample))]
 #[reflect(Component)]
 pub struct Stretch(pub Vec2);
@@ -0,0 +1,349 @@
+//! Blending between several shapes with a weight each, see [`MorphWeights`].
+
+use crate::cpu::SdfSample;
+use crate::{Sdf, SdfOrder, Stroke, StrokeDash};
+use bevy_app::{AnimationSystems, App, PostUpdate};
+use bevy_asset::embedded_asset;
+use bevy_color::{ColorToComponents, Srgba};
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::prelude::*;
+use bevy_log::warn_once;
+use bevy_math::Vec4;
+use bevy_platform::collections::HashMap;
+use bevy_platform::collections::hash_map::Entry;
+use bevy_reflect::Reflect;
+use bevy_render::render_resource::ShaderType;
+use bevy_transform::TransformSystems;
+use cuttle_core::extensions::{ExtendedBy, Extends};
+use cuttle_core::prelude::CuttleGroupBuilderAppExt;
+use cuttle_macros::Cuttle;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<(MorphWeights, MorphTarget, MorphStroke)>();
+
+    embedded_asset!(app, "morph.wgsl");
+
+    app.cuttle_config::<Sdf>()
+        .snippet_file("embedded://cuttle_sdf/morph.wgsl")
+        .snippet(format!(
+            "const MAX_MORPH_TARGETS: u32 = {MAX_MORPH_TARGETS}u;"
+        ))
+        .variable(
+            "morph_target_weights",
+            "array<vec4<f32>, MAX_MORPH_TARGETS / 4>",
+        )
+        .variable("morph_distance", "f32")
+        .variable("morph_color", "vec4<f32>")
+        .variable("morph_total", "f32")
+        .components::<(MorphWeights, MorphTarget)>();
+
+    app.add_systems(
+        PostUpdate,
+        blend_morph_strokes
+            .after(AnimationSystems)
+            .before(TransformSystems::Propagate),
+    );
+}
+
+/// Weights beyond this count are ignored.
+pub const MAX_MORPH_TARGETS: usize = 8;
+
+/// Blends the base shape of this part with the following extensions marked with [`MorphTarget`]
+/// up to the next [`MorphWeights`], weighting the distance and color of each. Weight `0` is the
+/// one of this part, weight `i` the one of `MorphTarget(i)`. The weights are divided by their sum, so `[1, 1]` is halfway.
+///
+/// Fills and [`Gradient`](crate::Gradient)s of the targets are blended per pixel,
+/// a [`MorphStroke`] on the part and the targets blends the shape's [`Stroke`].
+/// The part has no operation, start it with [`PushOperand`](crate::PushOperand) to combine
+/// the morphed shape with the shape before it. Morphs can't be nested in each other.
+///
+/// The bounds of the shape cover all targets, with [`AutoBounds`](cuttle_core::prelude::AutoBounds)
+/// the CPU implementation narrows them down to the blended shape.
+#[derive(Debug, Clone, Default, Component, Reflect, Deref, DerefMut, Cuttle)]
+#[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
+#[cuttle(render_data(MorphWeightsData))]
+#[reflect(Component)]
+pub struct MorphWeights(pub Vec<f32>);
+
+impl MorphWeights {
+    pub fn new(weights: impl IntoIterator<Item = f32>) -> Self {
+        Self(weights.into_iter().collect())
+    }
+
+    /// The weights padded with zeros, or cut off, to [`MAX_MORPH_TARGETS`].
+    pub(crate) fn padded(&self) -> [f32; MAX_MORPH_TARGETS] {
+        let mut weights = [0.; MAX_MORPH_TARGETS];
+        for (padded, &weight) in weights.iter_mut().zip(&self.0) {
+            *padded = weight;
+        }
+        weights
+    }
+}
+
+/// Render data of [`MorphWeights`].
+#[derive(Debug, Default, Clone, Reflect, ShaderType)]
+pub struct MorphWeightsData {
+    pub weights: [Vec4; MAX_MORPH_TARGETS / 4],
+}
+
+impl From<&MorphWeights> for MorphWeightsData {
+    fn from(weights: &MorphWeights) -> Self {
+        let padded = weights.padded();
+        Self {
+            weights: std::array::from_fn(|i| Vec4::from_slice(&padded[i * 4..])),
+        }
+    }
+}
+
+/// Marks an extension as target `index` of the [`MorphWeights`] before it.
+#[derive(Debug, Clone, Copy, Default, Component, Reflect, Deref, DerefMut, Cuttle)]
+#[cuttle(sort(SdfOrder::Operations))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+pub struct MorphTarget(pub u32);
+
+/// The [`Stroke`] of a morph part, blended with those of the other parts into the shape's stroke
+/// by the [`MorphWeights`]. Parts without one don't take part in the blend.
+///
+/// Colors, widths and dashes are blended, the alignment of the heaviest part is used.
+/// A shape has a single stroke: with several [`MorphWeights`] in a shape, only the first one
+/// with strokes blends it.
+#[derive(Debug, Clone, Component, Reflect, Deref, DerefMut)]
+#[reflect(Component)]
+pub struct MorphStroke(pub Stroke);
+
+impl MorphStroke {
+    /// The weighted blend of the given strokes, `None` if the weights sum up to zero.
+    pub fn blend<'a>(strokes: impl IntoIterator<Item = (f32, &'a Stroke)>) -> Option<Stroke> {
+        let strokes: Vec<_> = strokes.into_iter().collect();
+        let total: f32 = strokes.iter().map(|(weight, _)| weight).sum();
+        if total.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let (_, heaviest) = strokes.iter().max_by(|(a, _), (b, _)| a.total_cmp(b))?;
+        let mut color = Vec4::ZERO;
+        let mut width = 0.;
+        for (weight, stroke) in &strokes {
+            color += stroke.color.to_vec4() * *weight / total;
+            width += stroke.width * *weight / total;
+        }
+
+        let dashed: Vec<_> = strokes
+            .iter()
+            .filter_map(|(weight, stroke)| Some((*weight, stroke.dash?)))
+            .collect();
+        let dash_total: f32 = dashed.iter().map(|(weight, _)| weight).sum();
+        let dash = (dash_total.abs() >= f32::EPSILON).then(|| {
+            dashed
+                .iter()
+                .fold(StrokeDash::default(), |blended, (weight, dash)| {
+                    StrokeDash {
+                        length: blended.length + dash.length * weight / dash_total,
+                        gap: blended.gap + dash.gap * weight / dash_total,
+                        offset: blended.offset + dash.offset * weight / dash_total,
+                    }
+                })
+        });
+
+        Some(Stroke {
+            color: Srgba::from_vec4(color),
+            width,
+            alignment: heaviest.alignment,
+            dash,
+        })
+    }
+}
+
+fn blend_morph_strokes(
+    mut cmds: Commands,
+    morphs: Query<(
+        Entity,
+        &MorphWeights,
+        Option<&MorphStroke>,
+        Option<&Extends>,
+    )>,
+    extended: Query<&ExtendedBy>,
+    parts: Query<(
+        Option<&MorphTarget>,
+        Option<&MorphStroke>,
+        Has<MorphWeights>,
+    )>,
+    mut strokes: Query<&mut Stroke>,
+) {
+    // A shape has a single stroke, the first of its morphs with strokes blends it
+    let mut blends: HashMap<Entity, (usize, Stroke)> = HashMap::default();
+    for (entity, weights, stroke, extends) in &morphs {
+        let root = extends.map_or(entity, |extends| extends.0);
+        let weight = |index: u32| weights.get(index as usize).copied().unwrap_or_default();
+
+        // The targets of this morph follow it, up to the next one
+        let extensions = extended
+            .get(root)
+            .map_or(&[][..], |extensions| &extensions[..]);
+        let start = match extends {
+            Some(_) => extensions
+                .iter()
+                .position(|&part| part == entity)
+                .map_or(extensions.len(), |i| i + 1),
+            None => 0,
+        };
+        let first = stroke.map(|stroke| (weight(0), &stroke.0));
+        let others = parts
+            .iter_many(&extensions[start..])
+            .take_while(|(.., is_morph)| !is_morph)
+            .filter_map(|(target, stroke, _)| Some((weight(target?.0), &stroke?.0)));
+        let Some(blended) = MorphStroke::blend(first.into_iter().chain(others)) else {
+            continue;
+        };
+
+        match blends.entry(root) {
+            Entry::Vacant(entry) => {
+                entry.insert((start, blended));
+            }
+            Entry::Occupied(mut entry) => {
+                warn_once!(
+                    "{root} has several morphs with a MorphStroke, only the first one blends its stroke"
+                );
+                if start < entry.get().0 {
+                    entry.insert((start, blended));
+                }
+            }
+        }
+    }
+
+    for (root, (_, blended)) in blends {
+        match strokes.get_mut(root) {
+            Ok(mut stroke) => {
+                stroke.set_if_neq(blended);
+            }
+            Err(_) => {
+                cmds.entity(root).insert(blended);
+            }
+        }
+    }
+}
+
+#[cfg(feature = "animation")]
+pub use animation::MorphWeightProperty;
+
+#[cfg(feature = "animation")]
+mod animation {
+    use super::MorphWeights;
+    use bevy_animation::animation_curves::{AnimatableProperty, EvaluatorId};
+    use bevy_animation::{AnimationEntityMut, AnimationEvaluationError};
+    use bevy_platform::hash::Hashed;
+    use std::any::TypeId;
+
+    /// A single weight of [`MorphWeights`] as an animated property, so that weights can be
+    /// keyframed with bevy's animation curves:
+    ///
+    /// ```
+    /// # use bevy_animation::animation_curves::{AnimatableCurve, AnimatableKeyframeCurve};
+    /// # use cuttle_sdf::morph::MorphWeightProperty;
+    /// let curve = AnimatableCurve::new(
+    ///     MorphWeightProperty::new(1),
+    ///     AnimatableKeyframeCurve::new([(0., 0.), (1., 1.), (2., 0.)]).unwrap(),
+    /// );
+    /// ```
+    #[derive(Debug, Clone)]
+    pub struct MorphWeightProperty {
+        index: usize,
+        evaluator_id: Hashed<(TypeId, usize)>,
+    }
+
+    impl MorphWeightProperty {
+        pub fn new(index: usize) -> Self {
+            Self {
+                index,
+                evaluator_id: Hashed::new((TypeId::of::<MorphWeights>(), index)),
+            }
+        }
+    }
+
+    impl AnimatableProperty for MorphWeightProperty {
+        type Property = f32;
+
+        fn get_mut<'a>(
+            &self,
+            entity: &'a mut AnimationEntityMut,
+        ) -> Result<&'a mut f32, AnimationEvaluationError> {
+            let missing = TypeId::of::<MorphWeights>();
+            let weights = entity
+                .get_mut::<MorphWeights>()
+                .ok_or(AnimationEvaluationError::ComponentNotPresent(missing))?;
+            weights
+                .into_inner()
+                .0
+                .get_mut(self.index)
+                .ok_or(AnimationEvaluationError::PropertyNotPresent(missing))
+        }
+
+        fn evaluator_id(&self) -> EvaluatorId<'_> {
+            EvaluatorId::ComponentField(&self.evaluator_id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MorphStroke, MorphTarget, MorphWeights, blend_morph_strokes};
+    use crate::{Stroke, StrokeAlignment};
+    use bevy_color::Srgba;
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use cuttle_core::extensions::Extends;
+
+    #[test]
+    fn strokes_blend_by_weight() {
+        let thin = Stroke::new(Srgba::RED, 2.);
+        let thick = Stroke::new(Srgba::BLUE, 6.)
+            .aligned(StrokeAlignment::Outside)
+            .dashed(4., 2.);
+
+        let blended = MorphStroke::blend([(1., &thin), (3., &thick)]).unwrap();
+        assert_eq!(blended.width, 5.);
+        assert_eq!(blended.color, Srgba::new(0.25, 0., 0.75, 1.));
+        assert_eq!(blended.alignment, StrokeAlignment::Outside);
+        let dash = blended.dash.unwrap();
+        assert_eq!((dash.length, dash.gap), (4., 2.));
+
+        assert!(MorphStroke::blend([(0., &thin)]).is_none());
+    }
+
+    #[test]
+    fn strokes_blend_the_targets_of_their_morph() {
+        let mut world = World::new();
+        let root = world
+            .spawn((
+                MorphWeights::new([0., 1.]),
+                MorphStroke(Stroke::new(Srgba::RED, 2.)),
+            ))
+            .id();
+        world.spawn((
+            Extends(root),
+            MorphTarget(1),
+            MorphStroke(Stroke::new(Srgba::BLUE, 4.)),
+        ));
+        // A second morph, whose target 1 is weighted 0
+        world.spawn((Extends(root), MorphWeights::new([1., 0.])));
+        world.spawn((
+            Extends(root),
+            MorphTarget(1),
+            MorphStroke(Stroke::new(Srgba::GREEN, 100.)),
+        ));
+
+        world.run_system_once(blend_morph_strokes).unwrap();
+        assert_eq!(world.get::<Stroke>(root).unwrap().width, 4.);
+
+        // The stroke of the shape stays the one of the first morph
+        world.spawn((
+            Extends(root),
+            MorphWeights::new([1.]),
+            MorphStroke(Stroke::new(Srgba::GREEN, 50.)),
+        ));
+        world.run_system_once(blend_morph_strokes).unwrap();
+        assert_eq!(world.get::<Stroke>(root).unwrap().width, 4.);
+    }
+}
@@ -0,0 +1,25 @@
+fn morph_weights(input: MorphWeightsData) {
+    morph_target_weights = input.weights;
+    let weight = input.weights[0].x;
+    morph_distance = weight * distance;
+    morph_color = weight * color;
+    morph_total = weight;
+    if abs(morph_total) > 1e-5 {
+        distance = morph_distance / morph_total;
+        color = morph_color / morph_total;
+    }
+}
+
+fn morph_target(index: u32) {
+    var weight = 0.0;
+    if index < MAX_MORPH_TARGETS {
+        weight = morph_target_weights[index / 4u][index % 4u];
+    }
+    morph_distance += weight * distance;
+    morph_color += weight * color;
+    morph_total += weight;
+    if abs(morph_total) > 1e-5 {
+        distance = morph_distance / morph_total;
+        color = morph_color / morph_total;
+    }
+}
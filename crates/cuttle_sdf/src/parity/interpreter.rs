@@ -0,0 +1,961 @@
+//! A small interpreter of naga's IR, covering what the shaders of this crate use,
+//! to run their functions without a GPU.
+
+use bevy_math::{Vec2, Vec4};
+use naga::valid::{Capabilities, FunctionInfo, ModuleInfo, ValidationFlags, Validator};
+use naga::{
+    ArraySize, BinaryOperator, Block, DerivativeAxis, Expression, Function, Handle, Literal,
+    MathFunction, Module, RelationalFunction, ScalarKind, Statement, SwitchValue, TypeInner,
+    UnaryOperator,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    F32(f32),
+    U32(u32),
+    I32(i32),
+    /// Vectors, matrices as their columns, arrays and structs
+    Composite(Vec<Value>),
+}
+
+impl Value {
+    pub fn f32(&self) -> f32 {
+        match *self {
+            Value::F32(value) => value,
+            ref other => panic!("{other:?} is not a f32"),
+        }
+    }
+
+    fn bool(&self) -> bool {
+        match *self {
+            Value::Bool(value) => value,
+            ref other => panic!("{other:?} is not a bool"),
+        }
+    }
+
+    fn index(&self) -> usize {
+        match *self {
+            Value::U32(value) => value as usize,
+            Value::I32(value) => value.max(0) as usize,
+            ref other => panic!("{other:?} is not an index"),
+        }
+    }
+
+    fn components(&self) -> &[Value] {
+        match self {
+            Value::Composite(components) => components,
+            other => panic!("{other:?} is not a composite"),
+        }
+    }
+
+    /// Out of bounds indices are clamped, like the robust buffer access of the shader.
+    fn element(&self, index: usize) -> &Value {
+        let components = self.components();
+        &components[index.min(components.len() - 1)]
+    }
+
+    fn element_mut(&mut self, index: usize) -> &mut Value {
+        let Value::Composite(components) = self else {
+            panic!("{self:?} is not a composite");
+        };
+        let last = components.len() - 1;
+        &mut components[index.min(last)]
+    }
+
+    fn map(self, f: &impl Fn(Value) -> Value) -> Value {
+        match self {
+            Value::Composite(components) => {
+                Value::Composite(components.into_iter().map(|c| c.map(f)).collect())
+            }
+            scalar => f(scalar),
+        }
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Self {
+        Value::F32(value)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(value: u32) -> Self {
+        Value::U32(value)
+    }
+}
+
+impl From<Vec2> for Value {
+    fn from(value: Vec2) -> Self {
+        Value::Composite(value.to_array().map(Value::F32).into())
+    }
+}
+
+impl From<Vec4> for Value {
+    fn from(value: Vec4) -> Self {
+        Value::Composite(value.to_array().map(Value::F32).into())
+    }
+}
+
+impl<T: Into<Value>> From<Vec<T>> for Value {
+    fn from(values: Vec<T>) -> Self {
+        Value::Composite(values.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Applies `f` to the scalars of `a` and `b`, a scalar is used for every component of the other.
+fn zip(a: Value, b: Value, f: &impl Fn(Value, Value) -> Value) -> Value {
+    match (a, b) {
+        (Value::Composite(a), Value::Composite(b)) => {
+            Value::Composite(a.into_iter().zip(b).map(|(a, b)| zip(a, b, f)).collect())
+        }
+        (Value::Composite(a), b) => {
+            Value::Composite(a.into_iter().map(|a| zip(a, b.clone(), f)).collect())
+        }
+        (a, Value::Composite(b)) => {
+            Value::Composite(b.into_iter().map(|b| zip(a.clone(), b, f)).collect())
+        }
+        (a, b) => f(a, b),
+    }
+}
+
+/// The interpreted module, together with the global variables of one invocation
+/// and those of its neighbours one pixel to the right and below, which derivatives are taken from.
+pub struct Interpreter {
+    module: Module,
+    info: ModuleInfo,
+    globals: Vec<Value>,
+    neighbors: [Vec<Value>; 2],
+}
+
+impl Interpreter {
+    pub fn new(source: &str) -> Self {
+        let module = naga::front::wgsl::parse_str(source)
+            .unwrap_or_else(|err| panic!("{}", err.emit_to_string(source)));
+        let info = Validator::new(ValidationFlags::all(), Capabilities::default())
+            .validate(&module)
+            .unwrap_or_else(|err| panic!("{}", err.emit_to_string(source)));
+        let mut interpreter = Self {
+            module,
+            info,
+            globals: Vec::new(),
+            neighbors: [Vec::new(), Vec::new()],
+        };
+        let module = &interpreter.module;
+        let globals: Vec<_> = module
+            .global_variables
+            .iter()
+            .map(|(_, global)| match global.init {
+                Some(init) => constant(module, init),
+                None => zero(module, global.ty),
+            })
+            .collect();
+        interpreter.neighbors = [globals.clone(), globals.clone()];
+        interpreter.globals = globals;
+        interpreter
+    }
+
+    pub fn functions(&self) -> impl Iterator<Item = &str> {
+        self.module
+            .functions
+            .iter()
+            .filter_map(|(_, function)| function.name.as_deref())
+    }
+
+    fn global(&self, name: &str) -> usize {
+        self.module
+            .global_variables
+            .iter()
+            .position(|(_, global)| global.name.as_deref() == Some(name))
+            .unwrap_or_else(|| panic!("no global variable {name}"))
+    }
+
+    pub fn get(&self, name: &str) -> &Value {
+        &self.globals[self.global(name)]
+    }
+
+    pub fn set(&mut self, name: &str, value: Value) {
+        let global = self.global(name);
+        self.globals[global] = value;
+    }
+
+    /// Sets a global of the neighbour to the right for `axis` 0, below for `axis` 1.
+    pub fn set_neighbor(&mut self, axis: usize, name: &str, value: Value) {
+        let global = self.global(name);
+        self.neighbors[axis][global] = value;
+    }
+
+    pub fn call(&mut self, name: &str, arguments: Vec<Value>) -> Option<Value> {
+        let (function, _) = self
+            .module
+            .functions
+            .iter()
+            .find(|(_, function)| function.name.as_deref() == Some(name))
+            .unwrap_or_else(|| panic!("no function {name}"));
+        Machine {
+            module: &self.module,
+            info: &self.info,
+            globals: &mut self.globals,
+            neighbors: &self.neighbors,
+        }
+        .run(function, arguments)
+    }
+}
+
+fn zero(module: &Module, ty: Handle<naga::Type>) -> Value {
+    match module.types[ty].inner {
+        TypeInner::Scalar(scalar) => zero_scalar(scalar.kind),
+        TypeInner::Vector { size, scalar } => {
+            Value::Composite(vec![zero_scalar(scalar.kind); size as usize])
+        }
+        TypeInner::Matrix {
+            columns,
+            rows,
+            scalar,
+        } => Value::Composite(vec![
+            Value::Composite(vec![
+                zero_scalar(scalar.kind);
+                rows as usize
+            ]);
+            columns as usize
+        ]),
+        TypeInner::Array { base, size, .. } => match size {
+            ArraySize::Constant(size) => {
+                Value::Composite(vec![zero(module, base); size.get() as usize])
+            }
+            _ => Value::Composite(Vec::new()),
+        },
+        TypeInner::Struct { ref members, .. } => Value::Composite(
+            members
+                .iter()
+                .map(|member| zero(module, member.ty))
+                .collect(),
+        ),
+        ref other => panic!("no zero value for {other:?}"),
+    }
+}
+
+fn zero_scalar(kind: ScalarKind) -> Value {
+    match kind {
+        ScalarKind::Bool => Value::Bool(false),
+        ScalarKind::Float => Value::F32(0.),
+        ScalarKind::Uint => Value::U32(0),
+        ScalarKind::Sint => Value::I32(0),
+        other => panic!("no zero value for {other:?}"),
+    }
+}
+
+fn literal(literal: Literal) -> Value {
+    match literal {
+        Literal::F32(value) => Value::F32(value),
+        Literal::F64(value) => Value::F32(value as f32),
+        Literal::U32(value) => Value::U32(value),
+        Literal::I32(value) => Value::I32(value),
+        Literal::Bool(value) => Value::Bool(value),
+        other => panic!("unsupported literal {other:?}"),
+    }
+}
+
+fn compose(module: &Module, ty: Handle<naga::Type>, components: Vec<Value>) -> Value {
+    match module.types[ty].inner {
+        TypeInner::Vector { .. } => Value::Composite(
+            components
+                .into_iter()
+                .flat_map(|component| match component {
+                    Value::Composite(components) => components,
+                    scalar => vec![scalar],
+                })
+                .collect(),
+        ),
+        TypeInner::Matrix { columns, rows, .. } if components.len() != columns as usize => {
+            Value::Composite(
+                components
+                    .chunks(rows as usize)
+                    .map(|column| Value::Composite(column.to_vec()))
+                    .collect(),
+            )
+        }
+        _ => Value::Composite(components),
+    }
+}
+
+/// Evaluates a constant expression of the module.
+fn constant(module: &Module, expr: Handle<Expression>) -> Value {
+    match module.global_expressions[expr] {
+        Expression::Literal(value) => literal(value),
+        Expression::Constant(handle) => constant(module, module.constants[handle].init),
+        Expression::ZeroValue(ty) => zero(module, ty),
+        Expression::Compose { ty, ref components } => compose(
+            module,
+            ty,
+            components.iter().map(|&c| constant(module, c)).collect(),
+        ),
+        Expression::Splat { size, value } => {
+            Value::Composite(vec![constant(module, value); size as usize])
+        }
+        ref other => panic!("unsupported constant expression {other:?}"),
+    }
+}
+
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(Option<Value>),
+}
+
+enum Root {
+    Local(usize),
+    Global(usize),
+}
+
+struct Pointer {
+    root: Root,
+    path: Vec<usize>,
+}
+
+struct Frame<'m> {
+    function: &'m Function,
+    info: &'m FunctionInfo,
+    arguments: Vec<Value>,
+    locals: Vec<Value>,
+    /// Values of the emitted expressions and call results
+    values: Vec<Option<Value>>,
+}
+
+struct Machine<'m> {
+    module: &'m Module,
+    info: &'m ModuleInfo,
+    globals: &'m mut Vec<Value>,
+    neighbors: &'m [Vec<Value>; 2],
+}
+
+impl<'m> Machine<'m> {
+    fn run(&mut self, handle: Handle<Function>, arguments: Vec<Value>) -> Option<Value> {
+        let (module, info) = (self.module, self.info);
+        let function = &module.functions[handle];
+        let mut frame = Frame {
+            function,
+            info: &info[handle],
+            arguments,
+            locals: Vec::new(),
+            values: vec![None; function.expressions.len()],
+        };
+        for (_, local) in function.local_variables.iter() {
+            let value = match local.init {
+                Some(init) => self.eval(&mut frame, init, None),
+                None => zero(module, local.ty),
+            };
+            frame.locals.push(value);
+        }
+        match self.block(&mut frame, &function.body) {
+            Flow::Return(value) => value,
+            _ => None,
+        }
+    }
+
+    fn ty(&self, frame: &Frame<'m>, expr: Handle<Expression>) -> &'m TypeInner {
+        frame.info[expr].ty.inner_with(&self.module.types)
+    }
+
+    fn block(&mut self, frame: &mut Frame<'m>, block: &'m Block) -> Flow {
+        for statement in block {
+            match self.statement(frame, statement) {
+                Flow::Next => {}
+                flow => return flow,
+            }
+        }
+        Flow::Next
+    }
+
+    fn statement(&mut self, frame: &mut Frame<'m>, statement: &'m Statement) -> Flow {
+        match *statement {
+            Statement::Emit(ref range) => {
+                for expr in range.clone() {
+                    let is_pointer = matches!(
+                        self.ty(frame, expr),
+                        TypeInner::Pointer { .. } | TypeInner::ValuePointer { .. }
+                    );
+                    if !is_pointer {
+                        frame.values[expr.index()] = Some(self.compute(frame, expr, None));
+                    }
+                }
+                Flow::Next
+            }
+            Statement::Block(ref block) => self.block(frame, block),
+            Statement::If {
+                condition,
+                ref accept,
+                ref reject,
+            } => match self.eval(frame, condition, None).bool() {
+                true => self.block(frame, accept),
+                false => self.block(frame, reject),
+            },
+            Statement::Switch {
+                selector,
+                ref cases,
+            } => {
+                let selector = self.eval(frame, selector, None);
+                let matches = |value: &SwitchValue| match *value {
+                    SwitchValue::I32(case) => selector == Value::I32(case),
+                    SwitchValue::U32(case) => selector == Value::U32(case),
+                    SwitchValue::Default => false,
+                };
+                let start = cases
+                    .iter()
+                    .position(|case| matches(&case.value))
+                    .or_else(|| {
+                        cases
+                            .iter()
+                            .position(|case| case.value == SwitchValue::Default)
+                    });
+                for case in start.map_or(&cases[..0], |start| &cases[start..]) {
+                    match self.block(frame, &case.body) {
+                        Flow::Next => {}
+                        Flow::Break => break,
+                        flow => return flow,
+                    }
+                    if !case.fall_through {
+                        break;
+                    }
+                }
+                Flow::Next
+            }
+            Statement::Loop {
+                ref body,
+                ref continuing,
+                break_if,
+            } => loop {
+                match self.block(frame, body) {
+                    Flow::Break => return Flow::Next,
+                    Flow::Return(value) => return Flow::Return(value),
+                    Flow::Next | Flow::Continue => {}
+                }
+                if let Flow::Return(value) = self.block(frame, continuing) {
+                    return Flow::Return(value);
+                }
+                if let Some(condition) = break_if
+                    && self.eval(frame, condition, None).bool()
+                {
+                    return Flow::Next;
+                }
+            },
+            Statement::Break => Flow::Break,
+            Statement::Continue => Flow::Continue,
+            Statement::Return { value } => {
+                Flow::Return(value.map(|value| self.eval(frame, value, None)))
+            }
+            Statement::Store { pointer, value } => {
+                let pointer = self.pointer(frame, pointer, None);
+                let value = self.eval(frame, value, None);
+                let root = match pointer.root {
+                    Root::Local(local) => &mut frame.locals[local],
+                    Root::Global(global) => &mut self.globals[global],
+                };
+                *pointer
+                    .path
+                    .iter()
+                    .fold(root, |value, &index| value.element_mut(index)) = value;
+                Flow::Next
+            }
+            Statement::Call {
+                function,
+                ref arguments,
+                result,
+            } => {
+                let arguments = arguments
+                    .iter()
+                    .map(|&argument| self.eval(frame, argument, None))
+                    .collect();
+                let value = self.run(function, arguments);
+                if let Some(result) = result {
+                    frame.values[result.index()] = value;
+                }
+                Flow::Next
+            }
+            ref other => panic!("unsupported statement {other:?}"),
+        }
+    }
+
+    fn pointer(
+        &mut self,
+        frame: &mut Frame<'m>,
+        expr: Handle<Expression>,
+        neighbor: Option<usize>,
+    ) -> Pointer {
+        match frame.function.expressions[expr] {
+            Expression::LocalVariable(local) => Pointer {
+                root: Root::Local(local.index()),
+                path: Vec::new(),
+            },
+            Expression::GlobalVariable(global) => Pointer {
+                root: Root::Global(global.index()),
+                path: Vec::new(),
+            },
+            Expression::Access { base, index } => {
+                let index = self.eval(frame, index, neighbor).index();
+                let mut pointer = self.pointer(frame, base, neighbor);
+                pointer.path.push(index);
+                pointer
+            }
+            Expression::AccessIndex { base, index } => {
+                let mut pointer = self.pointer(frame, base, neighbor);
+                pointer.path.push(index as usize);
+                pointer
+            }
+            ref other => panic!("{other:?} is not a pointer"),
+        }
+    }
+
+    /// The value of an expression, for `neighbor` it is computed again with the globals of that
+    /// neighbour. Locals and call results are shared with the invocation itself.
+    fn eval(
+        &mut self,
+        frame: &mut Frame<'m>,
+        expr: Handle<Expression>,
+        neighbor: Option<usize>,
+    ) -> Value {
+        if neighbor.is_none()
+            && let Some(value) = &frame.values[expr.index()]
+        {
+            return value.clone();
+        }
+        self.compute(frame, expr, neighbor)
+    }
+
+    fn compute(
+        &mut self,
+        frame: &mut Frame<'m>,
+        expr: Handle<Expression>,
+        neighbor: Option<usize>,
+    ) -> Value {
+        let module = self.module;
+        match frame.function.expressions[expr] {
+            Expression::Literal(value) => literal(value),
+            Expression::Constant(handle) => constant(module, module.constants[handle].init),
+            Expression::ZeroValue(ty) => zero(module, ty),
+            Expression::Compose { ty, ref components } => {
+                let components = components
+                    .iter()
+                    .map(|&component| self.eval(frame, component, neighbor))
+                    .collect();
+                compose(module, ty, components)
+            }
+            Expression::Access { base, index } => {
+                let index = self.eval(frame, index, neighbor).index();
+                self.eval(frame, base, neighbor).element(index).clone()
+            }
+            Expression::AccessIndex { base, index } => self
+                .eval(frame, base, neighbor)
+                .element(index as usize)
+                .clone(),
+            Expression::Splat { size, value } => {
+                Value::Composite(vec![self.eval(frame, value, neighbor); size as usize])
+            }
+            Expression::Swizzle {
+                size,
+                vector,
+                pattern,
+            } => {
+                let vector = self.eval(frame, vector, neighbor);
+                Value::Composite(
+                    pattern[..size as usize]
+                        .iter()
+                        .map(|&component| vector.element(component as usize).clone())
+                        .collect(),
+                )
+            }
+            Expression::FunctionArgument(index) => frame.arguments[index as usize].clone(),
+            Expression::Load { pointer } => {
+                let pointer = self.pointer(frame, pointer, neighbor);
+                let root = match (pointer.root, neighbor) {
+                    (Root::Local(local), _) => &frame.locals[local],
+                    (Root::Global(global), None) => &self.globals[global],
+                    (Root::Global(global), Some(neighbor)) => &self.neighbors[neighbor][global],
+                };
+                pointer
+                    .path
+                    .iter()
+                    .fold(root, |value, &index| value.element(index))
+                    .clone()
+            }
+            Expression::Unary { op, expr } => {
+                let value = self.eval(frame, expr, neighbor);
+                value.map(&|value| match (op, value) {
+                    (UnaryOperator::Negate, Value::F32(value)) => Value::F32(-value),
+                    (UnaryOperator::Negate, Value::I32(value)) => Value::I32(value.wrapping_neg()),
+                    (UnaryOperator::LogicalNot, Value::Bool(value)) => Value::Bool(!value),
+                    (UnaryOperator::BitwiseNot, Value::U32(value)) => Value::U32(!value),
+                    (UnaryOperator::BitwiseNot, Value::I32(value)) => Value::I32(!value),
+                    (op, value) => panic!("unsupported {op:?} of {value:?}"),
+                })
+            }
+            Expression::Binary { op, left, right } => {
+                let (left_ty, right_ty) = (self.ty(frame, left), self.ty(frame, right));
+                let left = self.eval(frame, left, neighbor);
+                let right = self.eval(frame, right, neighbor);
+                binary(op, left, right, left_ty, right_ty)
+            }
+            Expression::Select {
+                condition,
+                accept,
+                reject,
+            } => {
+                let condition = self.eval(frame, condition, neighbor);
+                let accept = self.eval(frame, accept, neighbor);
+                let reject = self.eval(frame, reject, neighbor);
+                select(condition, accept, reject)
+            }
+            Expression::Derivative { axis, expr, .. } => {
+                let value = self.eval(frame, expr, None);
+                let mut difference = |frame: &mut Frame<'m>, neighbor| {
+                    let other = self.eval(frame, expr, Some(neighbor));
+                    binary(
+                        BinaryOperator::Subtract,
+                        other,
+                        value.clone(),
+                        &TypeInner::Scalar(naga::Scalar::F32),
+                        &TypeInner::Scalar(naga::Scalar::F32),
+                    )
+                };
+                match axis {
+                    DerivativeAxis::X => difference(frame, 0),
+                    DerivativeAxis::Y => difference(frame, 1),
+                    DerivativeAxis::Width => {
+                        let x = difference(frame, 0);
+                        let y = difference(frame, 1);
+                        zip(x, y, &|x, y| Value::F32(x.f32().abs() + y.f32().abs()))
+                    }
+                }
+            }
+            Expression::Relational { fun, argument } => {
+                let argument = self.eval(frame, argument, neighbor);
+                match fun {
+                    RelationalFunction::All => {
+                        Value::Bool(argument.components().iter().all(Value::bool))
+                    }
+                    RelationalFunction::Any => {
+                        Value::Bool(argument.components().iter().any(Value::bool))
+                    }
+                    RelationalFunction::IsNan => {
+                        argument.map(&|value| Value::Bool(value.f32().is_nan()))
+                    }
+                    RelationalFunction::IsInf => {
+                        argument.map(&|value| Value::Bool(value.f32().is_infinite()))
+                    }
+                }
+            }
+            Expression::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                arg3,
+            } => {
+                let arguments = [Some(arg), arg1, arg2, arg3]
+                    .into_iter()
+                    .flatten()
+                    .map(|argument| self.eval(frame, argument, neighbor))
+                    .collect();
+                math(fun, arguments)
+            }
+            Expression::As {
+                expr,
+                kind,
+                convert,
+            } => {
+                let value = self.eval(frame, expr, neighbor);
+                value.map(&|value| cast(value, kind, convert.is_some()))
+            }
+            Expression::CallResult(_) => frame.values[expr.index()]
+                .clone()
+                .expect("call result used before the call"),
+            ref other => panic!("unsupported expression {other:?}"),
+        }
+    }
+}
+
+fn binary(
+    op: BinaryOperator,
+    left: Value,
+    right: Value,
+    left_ty: &TypeInner,
+    right_ty: &TypeInner,
+) -> Value {
+    let matrix_times_vector = |matrix: &Value, vector: &Value| {
+        matrix
+            .components()
+            .iter()
+            .zip(vector.components())
+            .map(|(column, scale)| zip(column.clone(), scale.clone(), &|a, b| mul(a, b)))
+            .reduce(|a, b| zip(a, b, &|a, b| Value::F32(a.f32() + b.f32())))
+            .unwrap()
+    };
+    if op == BinaryOperator::Multiply {
+        match (left_ty, right_ty) {
+            (TypeInner::Matrix { .. }, TypeInner::Vector { .. }) => {
+                return matrix_times_vector(&left, &right);
+            }
+            (TypeInner::Vector { .. }, TypeInner::Matrix { .. }) => {
+                return Value::Composite(
+                    right
+                        .components()
+                        .iter()
+                        .map(|column| math(MathFunction::Dot, vec![left.clone(), column.clone()]))
+                        .collect(),
+                );
+            }
+            (TypeInner::Matrix { .. }, TypeInner::Matrix { .. }) => {
+                return Value::Composite(
+                    right
+                        .components()
+                        .iter()
+                        .map(|column| matrix_times_vector(&left, column))
+                        .collect(),
+                );
+            }
+            _ => {}
+        }
+    }
+    zip(left, right, &|a, b| scalar_binary(op, a, b))
+}
+
+fn mul(a: Value, b: Value) -> Value {
+    scalar_binary(BinaryOperator::Multiply, a, b)
+}
+
+fn scalar_binary(op: BinaryOperator, a: Value, b: Value) -> Value {
+    use BinaryOperator as Op;
+    use Value::*;
+    match (op, a, b) {
+        (Op::Add, F32(a), F32(b)) => F32(a + b),
+        (Op::Subtract, F32(a), F32(b)) => F32(a - b),
+        (Op::Multiply, F32(a), F32(b)) => F32(a * b),
+        (Op::Divide, F32(a), F32(b)) => F32(a / b),
+        (Op::Modulo, F32(a), F32(b)) => F32(a % b),
+        (Op::Equal, F32(a), F32(b)) => Bool(a == b),
+        (Op::NotEqual, F32(a), F32(b)) => Bool(a != b),
+        (Op::Less, F32(a), F32(b)) => Bool(a < b),
+        (Op::LessEqual, F32(a), F32(b)) => Bool(a <= b),
+        (Op::Greater, F32(a), F32(b)) => Bool(a > b),
+        (Op::GreaterEqual, F32(a), F32(b)) => Bool(a >= b),
+
+        (Op::Add, U32(a), U32(b)) => U32(a.wrapping_add(b)),
+        (Op::Subtract, U32(a), U32(b)) => U32(a.wrapping_sub(b)),
+        (Op::Multiply, U32(a), U32(b)) => U32(a.wrapping_mul(b)),
+        (Op::Divide, U32(a), U32(b)) => U32(a.checked_div(b).unwrap_or(a)),
+        (Op::Modulo, U32(a), U32(b)) => U32(a.checked_rem(b).unwrap_or(0)),
+        (Op::And, U32(a), U32(b)) => U32(a & b),
+        (Op::InclusiveOr, U32(a), U32(b)) => U32(a | b),
+        (Op::ExclusiveOr, U32(a), U32(b)) => U32(a ^ b),
+        (Op::ShiftLeft, U32(a), U32(b)) => U32(a << (b & 31)),
+        (Op::ShiftRight, U32(a), U32(b)) => U32(a >> (b & 31)),
+        (Op::Equal, U32(a), U32(b)) => Bool(a == b),
+        (Op::NotEqual, U32(a), U32(b)) => Bool(a != b),
+        (Op::Less, U32(a), U32(b)) => Bool(a < b),
+        (Op::LessEqual, U32(a), U32(b)) => Bool(a <= b),
+        (Op::Greater, U32(a), U32(b)) => Bool(a > b),
+        (Op::GreaterEqual, U32(a), U32(b)) => Bool(a >= b),
+
+        (Op::Add, I32(a), I32(b)) => I32(a.wrapping_add(b)),
+        (Op::Subtract, I32(a), I32(b)) => I32(a.wrapping_sub(b)),
+        (Op::Multiply, I32(a), I32(b)) => I32(a.wrapping_mul(b)),
+        (Op::Divide, I32(a), I32(b)) => I32(a.checked_div(b).unwrap_or(a)),
+        (Op::Modulo, I32(a), I32(b)) => I32(a.checked_rem(b).unwrap_or(0)),
+        (Op::And, I32(a), I32(b)) => I32(a & b),
+        (Op::InclusiveOr, I32(a), I32(b)) => I32(a | b),
+        (Op::ExclusiveOr, I32(a), I32(b)) => I32(a ^ b),
+        (Op::ShiftLeft, I32(a), U32(b)) => I32(a << (b & 31)),
+        (Op::ShiftRight, I32(a), U32(b)) => I32(a >> (b & 31)),
+        (Op::Equal, I32(a), I32(b)) => Bool(a == b),
+        (Op::NotEqual, I32(a), I32(b)) => Bool(a != b),
+        (Op::Less, I32(a), I32(b)) => Bool(a < b),
+        (Op::LessEqual, I32(a), I32(b)) => Bool(a <= b),
+        (Op::Greater, I32(a), I32(b)) => Bool(a > b),
+        (Op::GreaterEqual, I32(a), I32(b)) => Bool(a >= b),
+
+        (Op::Equal, Bool(a), Bool(b)) => Bool(a == b),
+        (Op::NotEqual, Bool(a), Bool(b)) => Bool(a != b),
+        (Op::And | Op::LogicalAnd, Bool(a), Bool(b)) => Bool(a && b),
+        (Op::InclusiveOr | Op::LogicalOr, Bool(a), Bool(b)) => Bool(a || b),
+        (op, a, b) => panic!("unsupported {op:?} of {a:?} and {b:?}"),
+    }
+}
+
+fn select(condition: Value, accept: Value, reject: Value) -> Value {
+    match (condition, accept, reject) {
+        (Value::Bool(condition), accept, reject) => match condition {
+            true => accept,
+            false => reject,
+        },
+        (Value::Composite(conditions), accept, reject) => Value::Composite(
+            conditions
+                .into_iter()
+                .zip(accept.components())
+                .zip(reject.components())
+                .map(|((condition, accept), reject)| {
+                    select(condition, accept.clone(), reject.clone())
+                })
+                .collect(),
+        ),
+        (condition, ..) => panic!("{condition:?} is not a condition"),
+    }
+}
+
+fn cast(value: Value, kind: ScalarKind, convert: bool) -> Value {
+    use Value::*;
+    match (value, kind, convert) {
+        (value @ F32(_), ScalarKind::Float, _)
+        | (value @ U32(_), ScalarKind::Uint, _)
+        | (value @ I32(_), ScalarKind::Sint, _)
+        | (value @ Bool(_), ScalarKind::Bool, _) => value,
+        (F32(value), ScalarKind::Uint, true) => U32(value as u32),
+        (F32(value), ScalarKind::Sint, true) => I32(value as i32),
+        (U32(value), ScalarKind::Float, true) => F32(value as f32),
+        (I32(value), ScalarKind::Float, true) => F32(value as f32),
+        (F32(value), ScalarKind::Uint, false) => U32(value.to_bits()),
+        (F32(value), ScalarKind::Sint, false) => I32(value.to_bits() as i32),
+        (U32(value), ScalarKind::Float, false) => F32(f32::from_bits(value)),
+        (I32(value), ScalarKind::Float, false) => F32(f32::from_bits(value as u32)),
+        (U32(value), ScalarKind::Sint, _) => I32(value as i32),
+        (I32(value), ScalarKind::Uint, _) => U32(value as u32),
+        (Bool(value), ScalarKind::Float, _) => F32(value as u32 as f32),
+        (Bool(value), ScalarKind::Uint, _) => U32(value as u32),
+        (Bool(value), ScalarKind::Sint, _) => I32(value as i32),
+        (F32(value), ScalarKind::Bool, _) => Bool(value != 0.),
+        (U32(value), ScalarKind::Bool, _) => Bool(value != 0),
+        (I32(value), ScalarKind::Bool, _) => Bool(value != 0),
+        (value, kind, _) => panic!("unsupported cast of {value:?} to {kind:?}"),
+    }
+}
+
+fn math(fun: MathFunction, arguments: Vec<Value>) -> Value {
+    let dot = |a: &Value, b: &Value| {
+        a.components()
+            .iter()
+            .zip(b.components())
+            .map(|(a, b)| a.f32() * b.f32())
+            .sum::<f32>()
+    };
+    let length = |value: &Value| match value {
+        Value::Composite(_) => dot(value, value).sqrt(),
+        scalar => scalar.f32().abs(),
+    };
+    match fun {
+        MathFunction::Dot => Value::F32(dot(&arguments[0], &arguments[1])),
+        MathFunction::Length => Value::F32(length(&arguments[0])),
+        MathFunction::Distance => {
+            let [a, b] = <[Value; 2]>::try_from(arguments).unwrap();
+            let difference = scalar_zip(a, b, |a, b| a - b);
+            Value::F32(length(&difference))
+        }
+        MathFunction::Normalize => {
+            let length = length(&arguments[0]);
+            arguments[0].clone().map(&|v| Value::F32(v.f32() / length))
+        }
+        MathFunction::Cross => {
+            let [a, b] = [&arguments[0], &arguments[1]].map(|v| {
+                let c = v.components();
+                [c[0].f32(), c[1].f32(), c[2].f32()]
+            });
+            Value::Composite(vec![
+                Value::F32(a[1] * b[2] - a[2] * b[1]),
+                Value::F32(a[2] * b[0] - a[0] * b[2]),
+                Value::F32(a[0] * b[1] - a[1] * b[0]),
+            ])
+        }
+        _ => componentwise(fun, arguments),
+    }
+}
+
+fn scalar_zip(a: Value, b: Value, f: impl Fn(f32, f32) -> f32) -> Value {
+    zip(a, b, &|a, b| Value::F32(f(a.f32(), b.f32())))
+}
+
+/// Applies `fun` to each component, scalar arguments are used for every component.
+fn componentwise(fun: MathFunction, arguments: Vec<Value>) -> Value {
+    let len = arguments.iter().find_map(|argument| match argument {
+        Value::Composite(components) => Some(components.len()),
+        _ => None,
+    });
+    let Some(len) = len else {
+        return scalar_math(fun, &arguments);
+    };
+    Value::Composite(
+        (0..len)
+            .map(|i| {
+                let arguments = arguments
+                    .iter()
+                    .map(|argument| match argument {
+                        Value::Composite(components) => components[i].clone(),
+                        scalar => scalar.clone(),
+                    })
+                    .collect();
+                componentwise(fun, arguments)
+            })
+            .collect(),
+    )
+}
+
+fn scalar_math(fun: MathFunction, arguments: &[Value]) -> Value {
+    use MathFunction as M;
+    use Value::*;
+    match (fun, arguments) {
+        (M::Abs, &[I32(x)]) => I32(x.wrapping_abs()),
+        (M::Abs, &[U32(x)]) => U32(x),
+        (M::Min, &[U32(a), U32(b)]) => U32(a.min(b)),
+        (M::Max, &[U32(a), U32(b)]) => U32(a.max(b)),
+        (M::Clamp, &[U32(x), U32(low), U32(high)]) => U32(x.max(low).min(high)),
+        (M::Min, &[I32(a), I32(b)]) => I32(a.min(b)),
+        (M::Max, &[I32(a), I32(b)]) => I32(a.max(b)),
+        (M::Clamp, &[I32(x), I32(low), I32(high)]) => I32(x.max(low).min(high)),
+        (M::Sign, &[I32(x)]) => I32(x.signum()),
+        (fun, arguments) => {
+            let x: Vec<f32> = arguments.iter().map(Value::f32).collect();
+            F32(match (fun, &x[..]) {
+                (M::Abs, &[x]) => x.abs(),
+                (M::Min, &[a, b]) => a.min(b),
+                (M::Max, &[a, b]) => a.max(b),
+                (M::Clamp, &[x, low, high]) => x.max(low).min(high),
+                (M::Saturate, &[x]) => x.clamp(0., 1.),
+                (M::Cos, &[x]) => x.cos(),
+                (M::Cosh, &[x]) => x.cosh(),
+                (M::Sin, &[x]) => x.sin(),
+                (M::Sinh, &[x]) => x.sinh(),
+                (M::Tan, &[x]) => x.tan(),
+                (M::Tanh, &[x]) => x.tanh(),
+                (M::Acos, &[x]) => x.acos(),
+                (M::Asin, &[x]) => x.asin(),
+                (M::Atan, &[x]) => x.atan(),
+                (M::Atan2, &[y, x]) => y.atan2(x),
+                (M::Radians, &[x]) => x.to_radians(),
+                (M::Degrees, &[x]) => x.to_degrees(),
+                (M::Ceil, &[x]) => x.ceil(),
+                (M::Floor, &[x]) => x.floor(),
+                (M::Round, &[x]) => x.round_ties_even(),
+                (M::Fract, &[x]) => x - x.floor(),
+                (M::Trunc, &[x]) => x.trunc(),
+                (M::Exp, &[x]) => x.exp(),
+                (M::Exp2, &[x]) => x.exp2(),
+                (M::Log, &[x]) => x.ln(),
+                (M::Log2, &[x]) => x.log2(),
+                (M::Pow, &[x, y]) => x.powf(y),
+                (M::Sqrt, &[x]) => x.sqrt(),
+                (M::InverseSqrt, &[x]) => 1. / x.sqrt(),
+                (M::Sign, &[x]) => match x {
+                    x if x > 0. => 1.,
+                    x if x < 0. => -1.,
+                    _ => 0.,
+                },
+                (M::Fma, &[a, b, c]) => a.mul_add(b, c),
+                (M::Mix, &[a, b, t]) => a * (1. - t) + b * t,
+                (M::Step, &[edge, x]) => (x >= edge) as u32 as f32,
+                (M::SmoothStep, &[low, high, x]) => {
+                    let t = ((x - low) / (high - low)).clamp(0., 1.);
+                    t * t * (3. - 2. * t)
+                }
+                (fun, _) => panic!("unsupported {fun:?} of {arguments:?}"),
+            })
+        }
+    }
+}
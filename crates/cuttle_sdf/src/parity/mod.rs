@@ -0,0 +1,568 @@
+//! Runs the shader functions of this crate through an interpreter and compares them
+//! to their [`CuttleCpu`] mirrors, on the same [`SdfSample`] at a set of points.
+//!
+//! Derivatives are taken from the samples a [`PIXEL`] to the right and below, the CPU mirrors
+//! get the same differences as their gradient. `atlas.wgsl` is left out, the CPU has no textures.
+
+mod interpreter;
+
+use crate::cpu::SdfSample;
+use crate::gradient::{GradientData, MAX_GRADIENT_STOPS};
+use crate::morph::{MAX_MORPH_TARGETS, MorphWeightsData};
+use crate::paths::{BEZIER_STEPS, BezierPathData, PolygonData, PolylineData};
+use crate::*;
+use bevy_color::Alpha;
+use bevy_color::palettes::css;
+use bevy_math::{Mat4, Vec3};
+use bevy_reflect::{PartialReflect, ReflectRef};
+use bevy_transform::components::Transform;
+use cuttle_core::components::points::PointsRange;
+use cuttle_core::prelude::CuttleCpu;
+use cuttle_core::shader::FunctionName;
+use cuttle_core::shader::wgsl_struct::{self, WgslTypes};
+use interpreter::{Interpreter, Value};
+use std::collections::BTreeSet;
+
+/// World units between the invocation and its neighbours.
+const PIXEL: f32 = 1e-2;
+
+const PROBES: [Vec2; 8] = [
+    Vec2::new(0.3, 0.2),
+    Vec2::new(1.7, -0.4),
+    Vec2::new(-2.6, 1.1),
+    Vec2::new(0.05, -3.2),
+    Vec2::new(3.3, 2.9),
+    Vec2::new(-1.2, -0.9),
+    Vec2::new(0.9, 2.4),
+    Vec2::new(-0.6, 0.45),
+];
+
+macro_rules! struct_snippets {
+    ($types:expr; $($T:ty),* $(,)?) => {
+        [$($types.get_type::<$T>().snippet.unwrap()),*].concat()
+    };
+}
+
+fn source() -> String {
+    let mut app = App::new();
+    app.add_plugins(wgsl_struct::plugin)
+        .register_wgsl_type::<[Vec4; MAX_GRADIENT_STOPS]>("array<vec4<f32>, 8>")
+        .register_wgsl_type::<[Vec4; MAX_GRADIENT_STOPS / 4]>("array<vec4<f32>, 2>");
+    let points_range = app
+        .world()
+        .resource::<WgslTypes>()
+        .get_type::<PointsRange>()
+        .snippet
+        .unwrap();
+    app.register_wgsl_type::<PointsRange>("PointsRange");
+    let types = app.world().resource::<WgslTypes>();
+    let structs = struct_snippets!(types;
+        SdfTransform, GradientData, StrokeData, ShadowData, GlowData, DistanceGradient, Flame,
+        Repetition, Triangle, Ngon, Star, Arc, Pie, Capsule, RoundedBox, Trapezoid, Vesica,
+        Horseshoe, PolygonData, PolylineData, BezierPathData, PolarRepetition, ClippedRepetition,
+        Onion, NoiseDisplacement, MorphWeightsData,
+    );
+
+    let common = include_str!("../../../cuttle_core/src/shader/common.wgsl")
+        .replace("#define_import_path cuttle::common", "");
+    let prelude = format!(
+        "
+        var<private> vertex: VertexOut;
+        var<private> color: vec4<f32>;
+        @group(3) @binding(0) var<storage, read> elapsed_time: f32;
+        @group(2) @binding(0) var<storage, read> cuttle_points: array<vec2<f32>>;
+        const MAX_OPERANDS: u32 = {MAX_OPERANDS}u;
+        const BEZIER_STEPS: u32 = {BEZIER_STEPS}u;
+        const MAX_MORPH_TARGETS: u32 = {MAX_MORPH_TARGETS}u;
+        var<private> world_position: vec2<f32>;
+        var<private> position: vec2<f32>;
+        var<private> distance: f32;
+        var<private> size: f32;
+        var<private> distance_scale: f32;
+        var<private> prev_distance: f32;
+        var<private> prev_color: vec4<f32>;
+        var<private> operand_distances: array<f32, MAX_OPERANDS>;
+        var<private> operand_colors: array<vec4<f32>, MAX_OPERANDS>;
+        var<private> operand_depth: u32;
+        var<private> morph_target_weights: array<vec4<f32>, MAX_MORPH_TARGETS / 4>;
+        var<private> morph_distance: f32;
+        var<private> morph_color: vec4<f32>;
+        var<private> morph_total: f32;
+        "
+    );
+
+    [
+        &common,
+        &prelude,
+        &points_range,
+        &structs,
+        include_str!("../sdf.wgsl"),
+        include_str!("../primitives.wgsl"),
+        include_str!("../domain.wgsl"),
+        include_str!("../morph.wgsl"),
+        include_str!("../paths.wgsl"),
+    ]
+    .concat()
+}
+
+/// The shader variables of a sample.
+fn variables(s: &SdfSample) -> Vec<(&'static str, Value)> {
+    let weights: Vec<_> = s
+        .morph_target_weights
+        .chunks(4)
+        .map(Vec4::from_slice)
+        .collect();
+    let vertex = Value::Composite(vec![
+        Vec4::ZERO.into(),
+        s.world_position.into(),
+        0u32.into(),
+        0u32.into(),
+        s.size.into(),
+    ]);
+    vec![
+        ("vertex", vertex),
+        ("world_position", s.world_position.into()),
+        ("position", s.position.into()),
+        ("distance", s.distance.into()),
+        ("size", s.size.into()),
+        ("distance_scale", s.distance_scale.into()),
+        ("prev_distance", s.prev_distance.into()),
+        ("prev_color", s.prev_color.into()),
+        ("operand_distances", s.operand_distances.to_vec().into()),
+        ("operand_colors", s.operand_colors.to_vec().into()),
+        ("operand_depth", s.operand_depth.into()),
+        ("morph_target_weights", weights.into()),
+        ("morph_distance", s.morph_distance.into()),
+        ("morph_color", s.morph_color.into()),
+        ("morph_total", s.morph_total.into()),
+        ("color", s.color.into()),
+        ("elapsed_time", s.elapsed_time.into()),
+    ]
+}
+
+/// Render data as the shader sees it.
+fn render_data(data: &dyn PartialReflect) -> Value {
+    if let Some(&value) = data.try_downcast_ref::<f32>() {
+        return value.into();
+    }
+    if let Some(&value) = data.try_downcast_ref::<u32>() {
+        return value.into();
+    }
+    if let Some(&value) = data.try_downcast_ref::<Vec2>() {
+        return value.into();
+    }
+    if let Some(&value) = data.try_downcast_ref::<Vec3>() {
+        return Value::from(value.to_array().to_vec());
+    }
+    if let Some(&value) = data.try_downcast_ref::<Vec4>() {
+        return value.into();
+    }
+    if let Some(&value) = data.try_downcast_ref::<Mat4>() {
+        return Value::from(value.to_cols_array_2d().map(Vec4::from_array).to_vec());
+    }
+    match data.reflect_ref() {
+        ReflectRef::Struct(data) => Value::Composite(data.iter_fields().map(render_data).collect()),
+        ReflectRef::Array(data) => Value::Composite(data.iter().map(render_data).collect()),
+        _ => panic!("unsupported render data {data:?}"),
+    }
+}
+
+fn close(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::F32(a), Value::F32(b)) => {
+            (a.is_nan() && b.is_nan()) || (a - b).abs() <= 1e-3 * a.abs().max(b.abs()).max(1.)
+        }
+        (Value::Composite(a), Value::Composite(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| close(a, b))
+        }
+        (a, b) => a == b,
+    }
+}
+
+/// A sample in the middle of a shape, with every variable set, the shape being a circle
+/// of radius 1.5 in a local space that is scaled and moved.
+fn sample(world_position: Vec2) -> SdfSample {
+    let position = world_position * 0.8 + Vec2::new(0.3, -0.2);
+    let mut morph_target_weights = [0.; MAX_MORPH_TARGETS];
+    morph_target_weights[..3].copy_from_slice(&[0.5, 0.25, 0.25]);
+    SdfSample {
+        world_position,
+        position,
+        distance: position.length() - 1.5,
+        size: 4.,
+        distance_scale: 1.25,
+        prev_distance: (world_position - Vec2::new(0.8, 0.)).abs().max_element() - 1.,
+        prev_color: Vec4::new(0.1, 0.6, 0.9, 0.8),
+        operand_distances: [0.4, -0.3, 0., 0.],
+        operand_colors: [Vec4::X, Vec4::Y, Vec4::ZERO, Vec4::ZERO],
+        operand_depth: 2,
+        morph_target_weights,
+        morph_distance: 0.2,
+        morph_color: Vec4::splat(0.1),
+        morph_total: 0.5,
+        color: Vec4::new(0.9, 0.2, 0.1, 0.7),
+        elapsed_time: 0.7,
+        ..Default::default()
+    }
+}
+
+struct Parity {
+    interpreter: Interpreter,
+    checked: BTreeSet<String>,
+}
+
+impl Parity {
+    fn new() -> Self {
+        Self {
+            interpreter: Interpreter::new(&source()),
+            checked: BTreeSet::new(),
+        }
+    }
+
+    fn check<C: CuttleCpu<SdfSample>>(
+        &mut self,
+        component: C,
+        data: Option<&dyn PartialReflect>,
+    ) -> &mut Self {
+        self.check_with(component, data, sample)
+    }
+
+    /// Compares the function of `C` to its mirror, starting from `sample` at each probe.
+    fn check_with<C: CuttleCpu<SdfSample>>(
+        &mut self,
+        component: C,
+        data: Option<&dyn PartialReflect>,
+        sample: impl Fn(Vec2) -> SdfSample,
+    ) -> &mut Self {
+        let type_name = std::any::type_name::<C>().rsplit("::").next().unwrap();
+        let FunctionName(function) = FunctionName::from_type_name(type_name);
+        for probe in PROBES {
+            let start = sample(probe);
+            let neighbors = [Vec2::X, Vec2::Y].map(|axis| sample(probe + axis * PIXEL));
+
+            let mut cpu = start;
+            cpu.gradient = Vec2::new(neighbors[0].distance, neighbors[1].distance)
+                .map(|distance| (distance - start.distance) / PIXEL);
+            component.eval(&mut cpu);
+
+            for (name, value) in variables(&start) {
+                self.interpreter.set(name, value);
+            }
+            for (axis, neighbor) in neighbors.iter().enumerate() {
+                for (name, value) in variables(neighbor) {
+                    self.interpreter.set_neighbor(axis, name, value);
+                }
+            }
+            self.interpreter
+                .call(&function, data.map(render_data).into_iter().collect());
+
+            for (name, expected) in variables(&cpu) {
+                let actual = self.interpreter.get(name);
+                assert!(
+                    close(actual, &expected),
+                    "{function} at {probe}: {name} is {actual:?} in the shader, {expected:?} on the CPU"
+                );
+            }
+        }
+        self.checked.insert(function);
+        self
+    }
+
+    fn points(&mut self, points: &[Vec2]) -> PointsRange {
+        // Points of other instances come first
+        let offset = 2;
+        let buffer: Vec<_> = [Vec2::splat(9.); 2]
+            .into_iter()
+            .chain(points.iter().copied())
+            .collect();
+        self.interpreter.set("cuttle_points", buffer.into());
+        PointsRange {
+            offset,
+            len: points.len() as u32,
+        }
+    }
+}
+
+#[test]
+fn shader_functions_match_their_cpu_mirrors() {
+    let mut parity = Parity::new();
+
+    let transform = GlobalTransform::from(
+        Transform::from_xyz(0.5, -1., 0.)
+            .with_rotation(Quat::from_rotation_z(0.7))
+            .with_scale(Vec3::new(2., 1.5, 1.)),
+    );
+    parity
+        .check(Sdf, None)
+        .check(PrepareBase, None)
+        .check(transform, Some(&SdfTransform::from(&transform)))
+        .check(ScaleDistance, None)
+        .check(Circle(1.2), Some(&1.2f32))
+        .check(Line(1.5), Some(&1.5f32))
+        .check(Quad(Vec2::new(1., 0.5)), Some(&Vec2::new(1., 0.5)))
+        .check(Rounded(0.3), Some(&0.3f32))
+        .check(Annular(0.2), Some(&0.2f32))
+        .check(ForceFieldAlpha, None)
+        .check(Stretch(Vec2::new(1.5, 0.5)), Some(&Vec2::new(1.5, 0.5)));
+
+    let fill = Fill(css::ORANGE.with_alpha(0.6));
+    parity.check(fill.clone(), Some(&Vec4::from(&fill)));
+
+    let distance_gradient = DistanceGradient {
+        interval: 4.,
+        color: Vec4::new(0.2, 0.3, 1., 1.),
+    };
+    parity.check(distance_gradient.clone(), Some(&distance_gradient));
+
+    let flame = Flame {
+        sharpness: 2.,
+        tip: 0.2,
+        base: 1.,
+        flicker: 0.3,
+    };
+    parity.check(flame, Some(&flame));
+
+    let repetition = Repetition {
+        scale: 1.,
+        repetitions: Vec2::new(3., 2.),
+    };
+    parity.check(repetition, Some(&repetition));
+
+    for (shape, space, interpolation) in [
+        (
+            GradientShape::Linear {
+                start: Vec2::new(-1., 0.),
+                end: Vec2::new(2., 1.),
+            },
+            GradientSpace::Local,
+            GradientInterpolation::Srgb,
+        ),
+        (
+            GradientShape::Radial {
+                center: Vec2::new(0.5, 0.),
+                radius: 3.,
+            },
+            GradientSpace::World,
+            GradientInterpolation::LinearRgb,
+        ),
+        (
+            GradientShape::Conic {
+                center: Vec2::ZERO,
+                angle: 0.4,
+            },
+            GradientSpace::Local,
+            GradientInterpolation::Oklab,
+        ),
+    ] {
+        let gradient = Gradient {
+            shape,
+            stops: vec![
+                GradientStop::new(0., css::RED),
+                GradientStop::new(0.4, css::LIME),
+                GradientStop::new(1., css::BLUE.with_alpha(0.5)),
+            ],
+            space,
+            interpolation,
+        };
+        parity.check(gradient.clone(), Some(&GradientData::from(&gradient)));
+    }
+
+    for dash in [
+        None,
+        Some(StrokeDash {
+            length: 0.5,
+            gap: 0.3,
+            offset: 0.1,
+        }),
+    ] {
+        let stroke = Stroke {
+            color: css::WHITE,
+            width: 0.4,
+            alignment: StrokeAlignment::Outside,
+            dash,
+        };
+        parity.check(stroke.clone(), Some(&StrokeData::from(&stroke)));
+    }
+
+    let drop_shadow = DropShadow {
+        offset: Vec2::new(0.3, -0.4),
+        blur: 0.5,
+        color: css::BLACK.with_alpha(0.5),
+    };
+    let inner_shadow = InnerShadow {
+        offset: Vec2::new(-0.2, 0.3),
+        blur: 0.4,
+        color: css::NAVY.with_alpha(0.7),
+    };
+    let outer_glow = OuterGlow {
+        radius: 1.,
+        color: css::YELLOW,
+    };
+    parity
+        .check(drop_shadow.clone(), Some(&ShadowData::from(&drop_shadow)))
+        .check(inner_shadow.clone(), Some(&ShadowData::from(&inner_shadow)))
+        .check(outer_glow.clone(), Some(&GlowData::from(&outer_glow)));
+
+    parity
+        .check(PrepareOperation, None)
+        .check(PushOperand, None)
+        .check(PopOperand, None)
+        .check(Unioni, None)
+        .check(Subtract, None)
+        .check(Intersect, None)
+        .check(Xor, None)
+        .check(SmoothUnion(0.5), Some(&0.5f32))
+        .check(SmoothSubtract(0.5), Some(&0.5f32))
+        .check(SmoothIntersect(0.5), Some(&0.5f32))
+        .check(SmoothXor(0.5), Some(&0.5f32))
+        .check(Morph(0.3), Some(&0.3f32));
+
+    // A full operand stack, and an empty one
+    for depth in [MAX_OPERANDS as u32, 0] {
+        let with_depth = |p| SdfSample {
+            operand_depth: depth,
+            ..sample(p)
+        };
+        parity
+            .check_with(PushOperand, None, with_depth)
+            .check_with(PopOperand, None, with_depth);
+    }
+
+    let weights = MorphWeights::new([0.2, 0.3, 0., 0.1, 0., 0., 0.4]);
+    parity
+        .check(weights.clone(), Some(&MorphWeightsData::from(&weights)))
+        .check(MorphTarget(1), Some(&1u32))
+        .check(MorphTarget(6), Some(&6u32))
+        .check(MorphTarget(40), Some(&40u32));
+
+    parity
+        .check(Mirror(Vec2::new(1., 0.5)), Some(&Vec2::new(1., 0.5)))
+        .check(Twist(0.4), Some(&0.4f32))
+        .check(Bend(0.3), Some(&0.3f32));
+    let polar = PolarRepetition {
+        count: 5,
+        radius: 1.,
+    };
+    let clipped = ClippedRepetition {
+        spacing: Vec2::new(1.5, 1.),
+        copies: Vec2::new(2., 1.),
+    };
+    let onion = Onion {
+        thickness: 0.1,
+        spacing: 0.4,
+        count: 3,
+    };
+    let noise = NoiseDisplacement {
+        amplitude: 0.3,
+        frequency: 2.,
+        seed: 7,
+    };
+    parity
+        .check(polar, Some(&polar))
+        .check(clipped, Some(&clipped))
+        .check(onion, Some(&onion))
+        .check(noise, Some(&noise));
+
+    parity
+        .check(Ellipse(Vec2::new(1.5, 0.8)), Some(&Vec2::new(1.5, 0.8)))
+        .check(Ellipse(Vec2::splat(1.)), Some(&Vec2::splat(1.)))
+        .check(EquilateralTriangle(1.2), Some(&1.2f32))
+        .check(Rhombus(Vec2::new(1.2, 0.7)), Some(&Vec2::new(1.2, 0.7)))
+        .check(Heart(1.5), Some(&1.5f32))
+        .check(Cross(Vec2::new(1.2, 0.3)), Some(&Vec2::new(1.2, 0.3)));
+    let triangle = Triangle {
+        a: Vec2::new(-1., -0.5),
+        b: Vec2::new(1.2, -0.3),
+        c: Vec2::new(0.1, 1.4),
+    };
+    let ngon = Ngon {
+        radius: 1.2,
+        sides: 6,
+    };
+    let star = Star {
+        radius: 1.4,
+        points: 5,
+        sharpness: 3.,
+    };
+    let arc = Arc {
+        aperture: 1.,
+        radius: 1.2,
+        thickness: 0.2,
+    };
+    let pie = Pie {
+        aperture: 0.8,
+        radius: 1.3,
+    };
+    let capsule = Capsule {
+        a: Vec2::new(-1., 0.2),
+        b: Vec2::new(0.8, -0.5),
+        radius: 0.4,
+    };
+    let rounded_box = RoundedBox {
+        half_size: Vec2::new(1.2, 0.8),
+        radii: Vec4::new(0.1, 0.2, 0.3, 0.4),
+    };
+    let trapezoid = Trapezoid {
+        bottom: 1.2,
+        top: 0.6,
+        half_height: 0.8,
+    };
+    let vesica = Vesica {
+        radius: 1.2,
+        offset: 0.6,
+    };
+    let horseshoe = Horseshoe {
+        aperture: 1.2,
+        radius: 1.,
+        length: 0.4,
+        thickness: 0.2,
+    };
+    parity
+        .check(triangle, Some(&triangle))
+        .check(ngon, Some(&ngon))
+        .check(star, Some(&star))
+        .check(arc, Some(&arc))
+        .check(pie, Some(&pie))
+        .check(capsule, Some(&capsule))
+        .check(rounded_box, Some(&rounded_box))
+        .check(trapezoid, Some(&trapezoid))
+        .check(vesica, Some(&vesica))
+        .check(horseshoe, Some(&horseshoe));
+
+    let points = [
+        Vec2::new(-1., -1.),
+        Vec2::new(1.5, -0.5),
+        Vec2::new(0.5, 0.4),
+        Vec2::new(1., 1.5),
+        Vec2::new(-1.2, 0.8),
+        Vec2::new(-0.4, 1.9),
+        Vec2::new(0.2, -2.),
+    ];
+    let range = parity.points(&points[..5]);
+    let polygon = Polygon(points[..5].to_vec());
+    parity.check(polygon.clone(), Some(&PolygonData::from((&polygon, range))));
+
+    let range = parity.points(&points[..4]);
+    let polyline = Polyline::new(points[..4].iter().copied(), 0.3);
+    parity.check(
+        polyline.clone(),
+        Some(&PolylineData::from((&polyline, range))),
+    );
+
+    for (degree, len, closed) in [
+        (BezierDegree::Cubic, 7, true),
+        (BezierDegree::Cubic, 4, false),
+        (BezierDegree::Quadratic, 5, true),
+    ] {
+        let path = BezierPath {
+            points: points[..len].to_vec(),
+            degree,
+            closed,
+        };
+        let range = parity.points(&path.points);
+        parity.check(path.clone(), Some(&BezierPathData::from((&path, range))));
+    }
+
+    let functions: BTreeSet<_> = parity.interpreter.functions().map(str::to_string).collect();
+    assert_eq!(parity.checked, functions);
+}
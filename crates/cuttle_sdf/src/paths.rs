@@ -0,0 +1,206 @@
+//! Shapes through a variable number of points, stored in the shared points buffer
+//! described in [`cuttle_core::components::points`].
+
+use crate::cpu::SdfSample;
+use crate::{PrepareBase, ScaleDistance, Sdf, SdfOrder};
+use bevy_app::App;
+use bevy_asset::embedded_asset;
+use bevy_ecs::prelude::{Component, ReflectComponent};
+use bevy_math::Vec2;
+use bevy_reflect::Reflect;
+use bevy_render::render_resource::ShaderType;
+use cuttle_core::prelude::{Bounding, CuttleGroupBuilderAppExt, CuttlePoints, PointsRange};
+use cuttle_macros::Cuttle;
+
+/// Line segments each curve of a [`BezierPath`] is split into, shared with the shader.
+pub const BEZIER_STEPS: u32 = 16;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<(Polygon, Polyline, BezierPath, BezierDegree)>();
+
+    embedded_asset!(app, "paths.wgsl");
+
+    app.cuttle_config::<Sdf>()
+        .snippet(format!("const BEZIER_STEPS: u32 = {BEZIER_STEPS}u;"))
+        .snippet_file("embedded://cuttle_sdf/paths.wgsl")
+        .components::<(Polygon, Polyline, BezierPath)>()
+        .affect_bounds(Bounding::Union, |polygon: &Polygon| extents(&polygon.0))
+        .affect_bounds(Bounding::Union, |line: &Polyline| {
+            extents(&line.points) + line.width * 0.5
+        })
+        // Curves stay within the hull of their control points
+        .affect_bounds(Bounding::Union, |path: &BezierPath| extents(&path.points));
+}
+
+fn extents(points: &[Vec2]) -> Vec2 {
+    points
+        .iter()
+        .fold(Vec2::ZERO, |max, point| max.max(point.abs()))
+}
+
+/// A closed polygon through the points, filled by the even-odd rule.
+#[derive(Debug, Default, Clone, Component, Reflect, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[cuttle(render_points(PolygonData))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Polygon(pub Vec<Vec2>);
+
+impl CuttlePoints for Polygon {
+    fn points(&self) -> &[Vec2] {
+        &self.0
+    }
+}
+
+/// Line segments through the points, `width` wide.
+#[derive(Debug, Default, Clone, Component, Reflect, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[cuttle(render_points(PolylineData))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Polyline {
+    pub points: Vec<Vec2>,
+    pub width: f32,
+}
+
+impl Polyline {
+    pub fn new(points: impl IntoIterator<Item = Vec2>, width: f32) -> Self {
+        Self {
+            points: points.into_iter().collect(),
+            width,
+        }
+    }
+}
+
+impl CuttlePoints for Polyline {
+    fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+}
+
+/// Connected bezier curves, each sharing its first point with the end of the previous one.
+///
+/// A closed path returns to its first point in a straight line and is filled by the
+/// even-odd rule, an open path has no area and is usually given a width with
+/// [`Rounded`](crate::Rounded).
+#[derive(Debug, Clone, Component, Reflect, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[cuttle(render_points(BezierPathData))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct BezierPath {
+    pub points: Vec<Vec2>,
+    pub degree: BezierDegree,
+    pub closed: bool,
+}
+
+impl BezierPath {
+    /// Start, then a control point and an end per curve.
+    pub fn quadratic(points: impl IntoIterator<Item = Vec2>) -> Self {
+        Self::new(BezierDegree::Quadratic, points)
+    }
+
+    /// Start, then two control points and an end per curve.
+    pub fn cubic(points: impl IntoIterator<Item = Vec2>) -> Self {
+        Self::new(BezierDegree::Cubic, points)
+    }
+
+    pub fn new(degree: BezierDegree, points: impl IntoIterator<Item = Vec2>) -> Self {
+        Self {
+            points: points.into_iter().collect(),
+            degree,
+            closed: false,
+        }
+    }
+
+    pub fn closed(mut self) -> Self {
+        self.closed = true;
+        self
+    }
+
+    /// The line segments the path is evaluated as, including the closing one.
+    pub(crate) fn pieces(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        let degree = self.degree as usize;
+        let curves = self.points.len().saturating_sub(1) / degree;
+        let mut previous = self.points.first().copied().unwrap_or_default();
+        let curve_points = (0..curves as u32 * BEZIER_STEPS).map(move |q| {
+            let first = (q / BEZIER_STEPS) as usize * degree;
+            let [p0, mut p1, mut p2, p3] = [0, 1, 2, degree].map(|i| self.points[first + i]);
+            if self.degree == BezierDegree::Quadratic {
+                let control = p1;
+                p1 = p0 + (control - p0) * 2. / 3.;
+                p2 = p3 + (control - p3) * 2. / 3.;
+            }
+            let t = (q % BEZIER_STEPS + 1) as f32 / BEZIER_STEPS as f32;
+            let u = 1. - t;
+            u * u * u * p0 + 3. * u * u * t * p1 + 3. * u * t * t * p2 + t * t * t * p3
+        });
+        let closing = self
+            .closed
+            .then(|| self.points.first().copied().unwrap_or_default());
+        curve_points.chain(closing).map(move |point| {
+            let piece = (previous, point);
+            previous = point;
+            piece
+        })
+    }
+}
+
+impl CuttlePoints for BezierPath {
+    fn points(&self) -> &[Vec2] {
+        &self.points
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum BezierDegree {
+    Quadratic = 2,
+    #[default]
+    Cubic = 3,
+}
+
+#[derive(Debug, Default, Clone, Reflect, ShaderType)]
+pub struct PolygonData {
+    pub points: PointsRange,
+}
+
+impl From<(&Polygon, PointsRange)> for PolygonData {
+    fn from((_, points): (&Polygon, PointsRange)) -> Self {
+        Self { points }
+    }
+}
+
+#[derive(Debug, Default, Clone, Reflect, ShaderType)]
+pub struct PolylineData {
+    pub points: PointsRange,
+    pub width: f32,
+}
+
+impl From<(&Polyline, PointsRange)> for PolylineData {
+    fn from((line, points): (&Polyline, PointsRange)) -> Self {
+        Self {
+            points,
+            width: line.width,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Reflect, ShaderType)]
+pub struct BezierPathData {
+    pub points: PointsRange,
+    pub degree: u32,
+    pub closed: u32,
+}
+
+impl From<(&BezierPath, PointsRange)> for BezierPathData {
+    fn from((path, points): (&BezierPath, PointsRange)) -> Self {
+        Self {
+            points,
+            degree: path.degree as u32,
+            closed: path.closed as u32,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+fn polygon(input: PolygonData) {
+    let n = input.points.len;
+    let o = input.points.offset;
+    var d = 1e10;
+    var s = 1.0;
+    var j = n - 1u;
+    for (var i = 0u; i < n; i++) {
+        let a = cuttle_points[o + j];
+        let b = cuttle_points[o + i];
+        let e = b - a;
+        let w = position - a;
+        let h = w - e * clamp(dot(w, e) / max(dot(e, e), 1e-8), 0.0, 1.0);
+        d = min(d, dot(h, h));
+        // flip the sign for every edge crossed by a ray towards +x
+        let c = vec3(position.y >= a.y, position.y < b.y, e.x * w.y > e.y * w.x);
+        if all(c) || all(!c) {
+            s = -s;
+        }
+        j = i;
+    }
+    distance = s * sqrt(d);
+}
+
+fn polyline(input: PolylineData) {
+    let o = input.points.offset;
+    var d = 1e10;
+    for (var i = 0u; i < input.points.len; i++) {
+        let a = cuttle_points[o + max(i, 1u) - 1u];
+        let e = cuttle_points[o + i] - a;
+        let w = position - a;
+        let h = w - e * clamp(dot(w, e) / max(dot(e, e), 1e-8), 0.0, 1.0);
+        d = min(d, dot(h, h));
+    }
+    distance = sqrt(d) - input.width * 0.5;
+}
+
+fn bezier_path(input: BezierPathData) {
+    let o = input.points.offset;
+    let degree = input.degree;
+    let curves = select(0u, (input.points.len - 1u) / degree, input.points.len > 0u);
+    let start = cuttle_points[o];
+    let pieces = curves * BEZIER_STEPS + input.closed;
+    var d = 1e10;
+    var s = 1.0;
+    var a = start;
+    for (var q = 0u; q < pieces; q++) {
+        // the curves are split into line segments, the last piece of a closed path returns to the start
+        var b = start;
+        if q < curves * BEZIER_STEPS {
+            let first = o + (q / BEZIER_STEPS) * degree;
+            let p0 = cuttle_points[first];
+            var p1 = cuttle_points[first + 1u];
+            var p2 = cuttle_points[first + 2u];
+            let p3 = cuttle_points[first + degree];
+            if degree == 2u {
+                let control = p1;
+                p1 = p0 + (control - p0) * 2.0 / 3.0;
+                p2 = p3 + (control - p3) * 2.0 / 3.0;
+            }
+            let t = f32(q % BEZIER_STEPS + 1u) / f32(BEZIER_STEPS);
+            let u = 1.0 - t;
+            b = u * u * u * p0 + 3.0 * u * u * t * p1 + 3.0 * u * t * t * p2 + t * t * t * p3;
+        }
+        let e = b - a;
+        let w = position - a;
+        let h = w - e * clamp(dot(w, e) / max(dot(e, e), 1e-8), 0.0, 1.0);
+        d = min(d, dot(h, h));
+        let c = vec3(position.y >= a.y, position.y < b.y, e.x * w.y > e.y * w.x);
+        if all(c) || all(!c) {
+            s = -s;
+        }
+        a = b;
+    }
+    distance = select(1.0, s, input.closed != 0u) * sqrt(d);
+}
@@ -0,0 +1,282 @@
+//! Base shapes beyond [`Circle`](crate::Circle), [`Line`](crate::Line) and [`Quad`](crate::Quad).
+//!
+//! Most distance functions are adapted from <https://iquilezles.org/articles/distfunctions2d/>.
+//! Shapes are centered on the origin unless they are placed through points.
+
+use crate::cpu::SdfSample;
+use crate::{PrepareBase, ScaleDistance, Sdf, SdfOrder};
+use bevy_app::App;
+use bevy_asset::embedded_asset;
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::prelude::{Component, ReflectComponent};
+use bevy_math::{Vec2, Vec4};
+use bevy_reflect::Reflect;
+use bevy_render::render_resource::ShaderType;
+use cuttle_core::prelude::{Bounding, CuttleGroupBuilderAppExt};
+use cuttle_macros::Cuttle;
+use std::f32::consts::SQRT_2;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<(
+        Ellipse,
+        EquilateralTriangle,
+        Triangle,
+        Ngon,
+        Star,
+        Arc,
+        Pie,
+        Capsule,
+    )>()
+    .register_type::<(
+        RoundedBox,
+        Rhombus,
+        Trapezoid,
+        Heart,
+        Cross,
+        Vesica,
+        Horseshoe,
+    )>();
+
+    embedded_asset!(app, "primitives.wgsl");
+
+    app.cuttle_config::<Sdf>()
+        .snippet_file("embedded://cuttle_sdf/primitives.wgsl")
+        .components::<(
+            Ellipse,
+            EquilateralTriangle,
+            Triangle,
+            Ngon,
+            Star,
+            Arc,
+            Pie,
+            Capsule,
+        )>()
+        .components::<(
+            RoundedBox,
+            Rhombus,
+            Trapezoid,
+            Heart,
+            Cross,
+            Vesica,
+            Horseshoe,
+        )>()
+        .affect_bounds(Bounding::Union, |&Ellipse(radii)| radii)
+        .affect_bounds(Bounding::Union, EquilateralTriangle::half_extents)
+        .affect_bounds(Bounding::Union, |t: &Triangle| {
+            t.a.abs().max(t.b.abs()).max(t.c.abs())
+        })
+        .affect_bounds(Bounding::Union, |p: &Ngon| p.radius)
+        .affect_bounds(Bounding::Union, |s: &Star| s.radius)
+        .affect_bounds(Bounding::Union, |a: &Arc| a.radius + a.thickness)
+        .affect_bounds(Bounding::Union, |p: &Pie| p.radius)
+        .affect_bounds(Bounding::Union, |c: &Capsule| {
+            c.a.abs().max(c.b.abs()) + c.radius
+        })
+        .affect_bounds(Bounding::Union, |b: &RoundedBox| b.half_size)
+        .affect_bounds(Bounding::Union, |&Rhombus(half_size)| half_size)
+        .affect_bounds(Bounding::Union, |t: &Trapezoid| {
+            Vec2::new(t.bottom.max(t.top), t.half_height)
+        })
+        .affect_bounds(Bounding::Union, Heart::half_extents)
+        .affect_bounds(Bounding::Union, Cross::half_extents)
+        .affect_bounds(Bounding::Union, Vesica::half_extents)
+        .affect_bounds(Bounding::Union, Horseshoe::half_extents);
+}
+
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Ellipse(pub Vec2);
+
+/// An equilateral triangle pointing up, with the given half side length.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct EquilateralTriangle(pub f32);
+
+impl EquilateralTriangle {
+    /// The top corner lies twice as far from the center as the bottom edge
+    pub(crate) fn half_extents(&Self(half_side): &Self) -> Vec2 {
+        Vec2::new(half_side, half_side * 2. / 3f32.sqrt())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Triangle {
+    pub a: Vec2,
+    pub b: Vec2,
+    pub c: Vec2,
+}
+
+/// A regular polygon with `sides` corners at `radius` from the center,
+/// with one corner pointing up.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Ngon {
+    pub radius: f32,
+    pub sides: u32,
+}
+
+/// A star with `points` tips at `radius` from the center.
+/// `sharpness` ranges from 2, the sharpest, to `points`, which gives a regular polygon.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Star {
+    pub radius: f32,
+    pub points: u32,
+    pub sharpness: f32,
+}
+
+/// A segment of a ring, open towards the bottom.
+/// `aperture` is half the angle the arc spans, measured from the top.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Arc {
+    pub aperture: f32,
+    pub radius: f32,
+    pub thickness: f32,
+}
+
+/// A slice of a circle around the origin, facing up.
+/// `aperture` is half the angle of the slice.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Pie {
+    pub aperture: f32,
+    pub radius: f32,
+}
+
+/// A line from `a` to `b` with round caps.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Capsule {
+    pub a: Vec2,
+    pub b: Vec2,
+    pub radius: f32,
+}
+
+/// A box with a radius per corner, in the order
+/// top right, bottom right, top left, bottom left.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct RoundedBox {
+    pub half_size: Vec2,
+    pub radii: Vec4,
+}
+
+/// A rhombus with the given half diagonals.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Rhombus(pub Vec2);
+
+/// An isosceles trapezoid with the given half widths of its bottom and top edges.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Trapezoid {
+    pub bottom: f32,
+    pub top: f32,
+    pub half_height: f32,
+}
+
+/// A heart about `size` tall, centered on its bounds.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Heart(pub f32);
+
+impl Heart {
+    /// Half extents of a heart of size 1, the lobes are circles of radius `sqrt(2) / 4`
+    const EXTENTS: Vec2 = Vec2::new(0.25 + SQRT_2 / 4., (0.75 + SQRT_2 / 4.) / 2.);
+
+    pub(crate) fn half_extents(&Self(size): &Self) -> Vec2 {
+        Self::EXTENTS * size
+    }
+}
+
+/// A plus sign with arms reaching `x` from the center and `y` thick on each side.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, Deref, DerefMut, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Cross(pub Vec2);
+
+impl Cross {
+    /// The vertical arm is the horizontal one turned, so both reach the larger of `x` and `y`
+    pub(crate) fn half_extents(&Self(size): &Self) -> Vec2 {
+        Vec2::splat(size.x.max(size.y))
+    }
+}
+
+/// The intersection of two circles of `radius`, `offset` to the left and right of the center.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Vesica {
+    pub radius: f32,
+    pub offset: f32,
+}
+
+impl Vesica {
+    pub(crate) fn half_extents(vesica: &Self) -> Vec2 {
+        let height = (vesica.radius * vesica.radius - vesica.offset * vesica.offset).sqrt();
+        Vec2::new(vesica.radius - vesica.offset, height)
+    }
+}
+
+/// A bent ring open towards the bottom, with straight legs of `length`.
+/// `aperture` is the angle the ring spans on each side, measured from the top.
+#[derive(Debug, Default, Clone, Copy, Component, Reflect, ShaderType, Cuttle)]
+#[cuttle(sort(SdfOrder::Base))]
+#[cuttle(cpu(SdfSample))]
+#[reflect(Component)]
+#[require(PrepareBase, ScaleDistance)]
+pub struct Horseshoe {
+    pub aperture: f32,
+    pub radius: f32,
+    pub length: f32,
+    pub thickness: f32,
+}
+
+impl Horseshoe {
+    /// The legs end at most `length` past the end of the ring
+    pub(crate) fn half_extents(horseshoe: &Self) -> Vec2 {
+        Vec2::splat(horseshoe.radius + horseshoe.thickness + horseshoe.length)
+    }
+}
@@ -0,0 +1,195 @@
+// Distance functions adapted from https://iquilezles.org/articles/distfunctions2d/
+
+fn ellipse(radii: vec2<f32>) {
+    var p = abs(position);
+    var ab = radii;
+    if p.x > p.y {
+        p = p.yx;
+        ab = ab.yx;
+    }
+    let l = ab.y * ab.y - ab.x * ab.x;
+    // the solution below divides by l, which is zero for a circle
+    if abs(l) < 1e-5 {
+        distance = length(p) - ab.x;
+        return;
+    }
+    let m = ab.x * p.x / l;
+    let m2 = m * m;
+    let n = ab.y * p.y / l;
+    let n2 = n * n;
+    let c = (m2 + n2 - 1.0) / 3.0;
+    let c3 = c * c * c;
+    let q = c3 + m2 * n2 * 2.0;
+    let d = c3 + m2 * n2;
+    let g = m + m * n2;
+    var co: f32;
+    if d < 0.0 {
+        let h = acos(q / c3) / 3.0;
+        let s = cos(h);
+        let t = sin(h) * sqrt(3.0);
+        let rx = sqrt(-c * (s + t + 2.0) + m2);
+        let ry = sqrt(-c * (s - t + 2.0) + m2);
+        co = (ry + sign(l) * rx + abs(g) / (rx * ry) - m) / 2.0;
+    } else {
+        let h = 2.0 * m * n * sqrt(d);
+        let s = sign(q + h) * pow(abs(q + h), 1.0 / 3.0);
+        let u = sign(q - h) * pow(abs(q - h), 1.0 / 3.0);
+        let rx = -s - u - c * 4.0 + 2.0 * m2;
+        let ry = (s - u) * sqrt(3.0);
+        let rm = sqrt(rx * rx + ry * ry);
+        co = (ry / sqrt(rm - rx) + 2.0 * g / rm - m) / 2.0;
+    }
+    let r = ab * vec2(co, sqrt(max(1.0 - co * co, 0.0)));
+    distance = length(r - p) * sign(p.y - r.y);
+}
+
+fn equilateral_triangle(half_side: f32) {
+    let k = sqrt(3.0);
+    var p = vec2(abs(position.x) - half_side, position.y + half_side / k);
+    if p.x + k * p.y > 0.0 {
+        p = vec2(p.x - k * p.y, -k * p.x - p.y) / 2.0;
+    }
+    p.x -= clamp(p.x, -2.0 * half_side, 0.0);
+    distance = -length(p) * sign(p.y);
+}
+
+fn triangle(input: Triangle) {
+    let e0 = input.b - input.a;
+    let e1 = input.c - input.b;
+    let e2 = input.a - input.c;
+    let v0 = position - input.a;
+    let v1 = position - input.b;
+    let v2 = position - input.c;
+    let pq0 = v0 - e0 * clamp(dot(v0, e0) / dot(e0, e0), 0.0, 1.0);
+    let pq1 = v1 - e1 * clamp(dot(v1, e1) / dot(e1, e1), 0.0, 1.0);
+    let pq2 = v2 - e2 * clamp(dot(v2, e2) / dot(e2, e2), 0.0, 1.0);
+    let s = sign(e0.x * e2.y - e0.y * e2.x);
+    let d = min(
+        min(
+            vec2(dot(pq0, pq0), s * (v0.x * e0.y - v0.y * e0.x)),
+            vec2(dot(pq1, pq1), s * (v1.x * e1.y - v1.y * e1.x))
+        ),
+        vec2(dot(pq2, pq2), s * (v2.x * e2.y - v2.y * e2.x))
+    );
+    distance = -sqrt(d.x) * sign(d.y);
+}
+
+fn ngon(input: Ngon) {
+    let an = 3.14159265 / f32(max(input.sides, 3u));
+    let acs = vec2(cos(an), sin(an));
+    let angle = atan2(position.x, position.y);
+    let bn = angle - 2.0 * an * floor(angle / (2.0 * an)) - an;
+    var p = length(position) * vec2(cos(bn), abs(sin(bn)));
+    p -= input.radius * acs;
+    p.y += clamp(-p.y, 0.0, input.radius * acs.y);
+    distance = length(p) * sign(p.x);
+}
+
+fn star(input: Star) {
+    let an = 3.14159265 / f32(max(input.points, 2u));
+    let en = 3.14159265 / clamp(input.sharpness, 2.0, f32(max(input.points, 2u)));
+    let acs = vec2(cos(an), sin(an));
+    let ecs = vec2(cos(en), sin(en));
+    let angle = atan2(position.x, position.y);
+    let bn = angle - 2.0 * an * floor(angle / (2.0 * an)) - an;
+    var p = length(position) * vec2(cos(bn), abs(sin(bn)));
+    p -= input.radius * acs;
+    p += ecs * clamp(-dot(p, ecs), 0.0, input.radius * acs.y / ecs.y);
+    distance = length(p) * sign(p.x);
+}
+
+fn arc(input: Arc) {
+    let sc = vec2(sin(input.aperture), cos(input.aperture));
+    let p = vec2(abs(position.x), position.y);
+    if sc.y * p.x > sc.x * p.y {
+        distance = length(p - sc * input.radius) - input.thickness;
+    } else {
+        distance = abs(length(p) - input.radius) - input.thickness;
+    }
+}
+
+fn pie(input: Pie) {
+    let c = vec2(sin(input.aperture), cos(input.aperture));
+    let p = vec2(abs(position.x), position.y);
+    let l = length(p) - input.radius;
+    let m = length(p - c * clamp(dot(p, c), 0.0, input.radius));
+    distance = max(l, m * sign(c.y * p.x - c.x * p.y));
+}
+
+fn capsule(input: Capsule) {
+    let pa = position - input.a;
+    let ba = input.b - input.a;
+    let h = clamp(dot(pa, ba) / max(dot(ba, ba), 1e-8), 0.0, 1.0);
+    distance = length(pa - ba * h) - input.radius;
+}
+
+fn rounded_box(input: RoundedBox) {
+    let right = select(input.radii.zw, input.radii.xy, position.x > 0.0);
+    let r = select(right.y, right.x, position.y > 0.0);
+    let q = abs(position) - input.half_size + r;
+    distance = min(max(q.x, q.y), 0.0) + length(max(q, vec2(0.0))) - r;
+}
+
+fn rhombus(half_size: vec2<f32>) {
+    let p = abs(position);
+    let b = half_size;
+    let n = b - 2.0 * p;
+    let h = clamp((n.x * b.x - n.y * b.y) / dot(b, b), -1.0, 1.0);
+    let d = length(p - 0.5 * b * vec2(1.0 - h, 1.0 + h));
+    distance = d * sign(p.x * b.y + p.y * b.x - b.x * b.y);
+}
+
+fn trapezoid(input: Trapezoid) {
+    let k1 = vec2(input.top, input.half_height);
+    let k2 = vec2(input.top - input.bottom, 2.0 * input.half_height);
+    let p = vec2(abs(position.x), position.y);
+    let width = select(input.top, input.bottom, p.y < 0.0);
+    let ca = vec2(p.x - min(p.x, width), abs(p.y) - input.half_height);
+    let cb = p - k1 + k2 * clamp(dot(k1 - p, k2) / dot(k2, k2), 0.0, 1.0);
+    let s = select(1.0, -1.0, cb.x < 0.0 && ca.y < 0.0);
+    distance = s * sqrt(min(dot(ca, ca), dot(cb, cb)));
+}
+
+fn heart(size: f32) {
+    // the unit heart has its tip at the origin, shift it to be centered on its bounds
+    let half_height = (0.75 + sqrt(2.0) / 4.0) / 2.0;
+    let p = vec2(abs(position.x), position.y) / size + vec2(0.0, half_height);
+    if p.y + p.x > 1.0 {
+        distance = (length(p - vec2(0.25, 0.75)) - sqrt(2.0) / 4.0) * size;
+        return;
+    }
+    let a = p - vec2(0.0, 1.0);
+    let b = p - 0.5 * max(p.x + p.y, 0.0);
+    distance = sqrt(min(dot(a, a), dot(b, b))) * sign(p.x - p.y) * size;
+}
+
+fn cross(size: vec2<f32>) {
+    var p = abs(position);
+    if p.y > p.x {
+        p = p.yx;
+    }
+    let q = p - size;
+    let k = max(q.y, q.x);
+    let w = select(vec2(size.y - p.x, -k), q, k > 0.0);
+    distance = sign(k) * length(max(w, vec2(0.0)));
+}
+
+fn vesica(input: Vesica) {
+    let p = abs(position);
+    let b = sqrt(input.radius * input.radius - input.offset * input.offset);
+    if (p.y - b) * input.offset > p.x * b {
+        distance = length(p - vec2(0.0, b));
+    } else {
+        distance = length(p - vec2(-input.offset, 0.0)) - input.radius;
+    }
+}
+
+fn horseshoe(input: Horseshoe) {
+    let c = vec2(cos(input.aperture), sin(input.aperture));
+    var p = vec2(abs(position.x), position.y);
+    let l = length(p);
+    p = mat2x2(-c.x, c.y, c.y, c.x) * p;
+    p = vec2(select(l * sign(-c.x), p.x, p.y > 0.0 || p.x > 0.0), select(l, p.y, p.x > 0.0));
+    p = vec2(p.x, abs(p.y - input.radius)) - vec2(input.length, input.thickness);
+    distance = length(max(p, vec2(0.0))) + min(0.0, max(p.x, p.y));
+}
@@ -7,6 +7,8 @@ fn sdf() {
 
 fn prepare_base() {
     position = vertex.world_position;
+    world_position = vertex.world_position;
+    distance_scale = 1.0;
 }
 
 fn circle(radius: f32) {
@@ -23,8 +25,13 @@ fn quad(half_size: vec2<f32>) {
     distance = length(max(d, vec2(0.0))) + min(max(d.x, d.y), 0.0);
 }
 
-fn global_transform(transform: mat4x4<f32>) {
-    position = (transform * vec4(position.x, position.y, 0.0, 1.0)).xy;
+fn global_transform(transform: SdfTransform) {
+    position = (transform.local_from_world * vec4(position.x, position.y, 0.0, 1.0)).xy;
+    distance_scale *= transform.scale;
+}
+
+fn scale_distance() {
+    distance *= distance_scale;
 }
 
 fn rounded(rounded: f32) {
@@ -39,19 +46,150 @@ fn fill(fill_color: vec4<f32>) {
     color = fill_color;
 }
 
+fn gradient(input: GradientData) {
+    var point = position;
+    if input.space == 1u {
+        point = world_position;
+    }
+
+    let origin = input.params.xy;
+    var t: f32;
+    switch input.shape {
+        case 0u: {
+            let direction = input.params.zw - origin;
+            t = dot(point - origin, direction) / dot(direction, direction);
+        }
+        case 1u: {
+            t = length(point - origin) / input.params.z;
+        }
+        default: {
+            let d = point - origin;
+            t = fract((atan2(d.y, d.x) - input.params.z) / 6.28318530718);
+        }
+    }
+
+    if input.count == 0u {
+        return;
+    }
+    var mixed = input.colors[0];
+    for (var i = 1u; i < input.count; i++) {
+        let start = input.offsets[(i - 1u) / 4u][(i - 1u) % 4u];
+        let end = input.offsets[i / 4u][i % 4u];
+        if t > start {
+            let local = clamp((t - start) / max(end - start, 1e-5), 0.0, 1.0);
+            mixed = mix(input.colors[i - 1u], input.colors[i], local);
+        }
+    }
+
+    // Oklab to linear sRGB
+    if input.interpolation == 2u {
+        let lms = vec3(
+            mixed.x + 0.3963377774 * mixed.y + 0.2158037573 * mixed.z,
+            mixed.x - 0.1055613458 * mixed.y - 0.0638541728 * mixed.z,
+            mixed.x - 0.0894841775 * mixed.y - 1.2914855480 * mixed.z,
+        );
+        let cubed = lms * lms * lms;
+        mixed = vec4(
+            4.0767416621 * cubed.x - 3.3077115913 * cubed.y + 0.2309699292 * cubed.z,
+            -1.2684380046 * cubed.x + 2.6097574011 * cubed.y - 0.3413193965 * cubed.z,
+            -0.0041960863 * cubed.x - 0.7034186147 * cubed.y + 1.7076147010 * cubed.z,
+            mixed.w,
+        );
+    }
+    // Linear to nonlinear sRGB
+    if input.interpolation != 0u {
+        let linear = max(mixed.xyz, vec3(0.0));
+        let srgb = select(1.055 * pow(linear, vec3(1.0 / 2.4)) - 0.055, linear * 12.92, linear <= vec3(0.0031308));
+        mixed = vec4(srgb, mixed.w);
+    }
+    color = mixed;
+}
+
 fn distance_gradient(input: DistanceGradient) {
     color = mix(color, input.color, cos(distance * input.interval));
 }
 
+fn stroke(input: StrokeData) {
+    let outer = distance - input.outset;
+    let smoothing = fwidth(outer);
+    var dash = 1.0;
+    if input.dash.x > 0.0 {
+        let along = atan2(position.y, position.x) * length(position) + input.dash.z;
+        let period = input.dash.x + input.dash.y;
+        let phase = along - floor(along / period) * period;
+        dash = 1.0 - smoothstep(input.dash.x - smoothing, input.dash.x + smoothing, phase);
+    }
+    let band = smoothstep(-smoothing, smoothing, outer + input.width);
+    color = mix(color, input.color, band * dash);
+    distance = mix(distance, outer, dash);
+}
+
 fn force_field_alpha() {
     color.w = smoothstep(0.0, -distance, 1.0);
 }
 
+fn drop_shadow(input: ShadowData) {
+    // Solves dpdx(distance) = dot(gradient, dpdx(world_position)) and the same for y
+    let dx = dpdx(world_position);
+    let dy = dpdy(world_position);
+    let screen = vec2(dpdx(distance), dpdy(distance));
+    let gradient = vec2(screen.x * dy.y - dx.y * screen.y, dx.x * screen.y - screen.x * dy.x) / (dx.x * dy.y - dx.y * dy.x);
+    let shadow_distance = distance - dot(gradient, input.offset);
+
+    let blur = max(input.blur, fwidth(distance));
+    let shadow = input.color.w * (1.0 - smoothstep(-blur, blur, shadow_distance));
+    let alpha = color.w + shadow * (1.0 - color.w);
+    if alpha > 0.0 {
+        let rgb = (color.xyz * color.w + input.color.xyz * shadow * (1.0 - color.w)) / alpha;
+        color = vec4(rgb, alpha);
+    }
+}
+
+fn outer_glow(input: GlowData) {
+    let glow = input.color.w * (1.0 - smoothstep(0.0, input.radius, max(distance, 0.0)));
+    let alpha = color.w + glow * (1.0 - color.w);
+    if alpha > 0.0 {
+        let rgb = (color.xyz * color.w + input.color.xyz * glow * (1.0 - color.w)) / alpha;
+        color = vec4(rgb, alpha);
+    }
+}
+
+fn inner_shadow(input: ShadowData) {
+    let dx = dpdx(world_position);
+    let dy = dpdy(world_position);
+    let screen = vec2(dpdx(distance), dpdy(distance));
+    let gradient = vec2(screen.x * dy.y - dx.y * screen.y, dx.x * screen.y - screen.x * dy.x) / (dx.x * dy.y - dx.y * dy.x);
+    let shadow_distance = distance - dot(gradient, input.offset);
+
+    let blur = max(input.blur, fwidth(distance));
+    let shadow = input.color.w * smoothstep(-blur, blur, shadow_distance);
+    color = vec4(mix(color.xyz, input.color.xyz, shadow), color.w);
+}
+
 fn prepare_operation() {
     prev_distance = distance;
     prev_color = color;
 }
 
+fn push_operand() {
+    if operand_depth < MAX_OPERANDS {
+        operand_distances[operand_depth] = distance;
+        operand_colors[operand_depth] = color;
+    }
+    operand_depth += 1u;
+}
+
+fn pop_operand() {
+    if operand_depth == 0u {
+        return;
+    }
+    operand_depth -= 1u;
+    if operand_depth < MAX_OPERANDS {
+        prev_distance = operand_distances[operand_depth];
+        prev_color = operand_colors[operand_depth];
+    }
+}
+
 fn unioni() {
     if prev_distance < distance {
         distance = prev_distance;
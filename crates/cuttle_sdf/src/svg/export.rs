@@ -0,0 +1,552 @@
+use super::number;
+use crate::cpu::SdfSample;
+use crate::{Annular, Circle, Fill, Line, Quad, Rounded, Sdf, SdfTransform};
+use bevy_color::{ColorToComponents, ColorToPacked, Srgba};
+use bevy_ecs::entity::Entity;
+use bevy_ecs::world::{EntityRef, World};
+use bevy_math::{Affine3A, Isometry2d, Vec2, Vec4};
+use bevy_platform::collections::{HashMap, HashSet};
+use bevy_transform::prelude::GlobalTransform;
+use cuttle_core::bounding::GlobalBoundingBox;
+use cuttle_core::components::ConfigComponents;
+use cuttle_core::configs::ConfigStore;
+use cuttle_core::cpu::CuttleEvaluator;
+use cuttle_core::indices::CuttleIndices;
+use cuttle_core::shader::FunctionName;
+use std::fmt::Write;
+
+#[derive(Debug, Clone)]
+pub struct SvgExportSettings {
+    /// Cells along the longest side of a shape when extracting contours
+    pub resolution: u32,
+    /// Extract contours even for shapes with an exact outline
+    pub force_contours: bool,
+}
+
+impl Default for SvgExportSettings {
+    fn default() -> Self {
+        Self {
+            resolution: 128,
+            force_contours: false,
+        }
+    }
+}
+
+/// Converts the [`Sdf`] shape rooted at `entity`, including its extensions, to an SVG document.
+///
+/// A single [`Circle`], [`Quad`] or [`Line`], optionally [`Rounded`] and [`Annular`],
+/// is converted to an exact path. Everything else is traced with marching squares on the
+/// distance field evaluated on the CPU, with one path per [`Fill`] color.
+///
+/// Returns `None` if `entity` is not an [`Sdf`] shape, or if the shape needs contours
+/// and one of its components has no CPU implementation.
+pub fn export_svg(world: &World, entity: Entity, settings: &SvgExportSettings) -> Option<String> {
+    let root = world.get_entity(entity).ok()?;
+    if !root.contains::<Sdf>() {
+        return None;
+    }
+    let bounds = *root.get::<GlobalBoundingBox>()?;
+
+    let exact = (!settings.force_contours)
+        .then(|| exact_path(world, root))
+        .flatten();
+    let paths = match exact {
+        Some(path) => vec![path],
+        None => contour_paths(world, entity, bounds, settings.resolution)?,
+    };
+
+    let corners = [
+        Vec2::new(-1., -1.),
+        Vec2::new(1., -1.),
+        Vec2::ONE,
+        Vec2::new(-1., 1.),
+    ]
+    .map(|corner| flip(bounds.isometry.transform_point(corner * bounds.half_size)));
+    let min = corners.iter().fold(Vec2::MAX, |min, &c| min.min(c));
+    let max = corners.iter().fold(Vec2::MIN, |max, &c| max.max(c));
+    let size = max - min;
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+        number(min.x),
+        number(min.y),
+        number(size.x),
+        number(size.y),
+        number(size.x),
+        number(size.y),
+    );
+    svg.push('\n');
+    for path in paths {
+        path.write(&mut svg);
+    }
+    svg.push_str("</svg>\n");
+    Some(svg)
+}
+
+/// SVG is y down
+fn flip(point: Vec2) -> Vec2 {
+    Vec2::new(point.x, -point.y)
+}
+
+struct SvgPath {
+    data: String,
+    fill: Vec4,
+    /// Maps the path's coordinates to the document
+    transform: Option<Affine3A>,
+}
+
+impl SvgPath {
+    fn write(&self, svg: &mut String) {
+        let [r, g, b, a] = Srgba::from_vec4(self.fill).to_u8_array();
+        let _ = write!(
+            svg,
+            r##"  <path d="{}" fill="#{r:02x}{g:02x}{b:02x}" fill-rule="evenodd""##,
+            self.data
+        );
+        if a < 255 {
+            let _ = write!(svg, r#" fill-opacity="{}""#, number(a as f32 / 255.));
+        }
+        if let Some(affine) = self.transform {
+            let (x, y, t) = (affine.x_axis, affine.y_axis, affine.translation);
+            let _ = write!(
+                svg,
+                r#" transform="matrix({} {} {} {} {} {})""#,
+                number(x.x),
+                number(-x.y),
+                number(y.x),
+                number(-y.y),
+                number(t.x),
+                number(-t.y),
+            );
+        }
+        svg.push_str("/>\n");
+    }
+}
+
+enum Base {
+    Circle(f32),
+    Quad(Vec2),
+    Line(f32),
+}
+
+impl Base {
+    fn min_distance(&self) -> f32 {
+        match *self {
+            Base::Circle(radius) => -radius,
+            Base::Quad(half_size) => -half_size.min_element(),
+            Base::Line(_) => 0.,
+        }
+    }
+
+    /// Path of the points at distance `level`, in the shape's local space.
+    fn outline(&self, level: f32, data: &mut String) {
+        let n = number;
+        match *self {
+            Base::Circle(radius) => {
+                let r = radius + level;
+                let _ = write!(
+                    data,
+                    "M{} 0A{} {} 0 1 0 {} 0A{} {} 0 1 0 {} 0Z",
+                    n(r),
+                    n(r),
+                    n(r),
+                    n(-r),
+                    n(r),
+                    n(r),
+                    n(r)
+                );
+            }
+            Base::Quad(half_size) if level <= 0. => {
+                let h = half_size + level;
+                let _ = write!(
+                    data,
+                    "M{} {}H{}V{}H{}Z",
+                    n(-h.x),
+                    n(-h.y),
+                    n(h.x),
+                    n(h.y),
+                    n(-h.x)
+                );
+            }
+            Base::Quad(h) => {
+                let r = level;
+                let arc = format!("A{} {} 0 0 1", n(r), n(r));
+                let _ = write!(
+                    data,
+                    "M{} {}H{}{arc} {} {}V{}{arc} {} {}H{}{arc} {} {}V{}{arc} {} {}Z",
+                    n(-h.x),
+                    n(-h.y - r),
+                    n(h.x),
+                    n(h.x + r),
+                    n(-h.y),
+                    n(h.y),
+                    n(h.x),
+                    n(h.y + r),
+                    n(-h.x),
+                    n(-h.x - r),
+                    n(h.y),
+                    n(-h.y),
+                    n(-h.x),
+                    n(-h.y - r),
+                );
+            }
+            Base::Line(length) => {
+                let r = level;
+                let arc = format!("A{} {} 0 0 1", n(r), n(r));
+                let _ = write!(
+                    data,
+                    "M{} {}H{}{arc} {} {}H{}{arc} {} {}Z",
+                    n(-length),
+                    n(-r),
+                    n(length),
+                    n(length),
+                    n(r),
+                    n(-length),
+                    n(-length),
+                    n(-r),
+                );
+            }
+        }
+    }
+}
+
+/// Shapes made of a single base shape map to paths directly.
+///
+/// The shape is the set of points whose base distance lies in a set of intervals,
+/// found by inverting the distance operations applied after the base shape.
+fn exact_path(world: &World, root: EntityRef) -> Option<SvgPath> {
+    let mut base = None;
+    let mut transform = None;
+    let mut scale = 1.;
+    let mut operations = Vec::new();
+    let mut fill = Vec4::ZERO;
+
+    for (name, source) in component_names(world, root)? {
+        if source.id() != root.id() {
+            return None;
+        }
+        match (name.as_str(), &base) {
+            ("prepare_base" | "sdf", _) => {}
+            ("global_transform", None) => {
+                let global = source.get::<GlobalTransform>()?;
+                scale = SdfTransform::from(global).scale;
+                transform = Some(global.affine());
+            }
+            ("circle", None) => base = Some(Base::Circle(source.get::<Circle>()?.0)),
+            ("quad", None) => base = Some(Base::Quad(source.get::<Quad>()?.0)),
+            ("line", None) => base = Some(Base::Line(source.get::<Line>()?.0)),
+            ("scale_distance", Some(_)) => {}
+            ("rounded", Some(_)) => operations.push(Operation::Rounded(source.get::<Rounded>()?.0)),
+            ("annular", Some(_)) => operations.push(Operation::Annular(source.get::<Annular>()?.0)),
+            ("fill", _) => fill = Vec4::from(source.get::<Fill>()?),
+            _ => return None,
+        }
+    }
+
+    let base = base?;
+    let mut intervals = vec![(f32::NEG_INFINITY, 0.)];
+    for operation in operations.iter().rev() {
+        intervals = operation.preimage(&intervals);
+    }
+
+    let mut data = String::new();
+    for (low, high) in intervals {
+        let (low, high) = (low / scale, high / scale);
+        if !high.is_finite() || high <= base.min_distance() {
+            continue;
+        }
+        base.outline(high, &mut data);
+        if low > base.min_distance() {
+            base.outline(low, &mut data);
+        }
+    }
+
+    Some(SvgPath {
+        data,
+        fill,
+        transform: Some(transform.unwrap_or(Affine3A::IDENTITY)),
+    })
+}
+
+enum Operation {
+    Rounded(f32),
+    Annular(f32),
+}
+
+impl Operation {
+    /// The distances this operation maps into `intervals`
+    fn preimage(&self, intervals: &[(f32, f32)]) -> Vec<(f32, f32)> {
+        let mut result = Vec::new();
+        for &(low, high) in intervals {
+            match *self {
+                Operation::Rounded(rounded) => result.push((low + rounded, high + rounded)),
+                Operation::Annular(annular) => {
+                    let (low, high) = ((low + annular).max(0.), high + annular);
+                    if high < low {
+                        continue;
+                    }
+                    if low == 0. {
+                        result.push((-high, high));
+                    } else {
+                        result.extend([(-high, -low), (low, high)]);
+                    }
+                }
+            }
+        }
+        result.sort_by(|a, b| a.0.total_cmp(&b.0));
+        result
+    }
+}
+
+/// The function names of the shape's components, with the entities holding them, in shader order
+fn component_names<'w>(world: &'w World, root: EntityRef) -> Option<Vec<(String, EntityRef<'w>)>> {
+    let config = world.get_resource::<ConfigStore<Sdf>>()?.config_entity;
+    let components = world.get::<ConfigComponents>(config)?;
+    root.get::<CuttleIndices>()?
+        .components()
+        .map(|(position, source)| {
+            let name = world.get::<FunctionName>(*components.get(position as usize)?)?;
+            Some((name.0.clone(), world.get_entity(source).ok()?))
+        })
+        .collect()
+}
+
+/// Traces the outline of each fill color with marching squares
+fn contour_paths(
+    world: &World,
+    entity: Entity,
+    bounds: GlobalBoundingBox,
+    resolution: u32,
+) -> Option<Vec<SvgPath>> {
+    let evaluator = CuttleEvaluator::<SdfSample>::new(world)?;
+    if !evaluator.supports(entity) {
+        return None;
+    }
+
+    let cell = bounds.half_size.max_element() * 2. / resolution.max(1) as f32;
+    // One cell of margin on each side keeps every contour closed
+    let cells = (bounds.half_size * 2. / cell).ceil().as_uvec2() + 2;
+    let origin = -(cells.as_vec2() * cell) * 0.5;
+    let grid = Grid {
+        frame: bounds.isometry,
+        origin,
+        cell,
+        width: cells.x + 1,
+        height: cells.y + 1,
+    };
+
+    let samples: Vec<SdfSample> = (0..grid.height)
+        .flat_map(|y| (0..grid.width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let inner = x > 0 && y > 0 && x < grid.width - 1 && y < grid.height - 1;
+            let point = grid.world(Vec2::new(x as f32, y as f32));
+            match inner {
+                true => evaluator.sample(entity, point).unwrap_or_default(),
+                false => SdfSample {
+                    distance: cell,
+                    ..Default::default()
+                },
+            }
+        })
+        .collect();
+
+    let visible = |sample: &SdfSample| sample.distance <= 0. && sample.color.w > 0.;
+    let mut colors: Vec<[u8; 4]> = samples
+        .iter()
+        .filter(|sample| visible(sample))
+        .map(|sample| sample.color().to_u8_array())
+        .collect();
+    colors.sort();
+    colors.dedup();
+
+    let paths = colors
+        .into_iter()
+        .map(|color| {
+            let field: Vec<f32> = samples
+                .iter()
+                .map(|sample| match visible(sample) {
+                    true if sample.color().to_u8_array() == color => sample.distance,
+                    // Neighbouring colors meet halfway between samples
+                    true => cell * 0.5,
+                    false => sample.distance.max(f32::EPSILON),
+                })
+                .collect();
+            SvgPath {
+                data: grid.contours(&field),
+                fill: Srgba::from_u8_array(color).to_vec4(),
+                transform: None,
+            }
+        })
+        .collect();
+    Some(paths)
+}
+
+struct Grid {
+    frame: Isometry2d,
+    origin: Vec2,
+    cell: f32,
+    width: u32,
+    height: u32,
+}
+
+/// A point on the edge between two neighbouring samples, identified by the first sample
+/// and whether the edge is horizontal
+type EdgeId = (u32, u32, bool);
+
+impl Grid {
+    fn world(&self, grid_position: Vec2) -> Vec2 {
+        self.frame
+            .transform_point(self.origin + grid_position * self.cell)
+    }
+
+    fn contours(&self, field: &[f32]) -> String {
+        let value = |x: u32, y: u32| field[(y * self.width + x) as usize];
+        let mut neighbours: HashMap<EdgeId, Vec<EdgeId>> = HashMap::default();
+        let mut connect = |a: EdgeId, b: EdgeId| {
+            neighbours.entry(a).or_default().push(b);
+            neighbours.entry(b).or_default().push(a);
+        };
+
+        for y in 0..self.height - 1 {
+            for x in 0..self.width - 1 {
+                let corners = [
+                    value(x, y),
+                    value(x + 1, y),
+                    value(x + 1, y + 1),
+                    value(x, y + 1),
+                ];
+                let inside = corners.map(|v| v <= 0.);
+                let bottom = (x, y, true);
+                let right = (x + 1, y, false);
+                let top = (x, y + 1, true);
+                let left = (x, y, false);
+
+                let crossed: Vec<EdgeId> =
+                    [(bottom, 0, 1), (right, 1, 2), (top, 3, 2), (left, 0, 3)]
+                        .into_iter()
+                        .filter(|&(_, a, b)| inside[a] != inside[b])
+                        .map(|(edge, ..)| edge)
+                        .collect();
+
+                match crossed.len() {
+                    2 => connect(crossed[0], crossed[1]),
+                    4 => {
+                        // Saddle, the center decides which corners are connected
+                        let center_inside = corners.iter().sum::<f32>() <= 0.;
+                        if inside[0] == center_inside {
+                            connect(bottom, right);
+                            connect(top, left);
+                        } else {
+                            connect(bottom, left);
+                            connect(top, right);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let point = |(x, y, horizontal): EdgeId| {
+            let (a, b) = match horizontal {
+                true => (value(x, y), value(x + 1, y)),
+                false => (value(x, y), value(x, y + 1)),
+            };
+            let t = a / (a - b);
+            let offset = match horizontal {
+                true => Vec2::new(t, 0.),
+                false => Vec2::new(0., t),
+            };
+            flip(self.world(Vec2::new(x as f32, y as f32) + offset))
+        };
+
+        let mut data = String::new();
+        let mut keys: Vec<EdgeId> = neighbours.keys().copied().collect();
+        keys.sort();
+        let mut visited: HashSet<EdgeId> = HashSet::default();
+        for start in keys {
+            if !visited.insert(start) {
+                continue;
+            }
+            let p = point(start);
+            let _ = write!(data, "M{} {}", number(p.x), number(p.y));
+            let mut current = start;
+            while let Some(&next) = neighbours[&current].iter().find(|n| !visited.contains(*n)) {
+                visited.insert(next);
+                let p = point(next);
+                let _ = write!(data, "L{} {}", number(p.x), number(p.y));
+                current = next;
+            }
+            data.push('Z');
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Base, Grid, Operation};
+    use bevy_math::{Isometry2d, Vec2};
+
+    #[test]
+    fn annular_rounded_intervals() {
+        // distance = |d| - 2 - 1 <= 0, so the base distance lies within -3..=3
+        let mut intervals = vec![(f32::NEG_INFINITY, 0.)];
+        for operation in [Operation::Annular(2.), Operation::Rounded(1.)]
+            .iter()
+            .rev()
+        {
+            intervals = operation.preimage(&intervals);
+        }
+        assert_eq!(intervals, vec![(-3., 3.)]);
+
+        // Nested annular: ||d| - 2| - 1 <= 0 leaves two bands
+        let mut intervals = vec![(f32::NEG_INFINITY, 0.)];
+        for operation in [Operation::Annular(2.), Operation::Annular(1.)]
+            .iter()
+            .rev()
+        {
+            intervals = operation.preimage(&intervals);
+        }
+        assert_eq!(intervals, vec![(-3., -1.), (1., 3.)]);
+    }
+
+    #[test]
+    fn exact_outlines() {
+        let mut data = String::new();
+        Base::Circle(10.).outline(2., &mut data);
+        assert_eq!(data, "M12 0A12 12 0 1 0 -12 0A12 12 0 1 0 12 0Z");
+
+        let mut data = String::new();
+        Base::Quad(Vec2::new(3., 2.)).outline(-1., &mut data);
+        assert_eq!(data, "M-2 -1H2V1H-2Z");
+    }
+
+    #[test]
+    fn contour_of_circle() {
+        let grid = Grid {
+            frame: Isometry2d::IDENTITY,
+            origin: Vec2::splat(-12.),
+            cell: 1.,
+            width: 25,
+            height: 25,
+        };
+        let field: Vec<f32> = (0..25 * 25)
+            .map(|i| Vec2::new((i % 25) as f32 - 12., (i / 25) as f32 - 12.).length() - 8.)
+            .collect();
+
+        let data = grid.contours(&field);
+
+        assert_eq!(data.matches('M').count(), 1);
+        let points = data
+            .trim_end_matches('Z')
+            .split(['M', 'L'])
+            .filter(|p| !p.is_empty())
+            .map(|p| {
+                let (x, y) = p.split_once(' ').unwrap();
+                Vec2::new(x.parse().unwrap(), y.parse().unwrap())
+            });
+        for point in points {
+            assert!((point.length() - 8.).abs() < 0.1, "{point}");
+        }
+    }
+}
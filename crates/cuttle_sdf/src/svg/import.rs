@@ -0,0 +1,814 @@
+use super::parse::{self, SvgSegment, SvgSubpath};
+use crate::{
+    Annular, BezierPath, Circle, Fill, Intersect, Line, Polygon, Polyline, PopOperand, PushOperand,
+    Quad, Rounded, Sdf, Subtract, Unioni, Xor,
+};
+use bevy_app::{App, Update};
+use bevy_asset::io::Reader;
+use bevy_asset::{Asset, AssetApp, AssetEvent, AssetId, AssetLoader, Assets, Handle, LoadContext};
+use bevy_camera::visibility::Visibility;
+use bevy_color::{Alpha, Srgba};
+use bevy_derive::Deref;
+use bevy_ecs::prelude::*;
+use bevy_ecs::system::EntityCommands;
+use bevy_log::warn;
+use bevy_math::{Affine2, Affine3A, Mat4, Vec2, Vec3A};
+use bevy_platform::collections::HashSet;
+use bevy_reflect::{Reflect, TypePath};
+use bevy_transform::prelude::Transform;
+use cuttle_core::prelude::Extends;
+use derive_more::{Display, Error, From};
+use std::string::FromUtf8Error;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_asset::<SvgShapes>()
+        .register_asset_loader(SvgLoader)
+        .register_type::<SvgShapesRoot>()
+        .add_systems(Update, spawn_svg_shapes);
+}
+
+/// Distance along z between the shapes of one [`SvgShapes`], to keep document order.
+const SHAPE_Z_STEP: f32 = 0.01;
+
+/// Shapes loaded from an SVG file, instanced by [`SvgShapesRoot`].
+///
+/// Supports `circle`, `rect` (with `rx`), `line`, `ellipse`, `polygon`, `polyline` and `path`
+/// elements with solid fills and strokes. Each top level element or `<g>` becomes one
+/// shape: the first part is the root, all others extend it with a union, or with the
+/// operation named by a `data-cuttle-operation` attribute. Strokes are added as a second
+/// shape on top of the fills. The subpaths of a path are combined with [`Xor`] for the
+/// `evenodd` fill rule, and by their winding for `nonzero`, in a group of their own when
+/// parts come before the path.
+///
+/// SVG user units map to world units, with y flipped to point up.
+/// Unsupported features are skipped and reported in [`SvgShapes::warnings`].
+#[derive(Asset, TypePath, Debug, Clone, Default)]
+pub struct SvgShapes {
+    pub shapes: Vec<SvgShape>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SvgShape {
+    pub parts: Vec<SvgPart>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SvgPart {
+    pub primitive: SvgPrimitive,
+    pub transform: Transform,
+    pub fill: Srgba,
+    /// How the part combines with the ones before it, ignored for the first part of a shape
+    pub operation: SvgOperation,
+    pub rounded: f32,
+    pub annular: Option<f32>,
+    pub group: SvgGroup,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgPrimitive {
+    Circle(f32),
+    Quad(Vec2),
+    Line(f32),
+    /// Closed outline, also used to fill polylines
+    Polygon(Vec<Vec2>),
+    /// Open outline, only used for the stroke of a polyline
+    Polyline(Vec<Vec2>),
+    Path(SvgSubpath),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SvgOperation {
+    Union,
+    Subtract,
+    Intersect,
+    Xor,
+}
+
+/// Nests parts to combine them with each other first, like [`ShapeCommands::begin_group`].
+///
+/// [`ShapeCommands::begin_group`]: crate::builder::ShapeCommands::begin_group
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum SvgGroup {
+    #[default]
+    None,
+    /// The part starts a nested operand with [`PushOperand`], its operation is ignored
+    Begin,
+    /// The nested operand ends after the part, combined with the parts before it through
+    /// the operation
+    End(SvgOperation),
+}
+
+#[derive(Default)]
+pub struct SvgLoader;
+
+impl AssetLoader for SvgLoader {
+    type Asset = SvgShapes;
+    type Settings = ();
+    type Error = LoadSvgError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<SvgShapes, LoadSvgError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let text = String::from_utf8(bytes)?;
+        let shapes = parse_svg(&text)?;
+        for warning in &shapes.warnings {
+            warn!("{}: {warning}", load_context.path().display());
+        }
+        Ok(shapes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["svg"]
+    }
+}
+
+#[derive(Debug, Error, Display, From)]
+pub enum LoadSvgError {
+    IO(std::io::Error),
+    Utf8(FromUtf8Error),
+    Xml(roxmltree::Error),
+}
+
+/// Spawns the shapes of an [`SvgShapes`] asset as children of this entity,
+/// respawning them when the asset is reloaded.
+#[derive(Component, Reflect, Debug, Clone, Default, Deref)]
+#[require(Transform, Visibility)]
+#[reflect(Component)]
+pub struct SvgShapesRoot(pub Handle<SvgShapes>);
+
+#[derive(Component)]
+struct SpawnedSvgShapes(Vec<Entity>);
+
+fn spawn_svg_shapes(
+    mut cmds: Commands,
+    mut messages: MessageReader<AssetEvent<SvgShapes>>,
+    assets: Res<Assets<SvgShapes>>,
+    roots: Query<(Entity, Ref<SvgShapesRoot>, Option<&SpawnedSvgShapes>)>,
+) {
+    let updated: HashSet<AssetId<SvgShapes>> = messages
+        .read()
+        .filter_map(|message| match message {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+
+    for (entity, root, spawned) in &roots {
+        if !root.is_changed() && !updated.contains(&root.id()) {
+            continue;
+        }
+        let Some(svg) = assets.get(&root.0) else {
+            continue;
+        };
+        for &spawned in spawned.iter().flat_map(|spawned| &spawned.0) {
+            cmds.entity(spawned).try_despawn();
+        }
+        let mut entities = Vec::new();
+        for (i, shape) in svg.shapes.iter().enumerate() {
+            spawn_shape(
+                &mut cmds,
+                entity,
+                shape,
+                i as f32 * SHAPE_Z_STEP,
+                &mut entities,
+            );
+        }
+        cmds.entity(entity).insert(SpawnedSvgShapes(entities));
+    }
+}
+
+fn spawn_shape(
+    cmds: &mut Commands,
+    parent: Entity,
+    shape: &SvgShape,
+    z: f32,
+    entities: &mut Vec<Entity>,
+) {
+    let mut root = None;
+    for part in &shape.parts {
+        let mut transform = part.transform;
+        transform.translation.z += z;
+        let mut entity = cmds.spawn((transform, Fill(part.fill), ChildOf(parent)));
+        insert_primitive(&mut entity, &part.primitive);
+        if part.rounded != 0. {
+            entity.insert(Rounded(part.rounded));
+        }
+        if let Some(annular) = part.annular {
+            entity.insert(Annular(annular));
+        }
+        match root {
+            None => {
+                entity.insert(Sdf);
+                root = Some(entity.id());
+            }
+            Some(root) => {
+                entity.insert(Extends(root));
+                match part.group {
+                    SvgGroup::Begin => {
+                        entity.insert(PushOperand);
+                    }
+                    _ => insert_operation(&mut entity, part.operation),
+                }
+            }
+        }
+        entities.push(entity.id());
+        if let (SvgGroup::End(operation), Some(root)) = (part.group, root) {
+            let mut end = cmds.spawn((Extends(root), ChildOf(parent), PopOperand));
+            insert_operation(&mut end, operation);
+            entities.push(end.id());
+        }
+    }
+}
+
+fn insert_operation(entity: &mut EntityCommands, operation: SvgOperation) {
+    match operation {
+        SvgOperation::Union => entity.insert(Unioni),
+        SvgOperation::Subtract => entity.insert(Subtract),
+        SvgOperation::Intersect => entity.insert(Intersect),
+        SvgOperation::Xor => entity.insert(Xor),
+    };
+}
+
+fn insert_primitive(entity: &mut EntityCommands, primitive: &SvgPrimitive) {
+    match primitive {
+        &SvgPrimitive::Circle(radius) => entity.insert(Circle(radius)),
+        &SvgPrimitive::Quad(half_size) => entity.insert(Quad(half_size)),
+        &SvgPrimitive::Line(half_length) => entity.insert(Line(half_length)),
+        SvgPrimitive::Polygon(points) => entity.insert(Polygon(points.clone())),
+        SvgPrimitive::Polyline(points) => entity.insert(Polyline::new(points.clone(), 0.)),
+        SvgPrimitive::Path(subpath) => entity.insert(cubic_path(subpath)),
+    };
+}
+
+/// Converts all segments to cubic curves, as a [`BezierPath`] has a single degree.
+/// Only subpaths ending with `Z` are closed.
+fn cubic_path(subpath: &SvgSubpath) -> BezierPath {
+    let mut points = vec![subpath.start];
+    let mut previous = subpath.start;
+    for segment in &subpath.segments {
+        match *segment {
+            SvgSegment::Line(end) => points.extend([
+                previous.lerp(end, 1. / 3.),
+                previous.lerp(end, 2. / 3.),
+                end,
+            ]),
+            SvgSegment::Quadratic(control, end) => points.extend([
+                previous.lerp(control, 2. / 3.),
+                end.lerp(control, 2. / 3.),
+                end,
+            ]),
+            SvgSegment::Cubic(first, second, end) => points.extend([first, second, end]),
+        }
+        previous = segment.end();
+    }
+    let path = BezierPath::cubic(points);
+    match subpath.closed {
+        true => path.closed(),
+        false => path,
+    }
+}
+
+/// Parses an SVG document into [`SvgShapes`].
+pub fn parse_svg(text: &str) -> Result<SvgShapes, roxmltree::Error> {
+    let document = roxmltree::Document::parse(text)?;
+    let mut importer = Importer::default();
+    let root = document.root_element();
+    let style = Style::default().inherit(root, &mut importer.warnings);
+    for child in root.children().filter(roxmltree::Node::is_element) {
+        let mut parts = Parts::default();
+        importer.element(child, style, Affine2::IDENTITY, &mut parts);
+        for parts in [parts.fills, parts.strokes] {
+            if !parts.is_empty() {
+                importer.shapes.push(SvgShape { parts });
+            }
+        }
+    }
+    Ok(SvgShapes {
+        shapes: importer.shapes,
+        warnings: importer.warnings.0,
+    })
+}
+
+#[derive(Default)]
+struct Warnings(Vec<String>);
+
+impl Warnings {
+    fn push(&mut self, warning: String) {
+        if !self.0.contains(&warning) {
+            self.0.push(warning);
+        }
+    }
+}
+
+#[derive(Default)]
+struct Importer {
+    shapes: Vec<SvgShape>,
+    warnings: Warnings,
+}
+
+/// Parts of one top level element, strokes are drawn on top of fills.
+#[derive(Default)]
+struct Parts {
+    fills: Vec<SvgPart>,
+    strokes: Vec<SvgPart>,
+}
+
+/// Inherited presentation attributes.
+#[derive(Copy, Clone)]
+struct Style {
+    fill: Option<Srgba>,
+    stroke: Option<Srgba>,
+    stroke_width: f32,
+    evenodd: bool,
+    opacity: f32,
+    fill_opacity: f32,
+    stroke_opacity: f32,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fill: Some(Srgba::BLACK),
+            stroke: None,
+            stroke_width: 1.,
+            evenodd: false,
+            opacity: 1.,
+            fill_opacity: 1.,
+            stroke_opacity: 1.,
+        }
+    }
+}
+
+const UNSUPPORTED_ATTRIBUTES: [&str; 8] = [
+    "clip-path",
+    "mask",
+    "filter",
+    "stroke-dasharray",
+    "marker-start",
+    "marker-mid",
+    "marker-end",
+    "paint-order",
+];
+
+impl Style {
+    fn inherit(mut self, node: roxmltree::Node, warnings: &mut Warnings) -> Self {
+        let mut paint = |name: &str, current: Option<Srgba>| match attribute(node, name) {
+            None | Some("inherit") => current,
+            Some(value) => parse::color(value).unwrap_or_else(|| {
+                warnings.push(format!("unsupported {name} `{value}`, using black"));
+                Some(Srgba::BLACK)
+            }),
+        };
+        self.fill = paint("fill", self.fill);
+        self.stroke = paint("stroke", self.stroke);
+
+        let number = |name: &str| attribute(node, name).and_then(parse::length);
+        self.stroke_width = number("stroke-width").unwrap_or(self.stroke_width);
+        self.opacity *= number("opacity").unwrap_or(1.);
+        self.fill_opacity = number("fill-opacity").unwrap_or(self.fill_opacity);
+        self.stroke_opacity = number("stroke-opacity").unwrap_or(self.stroke_opacity);
+        if let Some(rule) = attribute(node, "fill-rule") {
+            self.evenodd = rule == "evenodd";
+        }
+
+        for name in UNSUPPORTED_ATTRIBUTES {
+            if attribute(node, name).is_some_and(|value| value != "none") {
+                warnings.push(format!("`{name}` is not supported"));
+            }
+        }
+        self
+    }
+
+    fn fill(&self) -> Option<Srgba> {
+        self.fill
+            .map(|fill| fill.with_alpha(fill.alpha * self.fill_opacity * self.opacity))
+    }
+
+    fn stroke(&self) -> Option<Srgba> {
+        self.stroke
+            .filter(|_| self.stroke_width > 0.)
+            .map(|stroke| stroke.with_alpha(stroke.alpha * self.stroke_opacity * self.opacity))
+    }
+}
+
+/// Looks up a presentation attribute, declarations in `style` take precedence.
+fn attribute<'a>(node: roxmltree::Node<'a, 'a>, name: &str) -> Option<&'a str> {
+    node.attribute("style")
+        .and_then(|style| {
+            style.split(';').find_map(|declaration| {
+                let (key, value) = declaration.split_once(':')?;
+                (key.trim() == name).then(|| value.trim())
+            })
+        })
+        .or_else(|| node.attribute(name))
+}
+
+impl Importer {
+    fn element(
+        &mut self,
+        node: roxmltree::Node,
+        style: Style,
+        transform: Affine2,
+        parts: &mut Parts,
+    ) {
+        let name = node.tag_name().name();
+        if matches!(name, "defs" | "title" | "desc" | "metadata") {
+            return;
+        }
+
+        let transform = match node.attribute("transform").map(parse::transform) {
+            Some(Ok(local)) => transform * local,
+            Some(Err(err)) => {
+                self.warnings.push(err);
+                transform
+            }
+            None => transform,
+        };
+        let style = style.inherit(node, &mut self.warnings);
+        let operation = match node.attribute("data-cuttle-operation") {
+            None | Some("union") => SvgOperation::Union,
+            Some("subtract") => SvgOperation::Subtract,
+            Some("intersect") => SvgOperation::Intersect,
+            Some("xor") => SvgOperation::Xor,
+            Some(other) => {
+                self.warnings
+                    .push(format!("unknown data-cuttle-operation `{other}`"));
+                SvgOperation::Union
+            }
+        };
+        if name == "g" {
+            for child in node.children().filter(roxmltree::Node::is_element) {
+                self.element(child, style, transform, parts);
+            }
+            return;
+        }
+
+        let number = |name: &str| node.attribute(name).and_then(parse::length).unwrap_or(0.);
+        let mut shape = Shape {
+            style,
+            transform,
+            operation,
+            parts,
+            warnings: &mut self.warnings,
+        };
+
+        match name {
+            "circle" => {
+                let center = Vec2::new(number("cx"), number("cy"));
+                shape.add(
+                    Affine2::from_translation(center),
+                    SvgPrimitive::Circle(number("r")),
+                    0.,
+                );
+            }
+            "ellipse" => {
+                let center = Vec2::new(number("cx"), number("cy"));
+                let radii = Vec2::new(number("rx"), number("ry"));
+                let radius = radii.min_element();
+                if radius > 0. {
+                    shape.add(
+                        Affine2::from_scale_angle_translation(radii / radius, 0., center),
+                        SvgPrimitive::Circle(radius),
+                        0.,
+                    );
+                }
+            }
+            "rect" => {
+                let size = Vec2::new(number("width"), number("height"));
+                let position = Vec2::new(number("x"), number("y"));
+                let rx = node.attribute("rx").and_then(parse::length);
+                let ry = node.attribute("ry").and_then(parse::length);
+                if rx.is_some() && ry.is_some() && rx != ry {
+                    shape
+                        .warnings
+                        .push("elliptical rect corners are approximated by `rx`".to_string());
+                }
+                let radius = rx.or(ry).unwrap_or(0.).clamp(0., size.min_element() * 0.5);
+                shape.add(
+                    Affine2::from_translation(position + size * 0.5),
+                    SvgPrimitive::Quad(size * 0.5 - radius),
+                    radius,
+                );
+            }
+            "line" => {
+                let from = Vec2::new(number("x1"), number("y1"));
+                let to = Vec2::new(number("x2"), number("y2"));
+                shape.line(from, to);
+            }
+            "polygon" | "polyline" => {
+                let points = node.attribute("points").map(parse::points);
+                shape.polygon(points.unwrap_or_default(), name == "polygon");
+            }
+            "path" => match node.attribute("d").map(parse::path) {
+                Some(Ok(subpaths)) => shape.path(subpaths),
+                Some(Err(err)) => shape.warnings.push(err),
+                None => {}
+            },
+            "svg" | "use" | "text" | "image" | "symbol" | "switch" | "foreignObject" => {
+                shape.warnings.push(format!("`<{name}>` is not supported"));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Builds the parts of a single element.
+struct Shape<'a> {
+    style: Style,
+    /// SVG user space from element space
+    transform: Affine2,
+    operation: SvgOperation,
+    parts: &'a mut Parts,
+    warnings: &'a mut Warnings,
+}
+
+impl Shape<'_> {
+    /// Adds the fill and stroke of a primitive centered at `local`.
+    fn add(&mut self, local: Affine2, primitive: SvgPrimitive, rounded: f32) {
+        let transform = self.cuttle_transform(local);
+        if let Some(fill) = self.style.fill() {
+            let operation = self.fill_operation(self.operation);
+            self.parts.fills.push(SvgPart {
+                primitive: primitive.clone(),
+                transform,
+                fill,
+                operation,
+                rounded,
+                annular: None,
+                group: SvgGroup::None,
+            });
+        }
+        if let Some(stroke) = self.style.stroke() {
+            self.parts.strokes.push(SvgPart {
+                primitive,
+                transform,
+                fill: stroke,
+                operation: SvgOperation::Union,
+                rounded,
+                annular: Some(self.style.stroke_width * 0.5),
+                group: SvgGroup::None,
+            });
+        }
+    }
+
+    /// Open outlines are filled as if closed,
+    /// their stroke follows the open outline with round caps.
+    fn add_open(&mut self, fill: SvgPrimitive, stroke: SvgPrimitive) {
+        let transform = self.cuttle_transform(Affine2::IDENTITY);
+        if let Some(color) = self.style.fill() {
+            let operation = self.fill_operation(self.operation);
+            self.parts.fills.push(SvgPart {
+                primitive: fill,
+                transform,
+                fill: color,
+                operation,
+                rounded: 0.,
+                annular: None,
+                group: SvgGroup::None,
+            });
+        }
+        if let Some(color) = self.style.stroke() {
+            self.parts.strokes.push(SvgPart {
+                primitive: stroke,
+                transform,
+                fill: color,
+                operation: SvgOperation::Union,
+                rounded: self.style.stroke_width * 0.5,
+                annular: None,
+                group: SvgGroup::None,
+            });
+        }
+    }
+
+    /// Lines are only stroked, with round caps.
+    fn line(&mut self, from: Vec2, to: Vec2) {
+        let Some(stroke) = self.style.stroke() else {
+            return;
+        };
+        let delta = to - from;
+        let local = Affine2::from_angle_translation(delta.to_angle(), (from + to) * 0.5);
+        let transform = self.cuttle_transform(local);
+        self.parts.strokes.push(SvgPart {
+            primitive: SvgPrimitive::Line(delta.length() * 0.5),
+            transform,
+            fill: stroke,
+            operation: SvgOperation::Union,
+            rounded: self.style.stroke_width * 0.5,
+            annular: None,
+            group: SvgGroup::None,
+        });
+    }
+
+    fn polygon(&mut self, points: Vec<Vec2>, closed: bool) {
+        let points: Vec<_> = points.into_iter().map(flip).collect();
+        match closed {
+            true => self.add(Affine2::IDENTITY, SvgPrimitive::Polygon(points), 0.),
+            false => self.add_open(
+                SvgPrimitive::Polygon(points.clone()),
+                SvgPrimitive::Polyline(points),
+            ),
+        }
+    }
+
+    fn path(&mut self, subpaths: Vec<SvgSubpath>) {
+        let Some(orientation) = subpaths.first().map(|s| s.signed_area() >= 0.) else {
+            return;
+        };
+        let first = self.parts.fills.len();
+        for (i, mut subpath) in subpaths.into_iter().enumerate() {
+            let operation = match (i, self.style.evenodd) {
+                (0, _) => self.operation,
+                (_, true) => SvgOperation::Xor,
+                (_, false) if (subpath.signed_area() >= 0.) == orientation => SvgOperation::Union,
+                (_, false) => SvgOperation::Subtract,
+            };
+            subpath.map(flip);
+            let previous = std::mem::replace(&mut self.operation, operation);
+            match subpath.closed {
+                true => self.add(Affine2::IDENTITY, SvgPrimitive::Path(subpath), 0.),
+                false => {
+                    let filled = SvgSubpath {
+                        closed: true,
+                        ..subpath.clone()
+                    };
+                    self.add_open(SvgPrimitive::Path(filled), SvgPrimitive::Path(subpath));
+                }
+            }
+            self.operation = previous;
+        }
+
+        // Subpaths only combine with each other, not with the parts before the path
+        if let [begin, .., end] = &mut self.parts.fills[first..]
+            && first > 0
+        {
+            begin.group = SvgGroup::Begin;
+            end.group = SvgGroup::End(self.operation);
+        }
+    }
+
+    /// The first fill of a shape is its root, a subtraction can not start it.
+    fn fill_operation(&self, operation: SvgOperation) -> SvgOperation {
+        match self.parts.fills.is_empty() {
+            true => SvgOperation::Union,
+            false => operation,
+        }
+    }
+
+    /// Converts `transform * local` to the y up space of cuttle.
+    fn cuttle_transform(&mut self, local: Affine2) -> Transform {
+        let flip = Affine2::from_scale(Vec2::new(1., -1.));
+        let affine = flip * self.transform * local * flip;
+        if affine.matrix2.x_axis.dot(affine.matrix2.y_axis).abs() > 1e-4 {
+            self.warnings
+                .push("skewed transforms are not supported".to_string());
+        }
+        let affine = Affine3A::from_cols(
+            affine.matrix2.x_axis.extend(0.).into(),
+            affine.matrix2.y_axis.extend(0.).into(),
+            Vec3A::Z,
+            affine.translation.extend(0.).into(),
+        );
+        Transform::from_matrix(Mat4::from(affine))
+    }
+}
+
+fn flip(point: Vec2) -> Vec2 {
+    Vec2::new(point.x, -point.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SvgGroup, SvgOperation, SvgPrimitive, cubic_path, parse_svg, spawn_shape};
+    use crate::{PopOperand, PushOperand, Unioni, Xor};
+    use bevy_color::Srgba;
+    use bevy_ecs::world::World;
+    use bevy_math::{Vec2, Vec3};
+
+    #[test]
+    fn shapes_from_document() {
+        let svg = parse_svg(
+            r##"<svg xmlns="http://www.w3.org/2000/svg">
+                <circle cx="10" cy="20" r="5" fill="#ff0000" stroke="blue" stroke-width="2"/>
+                <g transform="translate(100 0)" style="fill: blue">
+                    <rect width="20" height="10" rx="2"/>
+                    <circle r="3" data-cuttle-operation="subtract"/>
+                </g>
+                <text>unsupported</text>
+            </svg>"##,
+        )
+        .unwrap();
+
+        assert_eq!(svg.shapes.len(), 3);
+        let [fill, stroke] = [&svg.shapes[0].parts[0], &svg.shapes[1].parts[0]];
+        assert_eq!(fill.primitive, SvgPrimitive::Circle(5.));
+        assert_eq!(fill.fill, Srgba::RED);
+        assert_eq!(fill.transform.translation, Vec3::new(10., -20., 0.));
+        assert_eq!(stroke.annular, Some(1.));
+        assert_eq!(stroke.fill, Srgba::BLUE);
+
+        let group = &svg.shapes[2].parts;
+        assert_eq!(group[0].primitive, SvgPrimitive::Quad(Vec2::new(8., 3.)));
+        assert_eq!(group[0].rounded, 2.);
+        assert_eq!(group[0].transform.translation, Vec3::new(110., -5., 0.));
+        assert_eq!(group[1].operation, SvgOperation::Subtract);
+        assert_eq!(group[1].fill, Srgba::BLUE);
+
+        assert_eq!(svg.warnings, vec!["`<text>` is not supported".to_string()]);
+    }
+
+    #[test]
+    fn path_fill_rules() {
+        let svg = parse_svg(
+            r#"<svg>
+                <path d="M0 0h10v10h-10z M2 2v6h6v-6z"/>
+                <path fill-rule="evenodd" d="M0 0h10v10h-10z M2 2h6v6h-6z"/>
+            </svg>"#,
+        )
+        .unwrap();
+        let operations = |shape: usize| {
+            let parts = &svg.shapes[shape].parts;
+            parts.iter().map(|p| p.operation).collect::<Vec<_>>()
+        };
+        assert_eq!(operations(0)[1], SvgOperation::Subtract);
+        assert_eq!(operations(1)[1], SvgOperation::Xor);
+        assert!(
+            svg.shapes[1]
+                .parts
+                .iter()
+                .all(|p| p.group == SvgGroup::None)
+        );
+    }
+
+    #[test]
+    fn subpaths_after_other_parts_are_grouped() {
+        let svg = parse_svg(
+            r#"<svg>
+                <g>
+                    <rect x="-5" y="-5" width="30" height="30"/>
+                    <path fill-rule="evenodd" d="M0 0h10v10h-10z M2 2h6v6h-6z"/>
+                </g>
+            </svg>"#,
+        )
+        .unwrap();
+        let shape = &svg.shapes[0];
+        let groups: Vec<_> = shape.parts.iter().map(|p| p.group).collect();
+        assert_eq!(
+            groups,
+            [
+                SvgGroup::None,
+                SvgGroup::Begin,
+                SvgGroup::End(SvgOperation::Union)
+            ]
+        );
+
+        let mut world = World::new();
+        let parent = world.spawn_empty().id();
+        let mut entities = Vec::new();
+        spawn_shape(&mut world.commands(), parent, shape, 0., &mut entities);
+        world.flush();
+
+        // The hole of the path is not cut out of the rect
+        assert_eq!(entities.len(), 4);
+        let push = world.entity(entities[1]);
+        assert!(push.contains::<PushOperand>() && !push.contains::<Unioni>());
+        assert!(world.entity(entities[2]).contains::<Xor>());
+        let pop = world.entity(entities[3]);
+        assert!(pop.contains::<PopOperand>() && pop.contains::<Unioni>());
+    }
+
+    #[test]
+    fn open_outlines_stroke_open() {
+        let svg = parse_svg(
+            r#"<svg fill="red" stroke="blue" stroke-width="2">
+                <polyline points="0 0 10 0 10 10"/>
+            </svg>"#,
+        )
+        .unwrap();
+        let points = vec![Vec2::ZERO, Vec2::new(10., 0.), Vec2::new(10., -10.)];
+        let [fill, stroke] = [&svg.shapes[0].parts[0], &svg.shapes[1].parts[0]];
+        assert_eq!(fill.primitive, SvgPrimitive::Polygon(points.clone()));
+        assert_eq!(stroke.primitive, SvgPrimitive::Polyline(points));
+        assert_eq!((stroke.rounded, stroke.annular), (1., None));
+
+        let svg = parse_svg(
+            r#"<svg fill="red" stroke="blue">
+                <path d="M0 0 L10 0 L10 10"/>
+            </svg>"#,
+        )
+        .unwrap();
+        let [fill, stroke] = [&svg.shapes[0].parts[0], &svg.shapes[1].parts[0]];
+        let (SvgPrimitive::Path(filled), SvgPrimitive::Path(stroked)) =
+            (&fill.primitive, &stroke.primitive)
+        else {
+            panic!("expected paths");
+        };
+        assert!(cubic_path(filled).closed);
+        assert!(!cubic_path(stroked).closed);
+    }
+}
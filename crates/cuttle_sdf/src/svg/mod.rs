@@ -0,0 +1,21 @@
+//! Conversion between [`Sdf`](crate::Sdf) shapes and SVG documents.
+
+mod export;
+#[cfg(feature = "svg_import")]
+pub mod import;
+#[cfg(feature = "svg_import")]
+mod parse;
+
+pub use export::{SvgExportSettings, export_svg};
+#[cfg(feature = "svg_import")]
+pub use import::{SvgLoader, SvgShapes, SvgShapesRoot};
+
+/// Formats a coordinate with at most three decimals, without trailing zeros.
+fn number(value: f32) -> String {
+    let formatted = format!("{value:.3}");
+    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+    match trimmed {
+        "-0" | "" => "0".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
@@ -0,0 +1,451 @@
+//! Parsers for the attribute value syntaxes of SVG.
+
+use bevy_color::{Alpha, Srgba};
+use bevy_math::{Affine2, Mat2, Vec2};
+use std::f32::consts::PI;
+
+/// Splits SVG number lists, which may omit separators as in `1.5.5-2`.
+pub(super) struct Numbers<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Numbers<'a> {
+    pub fn new(source: &'a str) -> Self {
+        Self { rest: source }
+    }
+
+    fn skip_separators(&mut self) {
+        self.rest = self
+            .rest
+            .trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    }
+
+    pub fn is_empty(&mut self) -> bool {
+        self.skip_separators();
+        self.rest.is_empty()
+    }
+
+    pub fn peek_char(&mut self) -> Option<char> {
+        self.skip_separators();
+        self.rest.chars().next()
+    }
+
+    pub fn next_char(&mut self) -> Option<char> {
+        let c = self.peek_char()?;
+        self.rest = &self.rest[c.len_utf8()..];
+        Some(c)
+    }
+
+    pub fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let bytes = self.rest.as_bytes();
+        let mut end = 0;
+        let mut seen_dot = false;
+        let mut seen_exponent = false;
+        while end < bytes.len() {
+            match bytes[end] {
+                b'+' | b'-' if end == 0 || matches!(bytes[end - 1], b'e' | b'E') => {}
+                b'0'..=b'9' => {}
+                b'.' if !seen_dot && !seen_exponent => seen_dot = true,
+                b'e' | b'E' if !seen_exponent && end > 0 => seen_exponent = true,
+                _ => break,
+            }
+            end += 1;
+        }
+        let value = self.rest[..end].parse().ok()?;
+        self.rest = &self.rest[end..];
+        Some(value)
+    }
+
+    /// Arc flags are single digits that may be written without separators
+    pub fn flag(&mut self) -> Option<bool> {
+        match self.next_char()? {
+            '0' => Some(false),
+            '1' => Some(true),
+            _ => None,
+        }
+    }
+
+    pub fn point(&mut self) -> Option<Vec2> {
+        Some(Vec2::new(self.number()?, self.number()?))
+    }
+}
+
+/// Parses a length in user units, `px` is the only supported unit.
+pub(super) fn length(value: &str) -> Option<f32> {
+    value.trim().trim_end_matches("px").trim().parse().ok()
+}
+
+pub(super) fn points(value: &str) -> Vec<Vec2> {
+    let mut numbers = Numbers::new(value);
+    std::iter::from_fn(|| numbers.point()).collect()
+}
+
+/// `None` if the color is not understood, `Some(None)` for `none`.
+pub(super) fn color(value: &str) -> Option<Option<Srgba>> {
+    let value = value.trim();
+    if value == "none" || value == "transparent" {
+        return Some(None);
+    }
+    if value.starts_with('#') {
+        return Srgba::hex(value).ok().map(Some);
+    }
+    if let Some(arguments) = value
+        .strip_prefix("rgb(")
+        .or_else(|| value.strip_prefix("rgba("))
+        .and_then(|value| value.strip_suffix(')'))
+    {
+        let channels: Vec<f32> = arguments
+            .split([',', ' ', '/'])
+            .filter(|c| !c.is_empty())
+            .map(|c| match c.strip_suffix('%') {
+                Some(percent) => percent.parse::<f32>().map(|p| p * 2.55),
+                None => c.parse::<f32>(),
+            })
+            .collect::<Result<_, _>>()
+            .ok()?;
+        return match channels[..] {
+            [r, g, b] => Some(Some(Srgba::rgb_u8(r as u8, g as u8, b as u8))),
+            [r, g, b, a] => Some(Some(Srgba::rgb_u8(r as u8, g as u8, b as u8).with_alpha(a))),
+            _ => None,
+        };
+    }
+    let named = match value.to_ascii_lowercase().as_str() {
+        "black" => Srgba::BLACK,
+        "white" => Srgba::WHITE,
+        "red" => Srgba::RED,
+        "green" => Srgba::rgb_u8(0, 128, 0),
+        "lime" => Srgba::GREEN,
+        "blue" => Srgba::BLUE,
+        "yellow" => Srgba::rgb_u8(255, 255, 0),
+        "cyan" | "aqua" => Srgba::rgb_u8(0, 255, 255),
+        "magenta" | "fuchsia" => Srgba::rgb_u8(255, 0, 255),
+        "gray" | "grey" => Srgba::rgb_u8(128, 128, 128),
+        "orange" => Srgba::rgb_u8(255, 165, 0),
+        "purple" => Srgba::rgb_u8(128, 0, 128),
+        _ => return None,
+    };
+    Some(Some(named))
+}
+
+/// Parses the `transform` attribute.
+pub(super) fn transform(value: &str) -> Result<Affine2, String> {
+    let mut result = Affine2::IDENTITY;
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let (name, tail) = rest
+            .split_once('(')
+            .ok_or_else(|| format!("invalid transform `{value}`"))?;
+        let (arguments, tail) = tail
+            .split_once(')')
+            .ok_or_else(|| format!("invalid transform `{value}`"))?;
+        let mut numbers = Numbers::new(arguments);
+        let arguments: Vec<f32> = std::iter::from_fn(|| numbers.number()).collect();
+
+        let transform = match (name.trim(), arguments.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => {
+                Affine2::from_cols(Vec2::new(a, b), Vec2::new(c, d), Vec2::new(e, f))
+            }
+            ("translate", &[x]) => Affine2::from_translation(Vec2::new(x, 0.)),
+            ("translate", &[x, y]) => Affine2::from_translation(Vec2::new(x, y)),
+            ("scale", &[s]) => Affine2::from_scale(Vec2::splat(s)),
+            ("scale", &[x, y]) => Affine2::from_scale(Vec2::new(x, y)),
+            ("rotate", &[angle]) => Affine2::from_angle(angle.to_radians()),
+            ("rotate", &[angle, x, y]) => {
+                let center = Vec2::new(x, y);
+                Affine2::from_translation(center)
+                    * Affine2::from_angle(angle.to_radians())
+                    * Affine2::from_translation(-center)
+            }
+            ("skewX", &[angle]) => Affine2::from_mat2(Mat2::from_cols(
+                Vec2::X,
+                Vec2::new(angle.to_radians().tan(), 1.),
+            )),
+            ("skewY", &[angle]) => Affine2::from_mat2(Mat2::from_cols(
+                Vec2::new(1., angle.to_radians().tan()),
+                Vec2::Y,
+            )),
+            (name, _) => return Err(format!("invalid transform `{name}({arguments:?})`")),
+        };
+        result *= transform;
+        rest = tail.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    }
+    Ok(result)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgSegment {
+    Line(Vec2),
+    Quadratic(Vec2, Vec2),
+    Cubic(Vec2, Vec2, Vec2),
+}
+
+impl SvgSegment {
+    pub fn end(&self) -> Vec2 {
+        match *self {
+            SvgSegment::Line(end) | SvgSegment::Quadratic(_, end) | SvgSegment::Cubic(.., end) => {
+                end
+            }
+        }
+    }
+}
+
+/// A subpath of an SVG path, `closed` if it ends with `Z`.
+/// Fills treat every subpath as closed, only strokes follow open ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgSubpath {
+    pub start: Vec2,
+    pub segments: Vec<SvgSegment>,
+    pub closed: bool,
+}
+
+impl SvgSubpath {
+    /// Positive for counter clockwise subpaths, measured on the control polygon.
+    pub fn signed_area(&self) -> f32 {
+        let mut previous = self.start;
+        let mut area = 0.;
+        for segment in self.segments.iter().chain([&SvgSegment::Line(self.start)]) {
+            let end = segment.end();
+            area += previous.perp_dot(end);
+            previous = end;
+        }
+        area * 0.5
+    }
+
+    pub fn map(&mut self, func: impl Fn(Vec2) -> Vec2) {
+        self.start = func(self.start);
+        for segment in &mut self.segments {
+            match segment {
+                SvgSegment::Line(a) => *a = func(*a),
+                SvgSegment::Quadratic(a, b) => (*a, *b) = (func(*a), func(*b)),
+                SvgSegment::Cubic(a, b, c) => (*a, *b, *c) = (func(*a), func(*b), func(*c)),
+            }
+        }
+    }
+}
+
+/// Parses the `d` attribute of a path. Arcs are converted to cubic segments.
+pub(super) fn path(data: &str) -> Result<Vec<SvgSubpath>, String> {
+    let mut numbers = Numbers::new(data);
+    let mut subpaths: Vec<SvgSubpath> = Vec::new();
+    let mut current = Vec2::ZERO;
+    let mut start = Vec2::ZERO;
+    let mut command = None;
+    // Reflected control point for the smooth curve commands
+    let mut last_control: Option<(char, Vec2)> = None;
+    let invalid = || format!("invalid path data `{data}`");
+
+    while !numbers.is_empty() {
+        if let Some(c) = numbers.peek_char().filter(char::is_ascii_alphabetic) {
+            numbers.next_char();
+            command = Some(c);
+        }
+        let c = command.ok_or_else(invalid)?;
+        let relative = c.is_ascii_lowercase();
+        let offset = if relative { current } else { Vec2::ZERO };
+        let push = |subpaths: &mut Vec<SvgSubpath>, segment: SvgSegment| {
+            if let Some(subpath) = subpaths.last_mut() {
+                subpath.segments.push(segment);
+            }
+        };
+
+        match c.to_ascii_uppercase() {
+            'M' => {
+                current = numbers.point().ok_or_else(invalid)? + offset;
+                start = current;
+                subpaths.push(SvgSubpath {
+                    start,
+                    segments: Vec::new(),
+                    closed: false,
+                });
+                // Coordinates after a move are implicit lines
+                command = Some(if relative { 'l' } else { 'L' });
+            }
+            'L' => {
+                current = numbers.point().ok_or_else(invalid)? + offset;
+                push(&mut subpaths, SvgSegment::Line(current));
+            }
+            'H' => {
+                let x = numbers.number().ok_or_else(invalid)?;
+                current.x = if relative { current.x + x } else { x };
+                push(&mut subpaths, SvgSegment::Line(current));
+            }
+            'V' => {
+                let y = numbers.number().ok_or_else(invalid)?;
+                current.y = if relative { current.y + y } else { y };
+                push(&mut subpaths, SvgSegment::Line(current));
+            }
+            'C' | 'S' => {
+                let first = match c.to_ascii_uppercase() {
+                    'C' => numbers.point().ok_or_else(invalid)? + offset,
+                    _ => match last_control {
+                        Some(('C', control)) => current * 2. - control,
+                        _ => current,
+                    },
+                };
+                let second = numbers.point().ok_or_else(invalid)? + offset;
+                let end = numbers.point().ok_or_else(invalid)? + offset;
+                push(&mut subpaths, SvgSegment::Cubic(first, second, end));
+                last_control = Some(('C', second));
+                current = end;
+                continue;
+            }
+            'Q' | 'T' => {
+                let control = match c.to_ascii_uppercase() {
+                    'Q' => numbers.point().ok_or_else(invalid)? + offset,
+                    _ => match last_control {
+                        Some(('Q', control)) => current * 2. - control,
+                        _ => current,
+                    },
+                };
+                let end = numbers.point().ok_or_else(invalid)? + offset;
+                push(&mut subpaths, SvgSegment::Quadratic(control, end));
+                last_control = Some(('Q', control));
+                current = end;
+                continue;
+            }
+            'A' => {
+                let radii = numbers.point().ok_or_else(invalid)?;
+                let rotation = numbers.number().ok_or_else(invalid)?;
+                let large_arc = numbers.flag().ok_or_else(invalid)?;
+                let sweep = numbers.flag().ok_or_else(invalid)?;
+                let end = numbers.point().ok_or_else(invalid)? + offset;
+                for segment in arc(current, end, radii, rotation, large_arc, sweep) {
+                    push(&mut subpaths, segment);
+                }
+                current = end;
+            }
+            'Z' => {
+                if let Some(subpath) = subpaths.last_mut() {
+                    subpath.closed = true;
+                }
+                current = start;
+                command = None;
+            }
+            _ => return Err(format!("unknown path command `{c}`")),
+        }
+        last_control = None;
+    }
+
+    Ok(subpaths)
+}
+
+/// Converts an elliptical arc in endpoint parameterization to cubic segments,
+/// following the SVG implementation notes.
+fn arc(
+    from: Vec2,
+    to: Vec2,
+    radii: Vec2,
+    rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+) -> Vec<SvgSegment> {
+    let mut radii = radii.abs();
+    if from == to {
+        return Vec::new();
+    }
+    if radii.x == 0. || radii.y == 0. {
+        return vec![SvgSegment::Line(to)];
+    }
+
+    let rotation = Mat2::from_angle(rotation.to_radians());
+    let half = rotation.transpose() * ((from - to) * 0.5);
+    let lambda = (half / radii).length_squared();
+    if lambda > 1. {
+        radii *= lambda.sqrt();
+    }
+
+    let (rx2, ry2) = (radii.x * radii.x, radii.y * radii.y);
+    let numerator = rx2 * ry2 - rx2 * half.y * half.y - ry2 * half.x * half.x;
+    let denominator = rx2 * half.y * half.y + ry2 * half.x * half.x;
+    let sign = if large_arc == sweep { -1. } else { 1. };
+    let factor = sign * (numerator / denominator).max(0.).sqrt();
+    let center_prime = factor * Vec2::new(radii.x * half.y / radii.y, -radii.y * half.x / radii.x);
+    let center = rotation * center_prime + (from + to) * 0.5;
+
+    let start_vector = (half - center_prime) / radii;
+    let end_vector = (-half - center_prime) / radii;
+    let start_angle = Vec2::X.angle_to(start_vector);
+    let mut delta = start_vector.angle_to(end_vector);
+    if !sweep && delta > 0. {
+        delta -= 2. * PI;
+    } else if sweep && delta < 0. {
+        delta += 2. * PI;
+    }
+
+    let count = (delta.abs() / (PI * 0.5)).ceil().max(1.) as usize;
+    let step = delta / count as f32;
+    let handle = 4. / 3. * (step / 4.).tan();
+    let point = |angle: f32| center + rotation * (radii * Vec2::from_angle(angle));
+    let tangent = |angle: f32| rotation * (radii * Vec2::from_angle(angle).perp());
+
+    (0..count)
+        .map(|i| {
+            let a = start_angle + step * i as f32;
+            let b = a + step;
+            SvgSegment::Cubic(
+                point(a) + tangent(a) * handle,
+                point(b) - tangent(b) * handle,
+                if i == count - 1 { to } else { point(b) },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_without_separators() {
+        let mut numbers = Numbers::new("1.5.5-2e1,3 ");
+        let parsed: Vec<f32> = std::iter::from_fn(|| numbers.number()).collect();
+        assert_eq!(parsed, vec![1.5, 0.5, -20., 3.]);
+    }
+
+    #[test]
+    fn path_commands() {
+        let subpaths = path("M10 10h10v10H10zm5 5l1 1").unwrap();
+        assert_eq!(subpaths.len(), 2);
+        assert_eq!(subpaths[0].start, Vec2::new(10., 10.));
+        assert_eq!(
+            subpaths[0].segments,
+            vec![
+                SvgSegment::Line(Vec2::new(20., 10.)),
+                SvgSegment::Line(Vec2::new(20., 20.)),
+                SvgSegment::Line(Vec2::new(10., 20.)),
+            ]
+        );
+        // Relative move after closing starts at the start of the closed subpath
+        assert_eq!(subpaths[1].start, Vec2::new(15., 15.));
+        assert_eq!(
+            subpaths[1].segments,
+            vec![SvgSegment::Line(Vec2::new(16., 16.))]
+        );
+        assert!(subpaths[0].closed);
+        assert!(!subpaths[1].closed);
+    }
+
+    #[test]
+    fn arc_ends_on_circle() {
+        // Positive sweep turns clockwise on screen, where y points down
+        let subpaths = path("M0 0A5 5 0 1 1 10 0").unwrap();
+        let segments = &subpaths[0].segments;
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments.last().unwrap().end(), Vec2::new(10., 0.));
+        let SvgSegment::Cubic(.., middle) = segments[0] else {
+            panic!()
+        };
+        assert!((middle - Vec2::new(5., -5.)).length() < 1e-3);
+    }
+
+    #[test]
+    fn transforms_and_colors() {
+        let affine = transform("translate(10 20) scale(2)").unwrap();
+        assert_eq!(affine.transform_point2(Vec2::ONE), Vec2::new(12., 22.));
+        assert_eq!(color("none"), Some(None));
+        assert_eq!(color("#ff0000"), Some(Some(Srgba::RED)));
+        assert_eq!(color("rgb(0, 0, 255)"), Some(Some(Srgba::BLUE)));
+        assert_eq!(color("url(#gradient)"), None);
+    }
+}
@@ -0,0 +1,218 @@
+//! Text drawn with the glyphs of an [`SdfAtlas`], see [`SdfText`].
+
+use crate::atlas::{SdfAtlas, SdfTexture};
+use crate::{Sdf, Unioni};
+use bevy_app::{App, Update};
+use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_camera::visibility::Visibility;
+use bevy_color::Color;
+use bevy_ecs::entity::EntityHashSet;
+use bevy_ecs::prelude::*;
+use bevy_log::warn;
+use bevy_math::Vec2;
+use bevy_reflect::Reflect;
+use bevy_text::{
+    ComputedTextBlock, CosmicFontSystem, Font, Justify, LineBreak, TextBounds, TextError, TextFont,
+    TextPipeline,
+};
+use bevy_transform::prelude::Transform;
+use cuttle_core::prelude::Extends;
+use std::iter;
+
+pub(crate) fn plugin(app: &mut App) {
+    app.register_type::<SdfText>()
+        .add_systems(Update, layout_sdf_text);
+}
+
+/// Glyphs of one [`SdfText`]: the entity itself and up to 254 extensions.
+pub const MAX_TEXT_GLYPHS: usize = 255;
+
+/// Text drawn with the glyphs of an [`SdfAtlas`], laid out by bevy's text pipeline
+/// with the [`TextFont`] of the entity, which has to be the font the atlas was generated from.
+///
+/// The text is one shape: the first glyph is the base shape of the entity, the others are
+/// spawned as children extending it with a union. Fills, strokes, effects and operations of
+/// the entity apply to the whole text. As every glyph is evaluated across the bounds of the
+/// text, this suits labels and other short texts, glyphs beyond [`MAX_TEXT_GLYPHS`] are dropped.
+///
+/// Like `Text2d`, the text is centered on the entity, with `font_size` in world units.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[require(Sdf, TextFont, Transform, Visibility)]
+#[reflect(Component)]
+pub struct SdfText {
+    pub text: String,
+    pub atlas: Handle<SdfAtlas>,
+    pub justify: Justify,
+    pub linebreak: LineBreak,
+    /// Width at which lines break, unbounded if `None`
+    pub max_width: Option<f32>,
+}
+
+impl SdfText {
+    pub fn new(text: impl Into<String>, atlas: Handle<SdfAtlas>) -> Self {
+        Self {
+            text: text.into(),
+            atlas,
+            ..Default::default()
+        }
+    }
+}
+
+/// The extensions drawing all glyphs after the first.
+#[derive(Component, Default)]
+struct SdfTextGlyphs(Vec<Entity>);
+
+fn layout_sdf_text(
+    mut cmds: Commands,
+    texts: Query<(Entity, Ref<SdfText>, Ref<TextFont>, Option<&SdfTextGlyphs>)>,
+    mut atlas_messages: MessageReader<AssetEvent<SdfAtlas>>,
+    mut font_messages: MessageReader<AssetEvent<Font>>,
+    atlases: Res<Assets<SdfAtlas>>,
+    fonts: Res<Assets<Font>>,
+    mut pipeline: ResMut<TextPipeline>,
+    mut font_system: ResMut<CosmicFontSystem>,
+    mut computed: Local<ComputedTextBlock>,
+    mut pending: Local<EntityHashSet>,
+) {
+    let assets_changed = atlas_messages.read().count() + font_messages.read().count() > 0;
+
+    for (entity, text, font, glyphs) in &texts {
+        let was_pending = pending.remove(&entity);
+        if !(text.is_changed() || font.is_changed() || assets_changed || was_pending) {
+            continue;
+        }
+        let Some(atlas) = atlases.get(&text.atlas) else {
+            pending.insert(entity);
+            continue;
+        };
+
+        let result = pipeline.update_buffer(
+            &fonts,
+            iter::once((entity, 0, text.text.as_str(), &*font, Color::WHITE)),
+            text.linebreak,
+            text.justify,
+            TextBounds {
+                width: text.max_width,
+                height: None,
+            },
+            1.,
+            &mut computed,
+            &mut font_system,
+        );
+        match result {
+            Ok(()) => {}
+            Err(TextError::NoSuchFont) => {
+                pending.insert(entity);
+                continue;
+            }
+            Err(err) => {
+                warn!("Failed to lay out sdf text {entity}: {err}");
+                continue;
+            }
+        }
+
+        let mut shapes = Vec::new();
+        let mut size = Vec2::ZERO;
+        for run in computed.buffer().layout_runs() {
+            size = size.max(Vec2::new(run.line_w, run.line_top + run.line_height));
+            shapes.extend(run.glyphs.iter().filter_map(|glyph| {
+                let character = run.text[glyph.start..glyph.end].chars().next()?;
+                let origin = Vec2::new(glyph.x, -run.line_y);
+                atlas.glyph(character, origin, glyph.font_size)
+            }));
+        }
+        if shapes.len() > MAX_TEXT_GLYPHS {
+            warn!(
+                "Sdf text {entity} has {} glyphs, only the first {MAX_TEXT_GLYPHS} are drawn",
+                shapes.len()
+            );
+            shapes.truncate(MAX_TEXT_GLYPHS);
+        }
+        let offset = Vec2::new(-size.x, size.y) * 0.5;
+        for shape in &mut shapes {
+            shape.center += offset;
+        }
+
+        let mut shapes = shapes.into_iter();
+        match shapes.next() {
+            Some(first) => cmds.entity(entity).insert(first),
+            None => cmds.entity(entity).remove::<SdfTexture>(),
+        };
+        let existing = glyphs.map(|glyphs| glyphs.0.as_slice()).unwrap_or_default();
+        update_glyph_extensions(&mut cmds, entity, existing, shapes);
+    }
+}
+
+/// Reuses the existing extensions in order, so their extension indices stay in place.
+fn update_glyph_extensions(
+    cmds: &mut Commands,
+    entity: Entity,
+    existing: &[Entity],
+    shapes: impl Iterator<Item = SdfTexture>,
+) {
+    let mut updated = Vec::new();
+    for (i, shape) in shapes.enumerate() {
+        match existing.get(i) {
+            Some(&glyph) => {
+                cmds.entity(glyph).insert(shape);
+                updated.push(glyph);
+            }
+            None => updated.push(
+                cmds.spawn((
+                    Extends(entity),
+                    ChildOf(entity),
+                    Transform::default(),
+                    Unioni,
+                    shape,
+                ))
+                .id(),
+            ),
+        }
+    }
+    for &glyph in existing.iter().skip(updated.len()) {
+        cmds.entity(glyph).try_despawn();
+    }
+    cmds.entity(entity).insert(SdfTextGlyphs(updated));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SdfTextGlyphs, update_glyph_extensions};
+    use crate::atlas::{SdfTexture, SdfTextureKind};
+    use bevy_asset::Handle;
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::Vec2;
+
+    fn layout(world: &mut World, root: Entity, glyphs: usize) -> Vec<Entity> {
+        world
+            .run_system_once(move |mut cmds: Commands, existing: Query<&SdfTextGlyphs>| {
+                let existing = existing.get(root).map(|g| g.0.clone()).unwrap_or_default();
+                let shapes = (0..glyphs).map(|i| SdfTexture {
+                    center: Vec2::X * i as f32,
+                    ..SdfTexture::new(Handle::default(), SdfTextureKind::Sdf, 1., Vec2::ONE)
+                });
+                update_glyph_extensions(&mut cmds, root, &existing, shapes);
+            })
+            .unwrap();
+        world.get::<SdfTextGlyphs>(root).unwrap().0.clone()
+    }
+
+    #[test]
+    fn shortened_text_despawns_trailing_glyphs() {
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+
+        let glyphs = layout(&mut world, root, 3);
+        assert_eq!(glyphs.len(), 3);
+
+        // The remaining glyph keeps its entity, and with it its extension index
+        assert_eq!(layout(&mut world, root, 1), glyphs[..1]);
+        assert_eq!(
+            world.get::<SdfTexture>(glyphs[0]).unwrap().center,
+            Vec2::ZERO
+        );
+        assert!(world.get_entity(glyphs[1]).is_err());
+        assert!(world.get_entity(glyphs[2]).is_err());
+    }
+}
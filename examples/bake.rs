@@ -0,0 +1,52 @@
+use bevy::{color::palettes::css, prelude::*};
+use cuttle::prelude::*;
+use cuttle_sdf::bake::{BakeSettings, bake_image};
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .add_systems(Last, bake_on_cpu.run_if(run_once))
+        .run();
+}
+
+#[derive(Component)]
+struct Baked;
+
+/// The shape on the left, rendered into the image of the sprite in the middle.
+fn spawn(mut cmds: Commands, mut images: ResMut<Assets<Image>>) {
+    cmds.spawn(Camera2d);
+    let image = images.add(RenderToImage::target_image(UVec2::splat(128)));
+
+    cmds.spawn((
+        Sdf,
+        Baked,
+        Transform::from_xyz(-250., 0., 0.),
+        Star {
+            radius: 100.,
+            points: 6,
+            sharpness: 2.,
+        },
+        Fill(css::ORANGE),
+        Stroke::new(css::DARK_RED, 8.),
+        RenderToImage::new(image.clone()),
+    ));
+    cmds.spawn(Sprite::from_image(image));
+}
+
+/// The same shape rasterized on the CPU on the right, once its bounds are known.
+fn bake_on_cpu(world: &mut World) {
+    let Ok(shape) = world.query_filtered::<Entity, With<Baked>>().single(world) else {
+        return;
+    };
+    let settings = BakeSettings {
+        size: UVec2::splat(128),
+        samples: 4,
+        ..default()
+    };
+    let Some(image) = bake_image(world, shape, &settings) else {
+        return;
+    };
+    let image = world.resource_mut::<Assets<Image>>().add(image);
+    world.spawn((Sprite::from_image(image), Transform::from_xyz(250., 0., 0.)));
+}
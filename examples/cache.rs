@@ -0,0 +1,64 @@
+use bevy::{color::palettes::tailwind, prelude::*};
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .add_systems(Update, zoom)
+        .run();
+}
+
+/// A grid of flowers, each a smooth union of many circles, drawn from their cached images.
+/// Zooming with the arrow keys renders them again once the scale changed enough.
+fn spawn(mut cmds: Commands) {
+    cmds.spawn(Camera2d);
+
+    let colors = [
+        tailwind::RED_400,
+        tailwind::AMBER_400,
+        tailwind::EMERALD_400,
+        tailwind::SKY_400,
+        tailwind::FUCHSIA_400,
+    ];
+    for x in -4..=4 {
+        for y in -2..=2 {
+            let color = colors[(x + y).rem_euclid(colors.len() as i32) as usize];
+            let center = Vec2::new(x as f32, y as f32) * 140.;
+            let flower = cmds
+                .spawn((
+                    Sdf,
+                    Transform::from_translation(center.extend(0.)),
+                    Circle(20.),
+                    Fill(tailwind::YELLOW_200),
+                    Stroke::new(tailwind::GRAY_800, 3.),
+                    CuttleCache::default(),
+                ))
+                .id();
+
+            for i in 0..11 {
+                let angle = i as f32 / 11. * std::f32::consts::TAU;
+                let offset = Vec2::from_angle(angle) * 40.;
+                cmds.spawn((
+                    Extends(flower),
+                    Transform::from_translation((center + offset).extend(0.)),
+                    Circle(16.),
+                    Fill(color),
+                    SmoothUnion::default(),
+                ));
+            }
+        }
+    }
+}
+
+fn zoom(mut projection: Single<&mut Projection, With<Camera2d>>, keys: Res<ButtonInput<KeyCode>>) {
+    let Projection::Orthographic(ortho) = projection.as_mut() else {
+        return;
+    };
+    if keys.pressed(KeyCode::ArrowUp) {
+        ortho.scale *= 0.98;
+    }
+    if keys.pressed(KeyCode::ArrowDown) {
+        ortho.scale *= 1.02;
+    }
+}
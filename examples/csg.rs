@@ -0,0 +1,41 @@
+use bevy::{color::palettes::tailwind, prelude::*};
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .add_systems(Update, move_group)
+        .run();
+}
+
+#[derive(Component)]
+struct Moving;
+
+/// `(A ∪ B) − (C ∩ D)` with smooth operations, the nested `C ∩ D` moving back and forth.
+fn spawn(mut cmds: Commands) {
+    cmds.spawn(Camera2d);
+
+    let parts = cmds
+        .spawn_shape(Circle(100.))
+        .at(Vec2::new(-60., 0.))
+        .fill(tailwind::SKY_400)
+        .smooth_union(20., Circle(100.))
+        .at(Vec2::new(60., 0.))
+        .fill(tailwind::INDIGO_400)
+        .begin_group(Quad(Vec2::splat(60.)))
+        .smooth_intersect(10., Circle(70.))
+        .at(Vec2::new(40., 0.))
+        .end_group(SmoothSubtract(10.))
+        .build();
+
+    cmds.entity(parts.extensions[1]).insert(Moving);
+    cmds.entity(parts.extensions[2]).insert(Moving);
+}
+
+fn move_group(mut query: Query<(&mut Transform, Has<Circle>), With<Moving>>, time: Res<Time>) {
+    let x = time.elapsed_secs().sin() * 120.;
+    for (mut transform, circle) in &mut query {
+        transform.translation.x = x + if circle { 40. } else { 0. };
+    }
+}
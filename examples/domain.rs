@@ -0,0 +1,105 @@
+use bevy::{color::palettes::tailwind, prelude::*};
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .add_systems(Update, animate)
+        .run();
+}
+
+#[derive(Component)]
+struct Animated;
+
+fn spawn(mut cmds: Commands) {
+    cmds.spawn(Camera2d);
+
+    cmds.spawn((
+        Sdf,
+        Transform::from_xyz(-400., 150., 0.),
+        Mirror(Vec2::X),
+        Capsule {
+            a: Vec2::new(10., -50.),
+            b: Vec2::new(60., 50.),
+            radius: 12.,
+        },
+        Fill(tailwind::RED_400),
+    ));
+
+    cmds.spawn((
+        Sdf,
+        Transform::from_xyz(-130., 150., 0.),
+        PolarRepetition {
+            count: 8,
+            radius: 70.,
+        },
+        Circle(18.),
+        Fill(tailwind::AMBER_400),
+    ));
+
+    cmds.spawn((
+        Sdf,
+        Transform::from_xyz(130., 150., 0.),
+        ClippedRepetition {
+            spacing: Vec2::splat(40.),
+            copies: Vec2::new(2., 1.),
+        },
+        Quad(Vec2::splat(12.)),
+        Rounded(4.),
+        Fill(tailwind::EMERALD_400),
+    ));
+
+    cmds.spawn((
+        Sdf,
+        Transform::from_xyz(400., 150., 0.),
+        Twist(0.02),
+        Quad(Vec2::new(90., 15.)),
+        Fill(tailwind::SKY_400),
+        Animated,
+    ));
+
+    cmds.spawn((
+        Sdf,
+        Transform::from_xyz(-270., -150., 0.),
+        Bend(0.01),
+        Quad(Vec2::new(100., 10.)),
+        Fill(tailwind::VIOLET_400),
+        Animated,
+    ));
+
+    cmds.spawn((
+        Sdf,
+        Transform::from_xyz(0., -150., 0.),
+        Circle(40.),
+        Onion::default(),
+        Fill(tailwind::PINK_400),
+    ));
+
+    cmds.spawn((
+        Sdf,
+        Transform::from_xyz(270., -150., 0.),
+        Circle(70.),
+        NoiseDisplacement {
+            amplitude: 8.,
+            frequency: 0.05,
+            seed: 3,
+        },
+        Fill(tailwind::LIME_400),
+    ));
+}
+
+/// Swings the twist and bend back and forth.
+fn animate(
+    mut twists: Query<&mut Twist, With<Animated>>,
+    mut bends: Query<&mut Bend, With<Animated>>,
+    time: Res<Time>,
+) {
+    let swing = time.elapsed_secs().sin();
+    for mut twist in &mut twists {
+        twist.0 = 0.02 * swing;
+    }
+    for mut bend in &mut bends {
+        bend.0 = 0.01 * swing;
+    }
+}
@@ -0,0 +1,38 @@
+use bevy::{color::palettes::css, prelude::*};
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, |mut cmds: Commands| {
+            cmds.spawn(Camera2d);
+        })
+        .add_systems(Update, draw)
+        .run();
+}
+
+fn draw(mut draw: CuttleDraw, time: Res<Time>) {
+    let t = time.elapsed_secs();
+
+    for i in 0..12 {
+        let angle = t + i as f32 * std::f32::consts::TAU / 12.;
+        draw.circle(Vec2::from_angle(angle) * 250., 15., css::SKY_BLUE);
+    }
+
+    draw.line(
+        Vec2::new(-300., -300.),
+        Vec2::new(300., -300.),
+        6.,
+        css::WHITE,
+    );
+
+    draw.shape()
+        .circle(80.)
+        .fill(css::ORANGE)
+        .smooth_union(30., |part| {
+            part.quad(Vec2::splat(40.))
+                .at(Vec2::new(t.sin() * 120., 0.))
+                .rotated(t)
+        })
+        .subtract(|part| part.circle(30.).at(Vec2::new(0., t.cos() * 60.)));
+}
@@ -0,0 +1,50 @@
+use bevy::{color::palettes::css, prelude::*};
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin, effects))
+        .add_systems(Startup, spawn)
+        .add_systems(Update, wobble)
+        .run();
+}
+
+fn effects(app: &mut App) {
+    app.cuttle_config::<Sdf>()
+        .dynamic_components_file("effects.ron");
+}
+
+fn spawn(mut cmds: Commands) {
+    cmds.spawn(Camera2d);
+    cmds.spawn((
+        Sdf,
+        Circle(150.),
+        Fill(css::SKY_BLUE),
+        Transform::from_xyz(-200., 0., 0.),
+        DynamicCuttleComponent::new(
+            "Wobble",
+            [("amplitude", 20f32.into()), ("frequency", 0.05f32.into())],
+        ),
+    ));
+    cmds.spawn((
+        Sdf,
+        Quad(Vec2::splat(120.)),
+        Fill(css::ORANGE),
+        Transform::from_xyz(200., 0., 0.),
+        DynamicCuttleComponent::new(
+            "Stripes",
+            [
+                ("width", 30f32.into()),
+                ("color", Vec4::new(0.6, 0.1, 0.1, 1.).into()),
+            ],
+        ),
+    ));
+}
+
+fn wobble(time: Res<Time>, mut query: Query<&mut DynamicCuttleComponent>) {
+    for mut component in &mut query {
+        if component.contains("Wobble") {
+            component.set("Wobble", "amplitude", time.elapsed_secs().sin() * 20.);
+        }
+    }
+}
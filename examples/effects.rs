@@ -0,0 +1,48 @@
+use bevy::{color::palettes::css, prelude::*};
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(css::LIGHT_GRAY.into()))
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .run();
+}
+
+fn spawn(mut cmds: Commands) {
+    cmds.spawn(Camera2d);
+
+    cmds.spawn((
+        Sdf,
+        Quad(Vec2::splat(70.)),
+        Rounded(20.),
+        Fill(css::WHITE),
+        DropShadow {
+            offset: Vec2::new(10., -15.),
+            blur: 15.,
+            color: css::BLACK.with_alpha(0.4),
+        },
+        Transform::from_xyz(-250., 0., 0.),
+    ));
+
+    cmds.spawn((
+        Sdf,
+        Circle(70.),
+        Fill(css::DEEP_SKY_BLUE),
+        OuterGlow {
+            radius: 40.,
+            color: css::AQUA,
+        },
+        Transform::default(),
+    ));
+
+    cmds.spawn_shape(Circle(70.))
+        .fill(css::SILVER)
+        .insert(InnerShadow {
+            offset: Vec2::new(6., -6.),
+            blur: 8.,
+            color: css::DIM_GRAY,
+        })
+        .at(Vec2::new(250., 0.))
+        .subtract(Quad(Vec2::splat(30.)));
+}
@@ -0,0 +1,42 @@
+use bevy::{color::palettes::css, prelude::*};
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .run();
+}
+
+fn spawn(mut cmds: Commands) {
+    cmds.spawn(Camera2d);
+
+    let stops = || GradientStop::evenly([css::GOLD, css::TOMATO, css::MEDIUM_PURPLE]);
+
+    cmds.spawn((
+        Sdf,
+        Quad(Vec2::splat(100.)),
+        Rounded(20.),
+        Gradient::linear(Vec2::new(-100., -100.), Vec2::new(100., 100.), stops()),
+        Transform::from_xyz(-300., 0., 0.),
+    ));
+    cmds.spawn((
+        Sdf,
+        Circle(120.),
+        Gradient::radial(Vec2::ZERO, 120., stops())
+            .with_interpolation(GradientInterpolation::Oklab),
+        Transform::default(),
+    ));
+    cmds.spawn((
+        Sdf,
+        Circle(120.),
+        Annular(30.),
+        Gradient::conic(
+            Vec2::ZERO,
+            0.,
+            GradientStop::evenly([css::BLUE, css::YELLOW, css::BLUE]),
+        )
+        .with_interpolation(GradientInterpolation::Oklab),
+        Transform::from_xyz(300., 0., 0.),
+    ));
+}
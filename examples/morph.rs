@@ -0,0 +1,77 @@
+use bevy::animation::{AnimationTarget, AnimationTargetId};
+use bevy::{color::palettes::tailwind, prelude::*};
+use cuttle::prelude::*;
+use cuttle_sdf::morph::MorphWeightProperty;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .run();
+}
+
+/// A circle morphing into a star and a heart and back, with keyframed weights.
+fn spawn(
+    mut cmds: Commands,
+    mut clips: ResMut<Assets<AnimationClip>>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+) {
+    cmds.spawn(Camera2d);
+
+    let parts = cmds
+        .spawn_shape(Circle(100.))
+        .fill(tailwind::SKY_400)
+        .morph(MorphWeights::new([1., 0., 0.]))
+        .insert(MorphStroke(Stroke::new(tailwind::SKY_800, 4.)))
+        .morph_target(Star {
+            radius: 130.,
+            points: 5,
+            sharpness: 2.5,
+        })
+        .fill(tailwind::AMBER_400)
+        .insert(MorphStroke(
+            Stroke::new(tailwind::AMBER_800, 10.).dashed(12., 6.),
+        ))
+        .morph_target(Heart(120.))
+        .insert((
+            Gradient::linear(
+                Vec2::new(0., -120.),
+                Vec2::new(0., 120.),
+                GradientStop::evenly([tailwind::PINK_600, tailwind::ROSE_300]),
+            ),
+            MorphStroke(Stroke::new(tailwind::ROSE_900, 6.)),
+        ))
+        .build();
+
+    let name = Name::new("morph");
+    let target = AnimationTargetId::from_name(&name);
+    let mut clip = AnimationClip::default();
+    let keyframes = [
+        [(0., 1.), (1., 0.), (2., 0.), (3., 1.)],
+        [(0., 0.), (1., 1.), (2., 0.), (3., 0.)],
+        [(0., 0.), (1., 0.), (2., 1.), (3., 0.)],
+    ];
+    for (index, keyframes) in keyframes.into_iter().enumerate() {
+        clip.add_curve_to_target(
+            target,
+            AnimatableCurve::new(
+                MorphWeightProperty::new(index),
+                AnimatableKeyframeCurve::new(keyframes).expect("at least two keyframes"),
+            ),
+        );
+    }
+
+    let (graph, animation) = AnimationGraph::from_clip(clips.add(clip));
+    let mut player = AnimationPlayer::default();
+    player.play(animation).repeat();
+
+    cmds.entity(parts.root).insert((
+        name,
+        AnimationGraphHandle(graphs.add(graph)),
+        player,
+        AnimationTarget {
+            id: target,
+            player: parts.root,
+        },
+    ));
+}
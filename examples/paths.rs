@@ -0,0 +1,85 @@
+use bevy::{color::palettes::css, prelude::*};
+use cuttle::prelude::*;
+use std::f32::consts::TAU;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .add_systems(Update, wave)
+        .run();
+}
+
+fn spawn(mut cmds: Commands) {
+    cmds.spawn(Camera2d);
+
+    let arrow = [
+        (-80., -20.),
+        (20., -20.),
+        (20., -60.),
+        (90., 0.),
+        (20., 60.),
+        (20., 20.),
+        (-80., 20.),
+    ];
+    cmds.spawn((
+        Sdf,
+        Polygon(arrow.map(Vec2::from).to_vec()),
+        Rounded(5.),
+        Fill(css::ORANGE),
+        Transform::from_xyz(-250., 150., 0.),
+    ));
+
+    let heart = [
+        (0., -70.),
+        (-40., -40.),
+        (-100., 0.),
+        (-60., 60.),
+        (-20., 100.),
+        (0., 40.),
+        (0., 40.),
+        (20., 100.),
+        (60., 60.),
+        (100., 0.),
+        (40., -40.),
+        (0., -70.),
+    ];
+    cmds.spawn((
+        Sdf,
+        BezierPath::cubic(heart.map(Vec2::from)).closed(),
+        Fill(css::CRIMSON),
+        Stroke::new(css::DARK_RED, 6.),
+        Transform::from_xyz(250., 150., 0.),
+    ));
+
+    cmds.spawn((
+        Sdf,
+        BezierPath::quadratic(
+            [(-80., 0.), (-40., 80.), (0., 0.), (40., -80.), (80., 0.)].map(Vec2::from),
+        ),
+        Rounded(4.),
+        Fill(css::SKY_BLUE),
+        Transform::from_xyz(0., 150., 0.),
+    ));
+
+    cmds.spawn((
+        Sdf,
+        Polyline::new([], 8.),
+        Fill(css::LIME),
+        Transform::from_xyz(0., -150., 0.),
+    ));
+}
+
+/// The number of points changes over time, moving the line within the shared points buffer
+fn wave(mut lines: Query<&mut Polyline>, time: Res<Time>) {
+    let t = time.elapsed_secs();
+    let count = 8 + ((t * 0.5).sin().abs() * 40.) as usize;
+    for mut line in &mut lines {
+        line.points = (0..count)
+            .map(|i| {
+                let x = i as f32 / (count - 1) as f32;
+                Vec2::new(x * 600. - 300., (x * TAU * 2. + t * 3.).sin() * 60.)
+            })
+            .collect();
+    }
+}
@@ -0,0 +1,53 @@
+use bevy::{color::palettes::css, prelude::*};
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin, SdfPickingPlugin::default()))
+        .add_systems(Startup, spawn)
+        .run();
+}
+
+fn spawn(mut cmds: Commands) {
+    cmds.spawn(Camera2d);
+
+    // Only the ring is hit, not the hole in its middle
+    cmds.spawn((
+        Sdf,
+        Transform::from_xyz(-150., 0., 0.),
+        Circle(100.),
+        Annular(20.),
+        Fill(css::SKY_BLUE),
+    ))
+    .observe(highlight::<Pointer<Over>>(css::ORANGE))
+    .observe(highlight::<Pointer<Out>>(css::SKY_BLUE));
+
+    // Extensions and operations are part of the hit test
+    let button = cmds
+        .spawn((
+            Sdf,
+            Transform::from_xyz(150., 0., 0.),
+            Quad(Vec2::new(80., 40.)),
+            Rounded(10.),
+            Fill(css::REBECCA_PURPLE),
+        ))
+        .observe(highlight::<Pointer<Over>>(css::ORANGE))
+        .observe(highlight::<Pointer<Out>>(css::REBECCA_PURPLE))
+        .observe(|click: On<Pointer<Click>>| info!("Clicked {}", click.entity))
+        .id();
+
+    cmds.spawn((
+        Extends(button),
+        Transform::from_xyz(150., 0., 0.),
+        Circle(25.),
+        Subtract,
+    ));
+}
+
+fn highlight<E: EntityEvent>(color: Srgba) -> impl Fn(On<E>, Query<&mut Fill>) {
+    move |event, mut fills| {
+        if let Ok(mut fill) = fills.get_mut(event.event_target()) {
+            fill.0 = color;
+        }
+    }
+}
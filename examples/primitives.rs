@@ -0,0 +1,116 @@
+use bevy::{color::palettes::css, prelude::*};
+use cuttle::prelude::*;
+use std::f32::consts::PI;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .run();
+}
+
+fn spawn(mut cmds: Commands) {
+    cmds.spawn(Camera2d);
+
+    spawn(
+        &mut cmds,
+        0,
+        "Ellipse",
+        Ellipse(Vec2::new(70., 40.)),
+        css::TOMATO,
+    );
+    spawn(
+        &mut cmds,
+        1,
+        "Equilateral Triangle",
+        EquilateralTriangle(60.),
+        css::GOLD,
+    );
+    let triangle = Triangle {
+        a: Vec2::new(-60., -50.),
+        b: Vec2::new(70., -30.),
+        c: Vec2::new(-10., 60.),
+    };
+    spawn_cell(&mut cmds, 2, "Triangle", triangle, css::YELLOW_GREEN);
+    let hexagon = Ngon {
+        radius: 60.,
+        sides: 6,
+    };
+    spawn_cell(&mut cmds, 3, "Hexagon", hexagon, css::SEA_GREEN);
+    let star = Star {
+        radius: 70.,
+        points: 5,
+        sharpness: 3.,
+    };
+    spawn_cell(&mut cmds, 4, "Star", star, css::ORANGE);
+    let arc = Arc {
+        aperture: PI * 0.7,
+        radius: 50.,
+        thickness: 10.,
+    };
+    spawn_cell(&mut cmds, 5, "Arc", arc, css::SKY_BLUE);
+    let pie = Pie {
+        aperture: PI * 0.35,
+        radius: 65.,
+    };
+    spawn_cell(&mut cmds, 6, "Pie", pie, css::PLUM);
+    let capsule = Capsule {
+        a: Vec2::new(-50., -30.),
+        b: Vec2::new(50., 30.),
+        radius: 20.,
+    };
+    spawn_cell(&mut cmds, 7, "Capsule", capsule, css::STEEL_BLUE);
+    let rounded = RoundedBox {
+        half_size: Vec2::new(70., 50.),
+        radii: Vec4::new(40., 0., 10., 25.),
+    };
+    spawn_cell(&mut cmds, 8, "Rounded Box", rounded, css::TURQUOISE);
+    spawn(
+        &mut cmds,
+        9,
+        "Rhombus",
+        Rhombus(Vec2::new(70., 45.)),
+        css::SALMON,
+    );
+    let trapezoid = Trapezoid {
+        bottom: 70.,
+        top: 35.,
+        half_height: 45.,
+    };
+    spawn_cell(&mut cmds, 10, "Trapezoid", trapezoid, css::KHAKI);
+    spawn_cell(&mut cmds, 11, "Heart", Heart(120.), css::CRIMSON);
+    spawn(
+        &mut cmds,
+        12,
+        "Cross",
+        Cross(Vec2::new(65., 20.)),
+        css::WHITE,
+    );
+    let vesica = Vesica {
+        radius: 70.,
+        offset: 40.,
+    };
+    spawn_cell(&mut cmds, 13, "Vesica", vesica, css::LIGHT_GREEN);
+    let horseshoe = Horseshoe {
+        aperture: PI * 0.75,
+        radius: 40.,
+        length: 20.,
+        thickness: 10.,
+    };
+    spawn_cell(&mut cmds, 14, "Horseshoe", horseshoe, css::SILVER);
+}
+
+/// Spawns the shape in a grid cell, five cells per row
+fn spawn_cell(cmds: &mut Commands, cell: usize, name: &str, shape: impl Bundle, color: Srgba) {
+    let position = Vec2::new(
+        (cell % 5) as f32 * 200. - 400.,
+        200. - (cell / 5) as f32 * 200.,
+    );
+    cmds.spawn((
+        Name::new(name.to_string()),
+        Sdf,
+        shape,
+        Fill(color),
+        Transform::from_translation(position.extend(0.)),
+    ));
+}
@@ -0,0 +1,23 @@
+//! Shapes authored in `.cuttle` files, edit `assets/keyhole.cuttle` while this runs
+//! (with bevy's `file_watcher` feature) to see changes applied to every instance.
+use bevy::prelude::*;
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .run();
+}
+
+fn spawn(mut cmds: Commands, assets: Res<AssetServer>) {
+    cmds.spawn(Camera2d);
+
+    let keyhole = assets.load("keyhole.cuttle");
+    for x in [-200., 0., 200.] {
+        cmds.spawn((
+            CuttleShapeRoot(keyhole.clone()),
+            Transform::from_xyz(x, 0., 0.),
+        ));
+    }
+}
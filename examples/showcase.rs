@@ -261,30 +261,18 @@ fn spin<OP: Default + Component>(
 fn box_op_circle<O: Default + Component>(cmds: &mut Commands, pos: impl Into<Vec2>) {
     let pos = pos.into();
 
-    let sdf = cmds
-        .spawn((
-            Sdf,
-            Transform::from_xyz(pos.x, pos.y, 0.),
-            Circle(30.),
-            Fill(tailwind::SKY_400),
-            // Gradient {
-            //     color: tailwind::NEUTRAL_200.into(),
-            //     interval: 1.,
-            // },
-        ))
-        .id();
-    cmds.spawn((
-        Extends(sdf),
-        Transform::from_xyz(pos.x, pos.y, 0.),
-        Quad(Vec2::splat(25.)),
-        Fill(tailwind::FUCHSIA_400),
-        O::default(),
-        MovingBox,
-        DistanceGradient {
-            interval: 1.,
-            color: Vec4::ZERO,
-        },
-    ));
+    cmds.spawn_shape(Circle(30.))
+        .fill(tailwind::SKY_400)
+        .at(pos)
+        .operation(O::default(), Quad(Vec2::splat(25.)))
+        .fill(tailwind::FUCHSIA_400)
+        .insert((
+            MovingBox,
+            DistanceGradient {
+                interval: 1.,
+                color: Vec4::ZERO,
+            },
+        ));
 }
 
 #[derive(Component)]
@@ -0,0 +1,38 @@
+use bevy::{color::palettes::css, prelude::*};
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .run();
+}
+
+fn spawn(mut cmds: Commands) {
+    cmds.spawn(Camera2d);
+
+    for (i, alignment) in [
+        StrokeAlignment::Inside,
+        StrokeAlignment::Center,
+        StrokeAlignment::Outside,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        cmds.spawn((
+            Sdf,
+            Quad(Vec2::splat(60.)),
+            Rounded(15.),
+            Fill(css::SKY_BLUE),
+            Stroke::new(css::NAVY, 12.).aligned(alignment),
+            Transform::from_xyz(-250. + i as f32 * 250., 120., 0.),
+        ));
+    }
+
+    cmds.spawn_shape(Circle(80.))
+        .fill(css::GOLD)
+        .insert(Stroke::new(css::DARK_RED, 6.).dashed(20., 10.))
+        .at(Vec2::new(-120., -150.))
+        .smooth_union(20., Circle(50.))
+        .at(Vec2::new(90., 0.));
+}
@@ -0,0 +1,23 @@
+use bevy::prelude::*;
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .run();
+}
+
+fn spawn(mut cmds: Commands, assets: Res<AssetServer>) {
+    cmds.spawn(Camera2d);
+
+    let badge = assets.load("badge.svg");
+    cmds.spawn((
+        svg::SvgShapesRoot(badge.clone()),
+        Transform::from_xyz(-150., 0., 0.),
+    ));
+    cmds.spawn((
+        svg::SvgShapesRoot(badge),
+        Transform::from_xyz(150., 0., 0.).with_scale(Vec3::splat(1.5)),
+    ));
+}
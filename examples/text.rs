@@ -0,0 +1,60 @@
+//! Generate the atlas with [msdf-atlas-gen](https://github.com/Chlumsky/msdf-atlas-gen):
+//!
+//! ```sh
+//! msdf-atlas-gen -font assets/fonts/FiraSans-Bold.ttf -type msdf -pxrange 6 \
+//!     -imageout assets/fonts/FiraSans-Bold.png -json assets/fonts/FiraSans-Bold.sdfatlas.json
+//! ```
+
+use bevy::{color::palettes::css, prelude::*};
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin))
+        .add_systems(Startup, spawn)
+        .add_systems(Update, count)
+        .run();
+}
+
+fn spawn(mut cmds: Commands, assets: Res<AssetServer>) {
+    cmds.spawn(Camera2d);
+    let font = TextFont {
+        font: assets.load("fonts/FiraSans-Bold.ttf"),
+        font_size: 120.,
+        ..default()
+    };
+    let atlas = assets.load("fonts/FiraSans-Bold.sdfatlas.json");
+
+    cmds.spawn((
+        SdfText::new("Cuttle", atlas.clone()),
+        font.clone(),
+        Fill(css::GOLD),
+        Stroke::new(css::DARK_RED, 6.),
+        DropShadow {
+            offset: Vec2::new(8., -8.),
+            blur: 6.,
+            color: css::BLACK.with_alpha(0.5),
+        },
+        Transform::from_xyz(0., 120., 0.),
+    ));
+
+    cmds.spawn((
+        SdfText::new("0", atlas),
+        TextFont {
+            font_size: 60.,
+            ..font
+        },
+        Fill(css::SKY_BLUE),
+        Transform::from_xyz(0., -120., 0.),
+        Counter,
+    ));
+}
+
+#[derive(Component)]
+struct Counter;
+
+fn count(mut counters: Query<&mut SdfText, With<Counter>>, time: Res<Time>) {
+    for mut text in &mut counters {
+        text.text = format!("{:.1} seconds", time.elapsed_secs());
+    }
+}
@@ -0,0 +1,106 @@
+use bevy::{
+    asset::RenderAssetUsages,
+    image::{ImageAddressMode, ImageSampler, ImageSamplerDescriptor},
+    prelude::*,
+    render::render_resource::{Extent3d, ShaderType, TextureDimension, TextureFormat},
+};
+use cuttle::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, CuttlePlugin, image_fill))
+        .add_systems(Startup, spawn)
+        .run();
+}
+
+fn spawn(mut cmds: Commands, mut images: ResMut<Assets<Image>>) {
+    cmds.spawn(Camera2d);
+    let checkers = images.add(checkers());
+
+    cmds.spawn((
+        Sdf,
+        Transform::from_xyz(-200., 0., 0.),
+        Circle(150.),
+        ImageFill {
+            image: checkers.clone(),
+            size: Vec2::splat(100.),
+        },
+    ));
+    cmds.spawn((
+        Sdf,
+        Transform::from_xyz(200., 0., 0.),
+        Star {
+            radius: 180.,
+            points: 5,
+            sharpness: 2.5,
+        },
+        ImageFill {
+            image: checkers,
+            size: Vec2::splat(40.),
+        },
+    ));
+}
+
+/// Repeats the image every `size` units.
+fn image_fill(app: &mut App) {
+    app.cuttle_config::<Sdf>()
+        .component::<ImageFill>()
+        .snippet(stringify!(
+            fn image_fill(input: ImageFillData) {
+                color = cuttle_texture(input.texture, position / input.size);
+            }
+        ));
+}
+
+#[derive(Clone, Debug, Component, Reflect, Cuttle)]
+#[cuttle(sort(SdfOrder::Color))]
+#[cuttle(render_texture(ImageFillData))]
+struct ImageFill {
+    image: Handle<Image>,
+    size: Vec2,
+}
+
+impl CuttleTexture for ImageFill {
+    fn texture(&self) -> &Handle<Image> {
+        &self.image
+    }
+}
+
+#[derive(Clone, Debug, Default, ShaderType, Reflect)]
+struct ImageFillData {
+    size: Vec2,
+    texture: u32,
+}
+
+impl From<(&ImageFill, TextureSlot)> for ImageFillData {
+    fn from((fill, slot): (&ImageFill, TextureSlot)) -> Self {
+        Self {
+            size: fill.size,
+            texture: *slot,
+        }
+    }
+}
+
+fn checkers() -> Image {
+    let colors = [[255, 140, 0, 255], [40, 40, 60, 255]];
+    let data = (0..4 * 4)
+        .flat_map(|i| colors[(i % 4 + i / 4) % 2])
+        .collect();
+    let mut image = Image::new(
+        Extent3d {
+            width: 4,
+            height: 4,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+        RenderAssetUsages::RENDER_WORLD,
+    );
+    image.sampler = ImageSampler::Descriptor(ImageSamplerDescriptor {
+        address_mode_u: ImageAddressMode::Repeat,
+        address_mode_v: ImageAddressMode::Repeat,
+        ..ImageSamplerDescriptor::nearest()
+    });
+    image
+}